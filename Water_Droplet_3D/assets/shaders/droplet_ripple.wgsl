@@ -0,0 +1,68 @@
+// Ripples travelling over the droplet's surface.
+// Only the vertex stage is replaced; shading is still the StandardMaterial's, so transmission and IOR keep working.
+#import bevy_pbr::{
+    mesh_functions,
+    forward_io::{Vertex, VertexOutput},
+    view_transformations::position_world_to_clip,
+}
+
+struct DropletRipple {
+    time: f32,
+    amplitude: f32,
+}
+
+@group(2) @binding(100) var<uniform> ripple: DropletRipple;
+
+// Height (xyz: gradient, w: height) of one sine wave travelling along `direction` over the unit sphere
+fn wave(position: vec3<f32>, direction: vec3<f32>, wave_number: f32, speed: f32) -> vec4<f32> {
+    let phase = dot(position, direction) * wave_number - ripple.time * speed;
+    return vec4<f32>(cos(phase) * wave_number * direction, sin(phase));
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Same rates as the old scaling wobble, now as waves crossing the surface
+    let waves = wave(vertex.position, normalize(vec3<f32>(0.8, 0.6, 0.0)), 7.0, 5.0)
+        + wave(vertex.position, normalize(vec3<f32>(-0.3, 0.5, 0.8)), 9.0, 4.3)
+        + wave(vertex.position, normalize(vec3<f32>(0.2, -0.7, -0.7)), 5.0, 3.5);
+    let scale = ripple.amplitude / 3.0;
+
+    // Push the surface in and out along the normal, and tilt the normal by the waves' slope along the surface
+    let position = vertex.position + vertex.normal * waves.w * scale;
+    let gradient = waves.xyz * scale;
+    let slope = gradient - vertex.normal * dot(gradient, vertex.normal);
+    let normal = normalize(vertex.normal - slope);
+
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4<f32>(position, 1.0));
+    out.position = position_world_to_clip(out.world_position.xyz);
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(normal, vertex.instance_index);
+
+#ifdef VERTEX_UVS_A
+    out.uv = vertex.uv;
+#endif
+#ifdef VERTEX_UVS_B
+    out.uv_b = vertex.uv_b;
+#endif
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(
+        world_from_local,
+        vertex.tangent,
+        vertex.instance_index
+    );
+#endif
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex.instance_index;
+#endif
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex.instance_index, world_from_local[3]);
+#endif
+
+    return out;
+}
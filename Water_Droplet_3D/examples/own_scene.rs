@@ -0,0 +1,36 @@
+// The droplet on its own, dropped onto a scene built here rather than the sandbox's: `cargo run --example own_scene`
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use water_droplet_renderer::WaterDropletPlugin;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(WaterDropletPlugin { seed: Some(7), ..default() })
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(3.0, 2.0, 4.0).looking_at(Vec3::new(0.0, 0.5, 0.0), Vec3::Y),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight { illuminance: 10_000.0, shadows_enabled: true, ..default() },
+        transform: Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -1.0, 0.4, 0.0)),
+        ..default()
+    });
+
+    // A slate table top for the droplet to land on
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(2.0, 0.1, 2.0)),
+            material: materials.add(Color::srgb(0.25, 0.27, 0.3)),
+            transform: Transform::from_xyz(0.0, -0.05, 0.0),
+            ..default()
+        },
+        Collider::cuboid(1.0, 0.05, 1.0),
+    ));
+}
@@ -0,0 +1,473 @@
+use bevy::asset::LoadState;
+use bevy::audio::{SpatialScale, Volume};
+use bevy::prelude::*;
+use bevy::time::Real;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::rain::RainSettings;
+use crate::secondary_splash::ParticleSplashEvent;
+use crate::{SplashEvent, SplashParticle, REFERENCE_IMPACT_SPEED};
+
+// A reference-speed impact plays at this volume; harder hits get louder up to `MAX_SPLASH_VOLUME`
+const REFERENCE_SPLASH_VOLUME: f32 = 0.6;
+const MAX_SPLASH_VOLUME: f32 = 1.0;
+// Each splash plays up to this much faster or slower (and so higher or lower), so repeated drops don't sound identical
+const PITCH_VARIATION: f32 = 0.08;
+// A secondary splash reuses the splash sample, much quieter and sped up into a plink
+const PLINK_VOLUME_SHARE: f32 = 0.15;
+const PLINK_SPEED: f32 = 1.8;
+// The rain loop at the default rain rate, and the loudest heavier rain can make it
+const RAIN_LOOP_VOLUME: f32 = 0.35;
+const MAX_RAIN_LOOP_VOLUME: f32 = 0.7;
+const RAIN_FADE_SECONDS: f32 = 1.0;
+// Distance (m) between the camera's ears; only the direction matters for panning
+const EAR_GAP: f32 = 0.4;
+// Sounds are positioned in units of 5 m, so splashes at the default camera distance play at full volume
+// and only fade once they're further off
+const SPATIAL_SCALE: f32 = 0.2;
+// Particle landings this close together (s) are heard as one patter
+const PATTER_WINDOW: f32 = 0.05;
+// Fewer landings than this in a window make no sound, so single stragglers stay quiet
+const MIN_PATTER_HITS: u32 = 3;
+// This many landings in a window play the patter at full volume
+const FULL_PATTER_HITS: u32 = 30;
+const MAX_PATTER_VOLUME: f32 = 0.5;
+// Master volume moves in steps of a tenth
+const VOLUME_STEPS: f32 = 10.0;
+// How long the volume stays on screen after it changes (s)
+const VOLUME_OVERLAY_SECONDS: f32 = 1.5;
+
+// Master volume for everything the app plays, applied through `GlobalVolume` and to sounds already playing.
+// Nothing resets it, so it carries over R and scene changes.
+#[derive(Resource)]
+pub struct AudioSettings {
+    // From 0.0 to 1.0
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { volume: 1.0, muted: false }
+    }
+}
+
+impl AudioSettings {
+    // What sounds are actually scaled by
+    pub fn level(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    // Moves the volume up or down by whole steps, staying within 0-100%
+    pub fn step(&mut self, steps: f32) {
+        self.volume = ((self.volume * VOLUME_STEPS).round() + steps).clamp(0.0, VOLUME_STEPS) / VOLUME_STEPS;
+    }
+}
+
+// Briefly shows the master volume when it changes
+#[derive(Component)]
+pub struct VolumeOverlay;
+
+#[derive(Resource)]
+pub struct SplashSound {
+    handle: Handle<AudioSource>,
+    patter: Handle<AudioSource>,
+    rain: Handle<AudioSource>,
+    // Whether secondary splashes plink
+    pub plinks: bool,
+}
+
+// Splash particle landings gathered over one short window, played as a single patter when it closes
+#[derive(Resource, Default)]
+pub struct PatterWindow {
+    hits: u32,
+    // Time since the first landing; `None` while no window is open
+    elapsed: Option<f32>,
+}
+
+impl PatterWindow {
+    // Opens a window if none is open
+    pub fn add_hits(&mut self, hits: u32) {
+        if hits > 0 {
+            self.hits += hits;
+            self.elapsed.get_or_insert(0.0);
+        }
+    }
+
+    // Once the open window has run its course, returns how many landings it gathered and starts over
+    pub fn tick(&mut self, delta: f32) -> Option<u32> {
+        let elapsed = self.elapsed.as_mut()?;
+        *elapsed += delta;
+        if *elapsed < PATTER_WINDOW {
+            return None;
+        }
+        self.elapsed = None;
+        Some(std::mem::take(&mut self.hits))
+    }
+}
+
+// The looping rain track while it's playing. Its `AudioSink` lives on `entity`, which is only kept around
+// while the loop is audible, so there's never more than one and nothing is left over once the rain stops.
+#[derive(Resource, Default)]
+pub struct RainLoop {
+    entity: Option<Entity>,
+    // From 0.0 (silent) to 1.0 (fully faded in)
+    fade: f32,
+}
+
+// Quiet for a few landings, rising to full volume at `FULL_PATTER_HITS`; `None` for too few to hear
+pub fn patter_volume(hits: u32) -> Option<f32> {
+    (hits >= MIN_PATTER_HITS).then(|| MAX_PATTER_VOLUME * (hits as f32 / FULL_PATTER_HITS as f32).min(1.0))
+}
+
+pub fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SplashSound {
+        handle: asset_server.load("sounds/splash.wav"),
+        patter: asset_server.load("sounds/patter.wav"),
+        rain: asset_server.load("sounds/rain.wav"),
+        plinks: true,
+    });
+}
+
+pub fn setup_volume_overlay(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 24.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        Visibility::Hidden,
+        VolumeOverlay,
+    ));
+}
+
+/// Goes on the camera, so splashes pan with the view
+pub fn listener() -> SpatialListener {
+    SpatialListener::new(EAR_GAP)
+}
+
+// M mutes and unmutes everything; Shift+M switches the secondary splash plinks off and on.
+// Shift+- and Shift+= step the master volume (plain -/= change gravity), unmuting if it was muted.
+pub fn control_audio(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut sound: ResMut<SplashSound>,
+    mut settings: ResMut<AudioSettings>,
+) {
+    if bindings.just_pressed(Action::Plinks, &keys) {
+        sound.plinks = !sound.plinks;
+        info!("Plinks {}", if sound.plinks { "on" } else { "off" });
+    }
+    if bindings.just_pressed(Action::Mute, &keys) {
+        settings.muted = !settings.muted;
+        info!("Sound {}", if settings.muted { "muted" } else { "on" });
+    }
+
+    let steps = match (
+        bindings.just_pressed(Action::VolumeDown, &keys),
+        bindings.just_pressed(Action::VolumeUp, &keys),
+    ) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => return,
+    };
+    settings.step(steps);
+    settings.muted = false;
+    info!("Volume: {:.0}%", settings.volume * 100.0);
+}
+
+// Sets the volume new sounds start at, and rescales the ones already playing so muting silences them at once
+pub fn apply_audio_settings(
+    settings: Res<AudioSettings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    sinks: Query<(&AudioSink, &PlaybackSettings)>,
+    spatial_sinks: Query<(&SpatialAudioSink, &PlaybackSettings)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let level = settings.level();
+    global_volume.volume = Volume::new(level);
+    for (sink, playback) in sinks.iter() {
+        sink.set_volume(playback.volume.get() * level);
+    }
+    for (sink, playback) in spatial_sinks.iter() {
+        sink.set_volume(playback.volume.get() * level);
+    }
+}
+
+pub fn show_volume(
+    time: Res<Time<Real>>,
+    settings: Res<AudioSettings>,
+    mut remaining: Local<f32>,
+    mut overlay: Query<(&mut Text, &mut Visibility), With<VolumeOverlay>>,
+) {
+    let Ok((mut text, mut visibility)) = overlay.get_single_mut() else { return };
+    if settings.is_changed() && !settings.is_added() {
+        text.sections[0].value = if settings.muted {
+            "Muted".to_string()
+        } else {
+            format!("Volume {:.0}%", settings.volume * 100.0)
+        };
+        *visibility = Visibility::Inherited;
+        *remaining = VOLUME_OVERLAY_SECONDS;
+    } else if *remaining > 0.0 {
+        *remaining -= time.delta_seconds();
+        if *remaining <= 0.0 {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+// One-shot playback from `position`, panned and faded relative to the camera
+fn spatial_sound(source: &Handle<AudioSource>, position: Vec3, volume: f32, speed: f32) -> impl Bundle {
+    (
+        AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(volume))
+                .with_speed(speed)
+                .with_spatial(true)
+                .with_spatial_scale(SpatialScale::new(SPATIAL_SCALE)),
+        },
+        TransformBundle::from_transform(Transform::from_translation(position)),
+    )
+}
+
+// Not drawn from the simulation's seeded RNG, so sound never changes how a replay plays out
+fn varied_pitch() -> f32 {
+    1.0 + rand::thread_rng().gen_range(-PITCH_VARIATION..=PITCH_VARIATION)
+}
+
+// Plays the splash sound where each splash happens, louder for harder impacts and at a slightly different pitch
+// every time, with a quiet plink for each secondary splash.
+// If the sound file is missing or broken the simulation just carries on silently.
+pub fn play_splash_sound(
+    mut commands: Commands,
+    mut splash_events: EventReader<SplashEvent>,
+    mut particle_splash_events: EventReader<ParticleSplashEvent>,
+    sound: Res<SplashSound>,
+    settings: Res<AudioSettings>,
+    asset_server: Res<AssetServer>,
+    mut warned: Local<bool>,
+) {
+    if settings.muted {
+        splash_events.clear();
+        particle_splash_events.clear();
+        return;
+    }
+
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&sound.handle) {
+        if !*warned {
+            warn!("Splash sound unavailable, running silently: {error}");
+            *warned = true;
+        }
+        splash_events.clear();
+        particle_splash_events.clear();
+        return;
+    }
+
+    for splash in splash_events.read() {
+        let volume =
+            (REFERENCE_SPLASH_VOLUME * splash.impact_speed / REFERENCE_IMPACT_SPEED).min(MAX_SPLASH_VOLUME);
+        commands.spawn(spatial_sound(&sound.handle, splash.position, volume, varied_pitch()));
+    }
+
+    if !sound.plinks {
+        particle_splash_events.clear();
+        return;
+    }
+    for splash in particle_splash_events.read() {
+        let volume = (REFERENCE_SPLASH_VOLUME * PLINK_VOLUME_SHARE * splash.impact_speed / REFERENCE_IMPACT_SPEED)
+            .min(MAX_SPLASH_VOLUME * PLINK_VOLUME_SHARE);
+        commands.spawn(spatial_sound(&sound.handle, splash.position, volume, PLINK_SPEED * varied_pitch()));
+    }
+}
+
+// Counts splash particles landing on anything but each other, and plays one patter per `PATTER_WINDOW`
+// scaled by how many landed, rather than a clip for every particle
+#[allow(clippy::too_many_arguments)]
+pub fn play_patter_sound(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    particles: Query<(), With<SplashParticle>>,
+    time: Res<Time>,
+    sound: Res<SplashSound>,
+    settings: Res<AudioSettings>,
+    asset_server: Res<AssetServer>,
+    mut window: ResMut<PatterWindow>,
+    mut warned: Local<bool>,
+) {
+    if settings.muted {
+        collision_events.clear();
+        *window = PatterWindow::default();
+        return;
+    }
+
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&sound.patter) {
+        if !*warned {
+            warn!("Patter sound unavailable, running without it: {error}");
+            *warned = true;
+        }
+        collision_events.clear();
+        return;
+    }
+
+    let mut landed: Vec<Entity> = Vec::new();
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+        for (particle, other) in [(*e1, *e2), (*e2, *e1)] {
+            if particles.contains(particle) && !particles.contains(other) && !landed.contains(&particle) {
+                landed.push(particle);
+            }
+        }
+    }
+    window.add_hits(landed.len() as u32);
+
+    let Some(volume) = window.tick(time.delta_seconds()).and_then(patter_volume) else { return };
+    commands.spawn(AudioBundle {
+        source: sound.patter.clone(),
+        settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+    });
+}
+
+// Fades the rain loop in over `RAIN_FADE_SECONDS` while rain is on, and out again once it stops or the sound is muted.
+// Toggling rain mid-fade just turns the fade around on the same sink rather than starting another.
+// It goes by the wall clock, so pausing or slowing the simulation doesn't hold up the fade.
+pub fn fade_rain_loop(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    rain: Res<RainSettings>,
+    sound: Res<SplashSound>,
+    settings: Res<AudioSettings>,
+    mut rain_loop: ResMut<RainLoop>,
+    sinks: Query<&AudioSink>,
+) {
+    let target = if rain.enabled && !settings.muted { 1.0 } else { 0.0 };
+    let step = time.delta_seconds() / RAIN_FADE_SECONDS;
+    rain_loop.fade = if target > rain_loop.fade {
+        (rain_loop.fade + step).min(target)
+    } else {
+        (rain_loop.fade - step).max(target)
+    };
+
+    if rain_loop.fade <= 0.0 {
+        if let Some(entity) = rain_loop.entity.take() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let volume = (RAIN_LOOP_VOLUME * rain.intensity()).min(MAX_RAIN_LOOP_VOLUME) * rain_loop.fade;
+    match rain_loop.entity {
+        None => {
+            let entity = commands
+                .spawn(AudioBundle {
+                    source: sound.rain.clone(),
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new(volume)),
+                })
+                .id();
+            rain_loop.entity = Some(entity);
+        }
+        // The sink turns up once the track has loaded and started
+        Some(entity) => {
+            // Set directly on the sink, so the master volume has to be applied here too
+            if let Ok(sink) = sinks.get(entity) {
+                sink.set_volume(volume * settings.level());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rain;
+
+    #[test]
+    fn near_simultaneous_landings_make_one_patter() {
+        let mut window = PatterWindow::default();
+        let mut played = Vec::new();
+        // Twenty particles landing over 40ms, one frame every 10ms, then a quiet stretch
+        for frame in 0..10 {
+            window.add_hits(if frame < 4 { 5 } else { 0 });
+            played.extend(window.tick(0.01));
+        }
+        assert_eq!(played, vec![20]);
+
+        let volume = |hits| patter_volume(hits).unwrap_or(0.0);
+        assert_eq!(volume(1), 0.0, "a lone landing should stay silent");
+        assert!(volume(20) > volume(5));
+        assert_eq!(volume(200), volume(1000), "the volume should top out");
+    }
+
+    #[test]
+    fn rain_loop_fades_with_one_sink_and_leaves_nothing_behind() {
+        use bevy::time::TimeUpdateStrategy;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<AudioSource>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+            .init_resource::<rain::RainSettings>()
+            .init_resource::<RainLoop>()
+            .init_resource::<AudioSettings>()
+            .add_systems(Startup, setup_audio)
+            .add_systems(Update, fade_rain_loop);
+        let set_rain = |app: &mut App, enabled| app.world_mut().resource_mut::<rain::RainSettings>().enabled = enabled;
+        let loops = |app: &mut App| app.world_mut().query::<&PlaybackSettings>().iter(app.world()).count();
+
+        // Flicking the rain on and off mid-fade keeps to the one loop
+        set_rain(&mut app, true);
+        for enabled in [true, true, false, true, false, true] {
+            set_rain(&mut app, enabled);
+            app.update();
+            assert!(loops(&mut app) <= 1);
+        }
+        assert_eq!(loops(&mut app), 1);
+
+        // Once the rain stops, the loop fades out and is gone
+        set_rain(&mut app, false);
+        for _ in 0..15 {
+            app.update();
+        }
+        assert_eq!(loops(&mut app), 0);
+    }
+
+    #[test]
+    fn master_volume_steps_in_tenths_and_mute_silences_it() {
+        let mut settings = AudioSettings::default();
+        settings.step(1.0);
+        assert_eq!(settings.volume, 1.0, "the volume should top out at 100%");
+        for _ in 0..3 {
+            settings.step(-1.0);
+        }
+        assert!((settings.volume - 0.7).abs() < 1e-6);
+        for _ in 0..20 {
+            settings.step(-1.0);
+        }
+        assert_eq!(settings.volume, 0.0);
+
+        settings.step(5.0);
+        settings.muted = true;
+        assert_eq!(settings.level(), 0.0);
+        settings.muted = false;
+        assert_eq!(settings.level(), 0.5);
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::core_pipeline::bloom::{BloomCompositeMode, BloomPrefilterSettings, BloomSettings};
+use bevy::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+
+// Only the HDR glints brighter than plain white bloom, so the sky and the floor don't glow
+const BLOOM_THRESHOLD: f32 = 1.0;
+const BLOOM_THRESHOLD_SOFTNESS: f32 = 0.3;
+
+#[derive(Resource)]
+pub struct BloomConfig {
+    pub enabled: bool,
+    pub intensity: f32,
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        Self { enabled: true, intensity: 0.15 }
+    }
+}
+
+impl BloomConfig {
+    fn settings(&self) -> BloomSettings {
+        BloomSettings {
+            intensity: self.intensity,
+            prefilter_settings: BloomPrefilterSettings {
+                threshold: BLOOM_THRESHOLD,
+                threshold_softness: BLOOM_THRESHOLD_SOFTNESS,
+            },
+            // A threshold only makes sense when the bloom is added on top
+            composite_mode: BloomCompositeMode::Additive,
+            ..BloomSettings::NATURAL
+        }
+    }
+}
+
+// O switches the bloom off and on, to compare the two
+pub fn toggle_bloom(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut config: ResMut<BloomConfig>,
+) {
+    if bindings.just_pressed(Action::Bloom, &keys) {
+        config.enabled = !config.enabled;
+        info!("Bloom {}", if config.enabled { "on" } else { "off" });
+    }
+}
+
+// Keeps the camera's bloom in line with the config (including at startup)
+pub fn apply_bloom(
+    mut commands: Commands,
+    config: Res<BloomConfig>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    for camera in cameras.iter() {
+        if config.enabled {
+            commands.entity(camera).insert(config.settings());
+        } else {
+            commands.entity(camera).remove::<BloomSettings>();
+        }
+    }
+}
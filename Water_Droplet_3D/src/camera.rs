@@ -0,0 +1,293 @@
+use bevy::prelude::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::{HasSplashed, PrimaryDroplet, SplashEvent};
+
+// Bookmarks go in slots 1 to 9, on the numpad digits by default
+const BOOKMARK_SLOTS: u8 = 9;
+const BOOKMARK_TRANSITION_SECONDS: f32 = 0.5;
+// How quickly the follow camera's focus closes on the droplet: the gap shrinks by e each 1/rate seconds
+const FOLLOW_RATE: f32 = 4.0;
+
+// A saved camera framing
+#[derive(Clone, Copy)]
+struct CameraView {
+    focus: Vec3,
+    radius: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl CameraView {
+    fn of(camera: &PanOrbitCamera) -> Self {
+        Self {
+            focus: camera.target_focus,
+            radius: camera.target_radius,
+            yaw: camera.target_yaw,
+            pitch: camera.target_pitch,
+        }
+    }
+
+    // Same rotation PanOrbitCamera builds its transform from
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(-self.pitch)
+    }
+
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        let (yaw, negative_pitch, _) = self.rotation().slerp(to.rotation(), t).to_euler(EulerRot::YXZ);
+        Self {
+            focus: self.focus.lerp(to.focus, t),
+            radius: self.radius.lerp(to.radius, t),
+            yaw,
+            pitch: -negative_pitch,
+        }
+    }
+
+    fn apply(&self, camera: &mut PanOrbitCamera) {
+        // Set current and target values together so PanOrbitCamera's own smoothing doesn't lag behind
+        camera.focus = self.focus;
+        camera.target_focus = self.focus;
+        camera.radius = Some(self.radius);
+        camera.target_radius = self.radius;
+        camera.yaw = Some(self.yaw);
+        camera.target_yaw = self.yaw;
+        camera.pitch = Some(self.pitch);
+        camera.target_pitch = self.pitch;
+        camera.force_update = true;
+    }
+}
+
+struct CameraTransition {
+    from: CameraView,
+    to: CameraView,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct CameraBookmarks {
+    slots: [Option<CameraView>; BOOKMARK_SLOTS as usize],
+    transition: Option<CameraTransition>,
+}
+
+// Shift+1..9 saves the current view, 1..9 flies back to it
+pub fn camera_bookmarks(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    cameras: Query<&PanOrbitCamera>,
+) {
+    let Ok(camera) = cameras.get_single() else { return };
+
+    for number in 1..=BOOKMARK_SLOTS {
+        let slot = usize::from(number - 1);
+        if bindings.just_pressed(Action::SaveBookmark(number), &keys) {
+            bookmarks.slots[slot] = Some(CameraView::of(camera));
+            info!("Saved camera view {number}");
+        } else if bindings.just_pressed(Action::FlyToBookmark(number), &keys) {
+            let Some(to) = bookmarks.slots[slot] else { continue };
+            bookmarks.transition = Some(CameraTransition {
+                from: CameraView::of(camera),
+                to,
+                elapsed: 0.0,
+            });
+        }
+    }
+}
+
+pub fn animate_camera_transition(
+    time: Res<Time<Real>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+) {
+    let Some(transition) = bookmarks.transition.as_mut() else { return };
+    let Ok(mut camera) = cameras.get_single_mut() else { return };
+
+    transition.elapsed += time.delta_seconds();
+    let t = (transition.elapsed / BOOKMARK_TRANSITION_SECONDS).min(1.0);
+    // Ease in and out so the camera doesn't start or stop with a jolt
+    let eased = t * t * (3.0 - 2.0 * t);
+    transition.from.interpolate(&transition.to, eased).apply(&mut camera);
+
+    if t >= 1.0 {
+        bookmarks.transition = None;
+    }
+}
+
+// Keeps the camera's focus on the droplet while it falls, then on the spot it splashed.
+// Orbiting and zooming still work as usual; panning away just drifts back.
+#[derive(Resource, Default)]
+pub struct CameraFollow {
+    pub enabled: bool,
+    impact: Option<Vec3>,
+}
+
+// F turns following on and off; off leaves the camera framed wherever it got to
+pub fn toggle_follow(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut follow: ResMut<CameraFollow>,
+) {
+    if bindings.just_pressed(Action::FollowCamera, &keys) {
+        follow.enabled = !follow.enabled;
+        info!("Camera follow {}", if follow.enabled { "on" } else { "off" });
+    }
+}
+
+pub fn follow_droplet(
+    time: Res<Time<Real>>,
+    mut follow: ResMut<CameraFollow>,
+    mut splash_events: EventReader<SplashEvent>,
+    bookmarks: Res<CameraBookmarks>,
+    droplets: Query<(Entity, &Transform, Has<HasSplashed>), With<PrimaryDroplet>>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+) {
+    let Ok((droplet, transform, splashed)) = droplets.get_single() else { return };
+    if let Some(splash) = splash_events.read().filter(|splash| splash.droplet == droplet).last() {
+        follow.impact = Some(splash.position);
+    }
+    // Back in the air after a reset
+    if !splashed {
+        follow.impact = None;
+    }
+
+    // A bookmark flight has the camera until it lands
+    if !follow.enabled || bookmarks.transition.is_some() {
+        return;
+    }
+    let Ok(mut camera) = cameras.get_single_mut() else { return };
+
+    let target = follow.impact.unwrap_or(transform.translation);
+    let t = 1.0 - (-FOLLOW_RATE * time.delta_seconds()).exp();
+    camera.target_focus = camera.target_focus.lerp(target, t);
+}
+
+// How F4's turntable circles the droplet, from `turntable` in scene.ron
+#[derive(Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TurntableSettings {
+    // One full turn takes this long, so a recording of it loops
+    pub seconds_per_turn: f32,
+    // Distance (m) from the droplet along the floor
+    pub radius: f32,
+    // Height (m) above the droplet
+    pub height: f32,
+}
+
+impl Default for TurntableSettings {
+    fn default() -> Self {
+        // Where the camera starts out
+        Self { seconds_per_turn: 12.0, radius: 5.0, height: 1.5 }
+    }
+}
+
+// While it's on, the turntable has the camera and the mouse doesn't
+#[derive(Resource, Default)]
+pub struct Turntable {
+    pub enabled: bool,
+    yaw: f32,
+}
+
+pub fn turntable_off(turntable: Res<Turntable>) -> bool {
+    !turntable.enabled
+}
+
+// F4 starts the turntable from wherever the camera is looking from, and stops it where it's got to
+pub fn toggle_turntable(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut turntable: ResMut<Turntable>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+) {
+    if !bindings.just_pressed(Action::Turntable, &keys) {
+        return;
+    }
+    turntable.enabled = !turntable.enabled;
+    for mut camera in cameras.iter_mut() {
+        camera.enabled = !turntable.enabled;
+        turntable.yaw = camera.target_yaw;
+    }
+    info!("Turntable {}", if turntable.enabled { "on" } else { "off" });
+}
+
+// Turns with the game clock, so a recording's fixed frame time gives exactly `seconds_per_turn` of frames per turn.
+// Current and target values are set together, so PanOrbitCamera picks up from here without a jump when it's off.
+pub fn turn_turntable(
+    time: Res<Time>,
+    settings: Res<TurntableSettings>,
+    mut turntable: ResMut<Turntable>,
+    droplets: Query<&Transform, With<PrimaryDroplet>>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+) {
+    if !turntable.enabled {
+        return;
+    }
+    let Ok(mut camera) = cameras.get_single_mut() else { return };
+
+    turntable.yaw = (turntable.yaw + TAU * time.delta_seconds() / settings.seconds_per_turn).rem_euclid(TAU);
+    let focus = droplets.get_single().map_or(camera.target_focus, |droplet| droplet.translation);
+    let view = CameraView {
+        focus,
+        radius: Vec2::new(settings.radius, settings.height).length(),
+        yaw: turntable.yaw,
+        pitch: settings.height.atan2(settings.radius),
+    };
+    view.apply(&mut camera);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_turntable_circles_the_droplet_once_per_turn_and_hands_the_camera_back_where_it_got_to() {
+        use bevy::time::TimeUpdateStrategy;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(0.1)))
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Turntable>()
+            .insert_resource(TurntableSettings { seconds_per_turn: 1.0, radius: 4.0, height: 3.0 })
+            .add_systems(Update, (toggle_turntable, turn_turntable).chain());
+        app.world_mut().spawn((PrimaryDroplet, Transform::from_xyz(1.0, 0.5, 0.0)));
+        let camera = app.world_mut().spawn(PanOrbitCamera { target_yaw: 0.3, ..default() }).id();
+        let orbit = |app: &App| *app.world().get::<PanOrbitCamera>(camera).unwrap();
+        let press_f4 = |app: &mut App| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.release(KeyCode::F4);
+            keys.clear();
+            keys.press(KeyCode::F4);
+        };
+
+        press_f4(&mut app);
+        app.update();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+        let start = orbit(&app);
+        assert!(!start.enabled);
+        assert_eq!(start.target_focus, Vec3::new(1.0, 0.5, 0.0));
+        assert_eq!(start.target_radius, 5.0);
+        assert!((start.target_pitch - 3.0_f32.atan2(4.0)).abs() < 1e-6);
+
+        let mut yaws = Vec::new();
+        for _ in 0..10 {
+            app.update();
+            yaws.push(orbit(&app).target_yaw);
+        }
+        // A tenth of a turn each frame, and back round to where it started after ten
+        assert!((yaws[0] - start.target_yaw - TAU / 10.0).abs() < 1e-4, "{yaws:?}");
+        assert!((yaws[9] - start.target_yaw).abs() < 1e-4, "{yaws:?}");
+
+        press_f4(&mut app);
+        app.update();
+        let handed_back = orbit(&app);
+        assert!(handed_back.enabled);
+        assert_eq!(handed_back.yaw, Some(handed_back.target_yaw));
+        assert_eq!(handed_back.radius, Some(handed_back.target_radius));
+        assert_eq!(handed_back.focus, handed_back.target_focus);
+    }
+}
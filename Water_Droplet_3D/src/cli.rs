@@ -0,0 +1,74 @@
+//! The command line options.
+
+use bevy::prelude::*;
+use bevy::window::{WindowMode, WindowResolution};
+use clap::Parser;
+use std::path::PathBuf;
+
+use crate::scene_config::SceneConfig;
+
+/// Options for a single run. The scene ones override `assets/scene.ron`, which overrides the built-in scene.
+#[derive(Parser, Resource, Debug, Clone)]
+#[command(about = "Water droplet splash simulation")]
+pub struct Cli {
+    /// Window width in logical pixels
+    #[arg(long, default_value_t = 1280.0)]
+    pub width: f32,
+    /// Window height in logical pixels
+    #[arg(long, default_value_t = 720.0)]
+    pub height: f32,
+    /// Start in borderless fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Seed for every random number, to replay a run [default: random]
+    #[arg(long, env = "DROPLET_SEED")]
+    pub seed: Option<u64>,
+    /// Height the droplet is dropped from, in metres [default: scene.ron, else 5]
+    #[arg(long)]
+    pub spawn_height: Option<f32>,
+    /// Droplet radius in metres, 0.2 to 1.5 [default: scene.ron, else 0.5]
+    #[arg(long)]
+    pub droplet_radius: Option<f32>,
+    /// Particles thrown by a splash, 1 to 100 [default: scene.ron, else 20]
+    #[arg(long)]
+    pub particles_per_splash: Option<usize>,
+    /// Turn off the sun's shadows
+    #[arg(long)]
+    pub no_shadows: bool,
+    /// Run the simulation without a window, print a summary and exit
+    #[arg(long)]
+    pub headless: bool,
+    /// Physics steps to run headless
+    #[arg(long, default_value_t = 600, requires = "headless")]
+    pub steps: u32,
+    /// Log every droplet on every physics step, and every splash, to a CSV file
+    #[arg(long, value_name = "CSV")]
+    pub log_trajectory: Option<PathBuf>,
+}
+
+impl Cli {
+    /// The primary window at the size and mode asked for
+    pub fn window(&self) -> Window {
+        Window {
+            resolution: WindowResolution::new(self.width, self.height),
+            mode: if self.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed },
+            ..default()
+        }
+    }
+
+    /// Puts whatever was given on the command line over the scene file's values
+    pub fn apply(&self, config: &mut SceneConfig) {
+        if let Some(height) = self.spawn_height {
+            config.droplet_position.1 = height;
+        }
+        if let Some(radius) = self.droplet_radius {
+            config.droplet_radius = radius;
+        }
+        if let Some(count) = self.particles_per_splash {
+            config.particles.count = count;
+        }
+        if self.no_shadows {
+            config.shadows = false;
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::droplet_color::DropletColor;
+use crate::liquid::CurrentLiquid;
+use crate::tuning::Viscosity;
+use crate::{spawn_droplet, Droplet, DropletAssets, DropletRadius, HasSplashed, ImpactVelocity, PrimaryDroplet};
+
+// Both droplets have to be at least this high (m) for a contact to count as mid-air
+const MIN_MERGE_HEIGHT: f32 = 0.5;
+// ...and both moving at least this fast (m/s), so droplets resting against each other stay apart
+const MIN_MERGE_SPEED: f32 = 0.5;
+
+// Two droplets that run into each other in the air become one droplet with their combined volume,
+// moving with their mass-weighted velocity. Only `Droplet`s merge, never splash particles.
+// The primary droplet is hidden rather than despawned, so R can still bring it back.
+#[allow(clippy::type_complexity)]
+pub fn merge_droplets(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut droplets: Query<
+        (&Transform, &DropletRadius, &ImpactVelocity, &mut Velocity, Has<PrimaryDroplet>, Option<&DropletColor>),
+        (With<Droplet>, Without<HasSplashed>, Without<RigidBodyDisabled>),
+    >,
+    droplet_assets: Res<DropletAssets>,
+    viscosity: Res<Viscosity>,
+    liquid: Res<CurrentLiquid>,
+) {
+    let mut merged: Vec<Entity> = Vec::new();
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+        if merged.contains(e1) || merged.contains(e2) {
+            continue;
+        }
+        let Ok([a, b]) = droplets.get_many([*e1, *e2]) else { continue };
+
+        // The velocity going into the contact, since Rapier has already bounced them apart
+        let airborne = |transform: &Transform, impact_velocity: &ImpactVelocity| {
+            transform.translation.y > MIN_MERGE_HEIGHT && impact_velocity.0.length() > MIN_MERGE_SPEED
+        };
+        if !airborne(a.0, a.2) || !airborne(b.0, b.2) {
+            continue;
+        }
+
+        // Mass goes with volume, and volume with r³
+        let (mass_a, mass_b) = (a.1 .0.powi(3), b.1 .0.powi(3));
+        let total_mass = mass_a + mass_b;
+        let radius = total_mass.cbrt();
+        let position = (a.0.translation * mass_a + b.0.translation * mass_b) / total_mass;
+        let velocity = (a.2 .0 * mass_a + b.2 .0 * mass_b) / total_mass;
+
+        let droplet = spawn_droplet(&mut commands, position, radius, &droplet_assets, &viscosity);
+        commands.entity(droplet).insert(Velocity::linear(velocity));
+        // Dye carries over in proportion to how much of the new droplet came from each
+        if a.5.is_some() || b.5.is_some() {
+            commands.entity(droplet).insert(DropletColor::blend(a.5, b.5, mass_b / total_mass, &liquid));
+        }
+
+        for entity in [*e1, *e2] {
+            merged.push(entity);
+            let Ok((_, _, _, mut velocity, is_primary, _)) = droplets.get_mut(entity) else { continue };
+            if is_primary {
+                *velocity = Velocity::zero();
+                commands.entity(entity).insert((RigidBodyDisabled, Visibility::Hidden));
+            } else {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tuning, SplashParticle};
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    fn merge_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<CollisionEvent>()
+            .insert_resource(DropletAssets {
+                mesh: Handle::default(),
+                material: Handle::default(),
+                surface_materials: vec![Handle::default()],
+            })
+            .init_resource::<tuning::Viscosity>()
+            .init_resource::<CurrentLiquid>()
+            .add_systems(Update, merge_droplets);
+        app
+    }
+
+    fn falling_droplet(app: &mut App, position: Vec3, velocity: Vec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                Droplet,
+                DropletRadius(0.5),
+                Transform::from_translation(position),
+                Velocity::linear(velocity),
+                ImpactVelocity(velocity),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn droplets_colliding_in_the_air_merge_conserving_volume() {
+        let mut app = merge_test_app();
+        let a = falling_droplet(&mut app, Vec3::new(-0.5, 3.0, 0.0), Vec3::new(2.0, -4.0, 0.0));
+        let b = falling_droplet(&mut app, Vec3::new(0.5, 3.0, 0.0), Vec3::new(-2.0, -4.0, 0.0));
+
+        app.world_mut().send_event(CollisionEvent::Started(a, b, CollisionEventFlags::empty()));
+        app.update();
+
+        assert!(app.world().get_entity(a).is_none());
+        assert!(app.world().get_entity(b).is_none());
+        let mut droplets = app.world_mut().query::<(&DropletRadius, &Velocity, &Transform)>();
+        let (radius, velocity, transform) = droplets.single(app.world());
+        assert!((radius.0 - 0.25_f32.cbrt()).abs() < 1e-5);
+        assert!(velocity.linvel.abs_diff_eq(Vec3::new(0.0, -4.0, 0.0), 1e-5));
+        assert!(transform.translation.abs_diff_eq(Vec3::new(0.0, 3.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn droplet_hitting_a_splash_particle_does_not_merge() {
+        let mut app = merge_test_app();
+        let droplet = falling_droplet(&mut app, Vec3::new(0.0, 3.0, 0.0), Vec3::new(0.0, -4.0, 0.0));
+        let particle = app
+            .world_mut()
+            .spawn((
+                SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 },
+                Transform::from_xyz(0.0, 2.5, 0.0),
+                Velocity::linear(Vec3::Y),
+                ImpactVelocity(Vec3::Y),
+            ))
+            .id();
+
+        app.world_mut()
+            .send_event(CollisionEvent::Started(droplet, particle, CollisionEventFlags::empty()));
+        app.update();
+
+        assert!(app.world().get_entity(droplet).is_some());
+        assert!(app.world().get_entity(particle).is_some());
+        assert_eq!(app.world_mut().query::<&Droplet>().iter(app.world()).count(), 1);
+    }
+}
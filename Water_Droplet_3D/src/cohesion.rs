@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier3d::prelude::*;
+
+use crate::SplashParticle;
+use crate::keybindings::{Action, KeyBindings};
+
+// Velocity changes smaller than this (m/s) are dropped, so particles that have settled can still fall asleep
+const MIN_VELOCITY_CHANGE: f32 = 0.01;
+
+// A light SPH-style pull between nearby splash particles, so they gather into blobs like liquid does.
+// Inside `repulsion_radius` they push apart instead, so the blobs don't collapse into one point.
+#[derive(Resource)]
+pub struct CohesionSettings {
+    pub enabled: bool,
+    // Particles further apart than this (m) don't interact
+    pub radius: f32,
+    // Centres closer than this (m) push apart; about two particle radii
+    pub repulsion_radius: f32,
+    // Acceleration (m/s²) at the strongest point of the pull
+    pub strength: f32,
+}
+
+impl Default for CohesionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            radius: 0.5,
+            repulsion_radius: 0.2,
+            strength: 3.0,
+        }
+    }
+}
+
+// Buckets points into a grid of cubes, so finding everything within one cell size of a point
+// only means checking the 27 cells around it
+#[derive(Resource, Default)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<usize>>,
+}
+
+impl SpatialHash {
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    // Refills the grid with `positions`, which are then referred to by index
+    pub fn rebuild(&mut self, cell_size: f32, positions: &[Vec3]) {
+        self.cell_size = cell_size;
+        // Keep the allocations around from frame to frame
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+        for (i, &position) in positions.iter().enumerate() {
+            let cell = self.cell(position);
+            self.cells.entry(cell).or_default().push(i);
+        }
+    }
+
+    // Indices of every point that could be within one cell size of `position` (and some that aren't)
+    pub fn nearby(&self, position: Vec3) -> impl Iterator<Item = usize> + '_ {
+        let center = self.cell(position);
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| IVec3::new(x, y, z))))
+            .filter_map(move |offset| self.cells.get(&(center + offset)))
+            .flatten()
+            .copied()
+    }
+}
+
+// U switches the cohesion off and on
+pub fn toggle_cohesion(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut settings: ResMut<CohesionSettings>,
+) {
+    if bindings.just_pressed(Action::Cohesion, &keys) {
+        settings.enabled = !settings.enabled;
+        info!("Particle cohesion {}", if settings.enabled { "on" } else { "off" });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_cohesion(
+    time: Res<Time>,
+    settings: Res<CohesionSettings>,
+    mut grid: ResMut<SpatialHash>,
+    mut particles: Query<(&Transform, &mut Velocity, &Sleeping), (With<SplashParticle>, Without<RigidBodyDisabled>)>,
+) {
+    if !settings.enabled || settings.radius <= 0.0 {
+        return;
+    }
+
+    let positions: Vec<Vec3> = particles.iter().map(|(transform, _, _)| transform.translation).collect();
+    grid.rebuild(settings.radius, &positions);
+
+    let dt = time.delta_seconds();
+    let attraction_range = (settings.radius - settings.repulsion_radius).max(f32::EPSILON);
+    for (i, (transform, mut velocity, sleeping)) in particles.iter_mut().enumerate() {
+        // Resting particles are left to sleep; the awake ones still pull towards them
+        if sleeping.sleeping {
+            continue;
+        }
+
+        let position = transform.translation;
+        let mut acceleration = Vec3::ZERO;
+        for j in grid.nearby(position) {
+            let offset = positions[j] - position;
+            let distance = offset.length();
+            if j == i || distance >= settings.radius || distance <= f32::EPSILON {
+                continue;
+            }
+            let direction = offset / distance;
+            acceleration += if distance < settings.repulsion_radius {
+                // Stronger the deeper they overlap
+                -direction * settings.strength * (1.0 - distance / settings.repulsion_radius) * 2.0
+            } else {
+                // Rises from nothing at the edge of the repulsion zone and fades out at the interaction radius
+                let t = (distance - settings.repulsion_radius) / attraction_range;
+                direction * settings.strength * 4.0 * t * (1.0 - t)
+            };
+        }
+
+        let change = acceleration * dt;
+        if change.length() > MIN_VELOCITY_CHANGE {
+            velocity.linvel += change;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn spatial_hash_finds_every_pair_brute_force_does() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let positions: Vec<Vec3> = (0..200)
+            .map(|_| Vec3::new(rng.gen_range(-2.0..2.0), rng.gen_range(0.0..1.0), rng.gen_range(-2.0..2.0)))
+            .collect();
+        let radius = 0.4;
+        let mut grid = SpatialHash::default();
+        grid.rebuild(radius, &positions);
+
+        for (i, &position) in positions.iter().enumerate() {
+            let mut expected: Vec<usize> =
+                (0..positions.len()).filter(|&j| positions[j].distance(position) < radius).collect();
+            let mut found: Vec<usize> =
+                grid.nearby(position).filter(|&j| positions[j].distance(position) < radius).collect();
+            expected.sort_unstable();
+            found.sort_unstable();
+            assert_eq!(found, expected, "neighbours of point {i}");
+        }
+    }
+}
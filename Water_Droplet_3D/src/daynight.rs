@@ -0,0 +1,111 @@
+use bevy::core_pipeline::Skybox;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::skybox::SKYBOX_BRIGHTNESS;
+
+pub const NOON_SKY: Color = Color::srgb(0.5, 0.8, 0.9); // Sky Blue
+const DAWN_SKY: Color = Color::srgb(0.95, 0.6, 0.4);
+const DUSK_SKY: Color = Color::srgb(0.8, 0.4, 0.35);
+const NIGHT_SKY: Color = Color::srgb(0.02, 0.03, 0.08);
+
+pub const NOON_AMBIENT: f32 = 500.0;
+const HORIZON_AMBIENT: f32 = 200.0;
+const NIGHT_AMBIENT: f32 = 20.0;
+pub const NOON_ILLUMINANCE: f32 = 10000.0;
+
+// Compass direction the sun travels along, so shadows fall at an angle across the floor
+const SUN_AZIMUTH: f32 = -0.5;
+
+#[derive(Component)]
+pub struct Sun;
+
+#[derive(Resource)]
+pub struct DayNightSettings {
+    // Seconds for a full day at speed 1.0
+    pub day_length: f32,
+    pub speed: f32,
+    pub paused: bool,
+    // 0.0 is sunrise, 0.25 noon, 0.5 sunset, and the second half is night
+    pub time_of_day: f32,
+    // The sky and the sunlight (lux) at noon; the rest of the day is worked out from them
+    pub noon_sky: Color,
+    pub noon_illuminance: f32,
+}
+
+impl Default for DayNightSettings {
+    fn default() -> Self {
+        Self {
+            day_length: 120.0,
+            speed: 1.0,
+            paused: false,
+            // Roughly where the sun used to sit before it moved
+            time_of_day: 1.0 / TAU,
+            noon_sky: NOON_SKY,
+            noon_illuminance: NOON_ILLUMINANCE,
+        }
+    }
+}
+
+// K pauses/resumes the cycle
+pub fn toggle_day_night(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut settings: ResMut<DayNightSettings>,
+) {
+    if bindings.just_pressed(Action::DayNight, &keys) {
+        settings.paused = !settings.paused;
+        info!("Day/night cycle {}", if settings.paused { "paused" } else { "running" });
+    }
+}
+
+pub fn cycle_sun(
+    time: Res<Time>,
+    mut settings: ResMut<DayNightSettings>,
+    mut sun: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+    mut skyboxes: Query<&mut Skybox>,
+    mut clear_color: ResMut<ClearColor>,
+    mut ambient: ResMut<AmbientLight>,
+) {
+    if !settings.paused && settings.day_length > 0.0 {
+        let advance = time.delta_seconds() * settings.speed / settings.day_length;
+        settings.time_of_day = (settings.time_of_day + advance).rem_euclid(1.0);
+    }
+
+    let angle = settings.time_of_day * TAU;
+    // 1 at noon, 0 on the horizon, negative at night
+    let elevation = angle.sin();
+
+    for (mut transform, mut light) in sun.iter_mut() {
+        // Tilting down from the horizon around X sweeps the light east to west overhead
+        transform.rotation = Quat::from_rotation_y(SUN_AZIMUTH) * Quat::from_rotation_x(-angle);
+        light.illuminance = settings.noon_illuminance * elevation.max(0.0);
+    }
+
+    let horizon_sky = if settings.time_of_day < 0.25 || settings.time_of_day > 0.75 {
+        DAWN_SKY
+    } else {
+        DUSK_SKY
+    };
+    let (sky, brightness) = if elevation >= 0.0 {
+        (
+            horizon_sky.mix(&settings.noon_sky, elevation),
+            HORIZON_AMBIENT.lerp(NOON_AMBIENT, elevation),
+        )
+    } else {
+        // Get dark quickly once the sun is down
+        let darkness = (-elevation * 4.0).min(1.0);
+        (
+            horizon_sky.mix(&NIGHT_SKY, darkness),
+            HORIZON_AMBIENT.lerp(NIGHT_AMBIENT, darkness),
+        )
+    };
+
+    clear_color.0 = sky;
+    ambient.brightness = brightness;
+    // The skybox can't be tinted, so it just dims along with the ambient light
+    for mut skybox in skyboxes.iter_mut() {
+        skybox.brightness = SKYBOX_BRIGHTNESS * brightness / NOON_AMBIENT;
+    }
+}
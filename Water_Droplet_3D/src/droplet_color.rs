@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::liquid::{CurrentLiquid, DROPLET_THICKNESS};
+use crate::{Droplet, DropletAssets, DropletMaterial, PrimaryDroplet};
+
+// Colours D cycles new droplets through, as (base, attenuation)
+const PALETTE: [(&str, Color, Color); 3] = [
+    ("red", Color::srgb(1.0, 0.35, 0.35), Color::srgb(0.9, 0.05, 0.05)),
+    ("blue", Color::srgb(0.35, 0.45, 1.0), Color::srgb(0.05, 0.15, 0.9)),
+    ("yellow", Color::srgb(1.0, 0.95, 0.35), Color::srgb(0.9, 0.8, 0.05)),
+];
+// Light passing through a dyed droplet picks up its colour within this distance (m), so even a small one shows it
+const DYED_ATTENUATION_DISTANCE: f32 = 0.5;
+// How far each droplet moves towards the pair's average colour when two touch; 1.0 evens them out at once
+const MIX_SHARE: f32 = 0.5;
+
+// A droplet dyed its own colour. Undyed droplets share the liquid's material; a dyed one gets a copy of it,
+// so the panel and L no longer restyle it.
+#[derive(Component, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DropletColor {
+    pub base: Color,
+    pub attenuation: Color,
+}
+
+impl DropletColor {
+    // What an undyed droplet of `liquid` looks like, for mixing it with a dyed one
+    fn of_liquid(liquid: &CurrentLiquid) -> Self {
+        let material = liquid.0.material(DROPLET_THICKNESS);
+        Self { base: material.base_color, attenuation: material.attenuation_color }
+    }
+
+    fn lerp(self, other: Self, share: f32) -> Self {
+        Self { base: self.base.mix(&other.base, share), attenuation: self.attenuation.mix(&other.attenuation, share) }
+    }
+
+    // `share` of the way from `a` to `b`, with either one left undyed standing in for the plain liquid
+    pub fn blend(a: Option<&Self>, b: Option<&Self>, share: f32, liquid: &CurrentLiquid) -> Self {
+        let plain = Self::of_liquid(liquid);
+        a.copied().unwrap_or(plain).lerp(b.copied().unwrap_or(plain), share)
+    }
+
+    // Both droplets' colours after they touch, each moved `MIX_SHARE` of the way to their average
+    pub fn mix(a: Self, b: Self) -> (Self, Self) {
+        let average = a.lerp(b, 0.5);
+        (a.lerp(average, MIX_SHARE), b.lerp(average, MIX_SHARE))
+    }
+}
+
+// The colour new droplets are dyed, if any
+#[derive(Resource, Default)]
+pub struct DropletDye {
+    selected: Option<usize>,
+}
+
+// D steps the dye through the palette and back to the plain liquid
+pub fn cycle_dye(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut dye: ResMut<DropletDye>,
+) {
+    if !bindings.just_pressed(Action::NextDye, &keys) {
+        return;
+    }
+    dye.selected = match dye.selected {
+        None => Some(0),
+        Some(i) if i + 1 < PALETTE.len() => Some(i + 1),
+        Some(_) => None,
+    };
+    match dye.selected {
+        Some(i) => info!("New droplets are {}", PALETTE[i].0),
+        None => info!("New droplets are the plain liquid"),
+    }
+}
+
+// Dyes each new droplet (the primary one aside) with the selected colour, unless it already has one of its own,
+// like a merged droplet that inherited its parents' colours
+#[allow(clippy::type_complexity)]
+pub fn dye_new_droplets(
+    mut commands: Commands,
+    dye: Res<DropletDye>,
+    droplets: Query<Entity, (Added<Droplet>, Without<DropletColor>, Without<PrimaryDroplet>)>,
+) {
+    let Some(selected) = dye.selected else { return };
+    let (_, base, attenuation) = PALETTE[selected];
+    for entity in droplets.iter() {
+        commands.entity(entity).insert(DropletColor { base, attenuation });
+    }
+}
+
+// Two droplets that touch blend their colours. An undyed droplet joins in with the liquid's own colours.
+pub fn mix_droplet_colors(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    liquid: Res<CurrentLiquid>,
+    mut droplets: Query<Option<&mut DropletColor>, With<Droplet>>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+        let Ok([a, b]) = droplets.get_many_mut([*e1, *e2]) else { continue };
+        // Two plain droplets have nothing to mix
+        if a.is_none() && b.is_none() {
+            continue;
+        }
+
+        let plain = DropletColor::of_liquid(&liquid);
+        let (a_color, b_color) = (a.as_deref().copied().unwrap_or(plain), b.as_deref().copied().unwrap_or(plain));
+        let (mixed_a, mixed_b) = DropletColor::mix(a_color, b_color);
+        for (entity, color, mixed) in [(*e1, a, mixed_a), (*e2, b, mixed_b)] {
+            match color {
+                Some(mut color) => *color = mixed,
+                // The droplet may be despawned this frame, going off the edge or merging
+                None => {
+                    commands.entity(entity).try_insert(mixed);
+                }
+            }
+        }
+    }
+}
+
+// Gives a newly dyed droplet its own material, and carries colour changes over to it
+pub fn apply_droplet_colors(
+    droplet_assets: Res<DropletAssets>,
+    mut materials: ResMut<Assets<DropletMaterial>>,
+    mut droplets: Query<(&DropletColor, &mut Handle<DropletMaterial>), Changed<DropletColor>>,
+) {
+    for (color, mut handle) in droplets.iter_mut() {
+        if droplet_assets.surface_materials.contains(&handle) {
+            let Some(shared) = materials.get(handle.id()).cloned() else { continue };
+            *handle = materials.add(shared);
+        }
+        let Some(material) = materials.get_mut(handle.id()) else { continue };
+        let standard = standard_material(material);
+        standard.base_color = color.base;
+        standard.attenuation_color = color.attenuation;
+        standard.attenuation_distance = DYED_ATTENUATION_DISTANCE;
+    }
+}
+
+#[cfg(not(feature = "cpu_wobble"))]
+fn standard_material(material: &mut DropletMaterial) -> &mut StandardMaterial {
+    &mut material.base
+}
+
+#[cfg(feature = "cpu_wobble")]
+fn standard_material(material: &mut DropletMaterial) -> &mut StandardMaterial {
+    material
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    #[test]
+    fn red_and_blue_droplets_both_turn_purple_when_they_touch() {
+        use DropletColor;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<CollisionEvent>()
+            .init_resource::<CurrentLiquid>()
+            .add_systems(Update, mix_droplet_colors);
+        let dyed = |color: Color| DropletColor { base: color, attenuation: color };
+        let red = app.world_mut().spawn((Droplet, dyed(Color::srgb(1.0, 0.0, 0.0)))).id();
+        let blue = app.world_mut().spawn((Droplet, dyed(Color::srgb(0.0, 0.0, 1.0)))).id();
+        let plain = app.world_mut().spawn(Droplet).id();
+
+        app.world_mut().send_event(CollisionEvent::Started(red, blue, CollisionEventFlags::empty()));
+        app.update();
+
+        let srgb = |entity: Entity| app.world().get::<DropletColor>(entity).unwrap().base.to_srgba();
+        let (reddish, bluish) = (srgb(red), srgb(blue));
+        assert!(reddish.red > reddish.blue && reddish.blue > 0.2, "red droplet went {reddish:?}");
+        assert!(bluish.blue > bluish.red && bluish.red > 0.2, "blue droplet went {bluish:?}");
+
+        // A plain droplet takes some of the dye
+        app.world_mut().send_event(CollisionEvent::Started(plain, red, CollisionEventFlags::empty()));
+        app.update();
+        assert!(app.world().get::<DropletColor>(plain).is_some());
+    }
+}
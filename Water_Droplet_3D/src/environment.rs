@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::daynight::NOON_AMBIENT;
+use crate::skybox::StackedCubemaps;
+
+// Baked from the gradient sky: a sharp map for reflections and a blurred one for diffuse light
+const DIFFUSE_MAP_PATH: &str = "environment_maps/sky_diffuse.png";
+const SPECULAR_MAP_PATH: &str = "environment_maps/sky_specular.png";
+
+// Image-based lighting from the sky, which is what gives the droplet its reflections on the rim
+#[derive(Resource)]
+pub struct EnvironmentSettings {
+    // Brightness of the environment map at noon in cd/m²; the day/night cycle dims it from there
+    pub intensity: f32,
+}
+
+impl Default for EnvironmentSettings {
+    fn default() -> Self {
+        Self { intensity: 1000.0 }
+    }
+}
+
+pub fn setup_environment_map(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut cubemaps: ResMut<StackedCubemaps>,
+    settings: Res<EnvironmentSettings>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    let diffuse_map = cubemaps.load(&asset_server, DIFFUSE_MAP_PATH);
+    let specular_map = cubemaps.load(&asset_server, SPECULAR_MAP_PATH);
+
+    for camera in cameras.iter() {
+        commands.entity(camera).insert(EnvironmentMapLight {
+            diffuse_map: diffuse_map.clone(),
+            specular_map: specular_map.clone(),
+            intensity: settings.intensity,
+        });
+    }
+}
+
+// Follows the tunable intensity, dimmed along with the ambient light as the sun goes down
+pub fn update_environment_intensity(
+    settings: Res<EnvironmentSettings>,
+    ambient: Res<AmbientLight>,
+    mut lights: Query<&mut EnvironmentMapLight>,
+) {
+    let intensity = settings.intensity * ambient.brightness / NOON_AMBIENT;
+    for mut light in lights.iter_mut() {
+        if light.intensity != intensity {
+            light.intensity = intensity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_environment_maps_are_stacked_cubemaps() {
+        use bevy::render::texture::{CompressedImageFormats, ImageSampler, ImageType};
+
+        for name in ["sky_diffuse.png", "sky_specular.png"] {
+            let path = format!("{}/assets/environment_maps/{name}", env!("CARGO_MANIFEST_DIR"));
+            let bytes = std::fs::read(&path).unwrap();
+            let mut image = Image::from_buffer(
+                &bytes,
+                ImageType::Extension("png"),
+                CompressedImageFormats::NONE,
+                true,
+                ImageSampler::Default,
+                bevy::render::render_asset::RenderAssetUsages::default(),
+            )
+            .unwrap();
+            assert_eq!(image.height(), image.width() * 6, "{name}");
+            image.reinterpret_stacked_2d_as_array(6);
+        }
+    }
+}
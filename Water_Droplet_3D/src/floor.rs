@@ -0,0 +1,344 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_rapier3d::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::TAU;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::terrain::TerrainSettings;
+use crate::wetness::FloorWetness;
+use crate::{create_checkerboard_image, SimulationRng, FLOOR_NORMAL_STRENGTH, FLOOR_TEXTURE_SIZE};
+
+// Lattice cells across the texture for each noise octave, with their weights.
+// Every cell count divides the texture evenly, which is what makes the noise tile.
+const NOISE_OCTAVES: [(usize, f32); 3] = [(4, 0.6), (8, 0.3), (16, 0.1)];
+// Width (m) of one checkerboard tile, whatever the floor size, so a bigger floor gets more tiles instead of bigger ones
+const TILE_METERS: f32 = 2.5;
+// Shift+V steps through these floor widths (m)
+const FLOOR_SIZES: [f32; 4] = [10.0, 20.0, 30.0, 40.0];
+// The ramp and the pool are laid out for this size of floor
+const DEFAULT_FLOOR_SIZE: f32 = 20.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FloorPattern {
+    #[default]
+    Checkerboard,
+    Noise,
+    SolidGray,
+}
+
+impl FloorPattern {
+    fn next(self) -> Self {
+        match self {
+            FloorPattern::Checkerboard => FloorPattern::Noise,
+            FloorPattern::Noise => FloorPattern::SolidGray,
+            FloorPattern::SolidGray => FloorPattern::Checkerboard,
+        }
+    }
+
+    fn image(self, seed: u64, tile_pixels: f32) -> Image {
+        match self {
+            FloorPattern::Checkerboard => create_checkerboard_image(tile_pixels),
+            FloorPattern::Noise => create_noise_image(seed),
+            FloorPattern::SolidGray => grayscale_image(|_, _| 200),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CurrentFloorPattern(pub FloorPattern);
+
+// Width and depth of the square floor (m). The mesh, its collider and the tiling are all built from this.
+#[derive(Resource)]
+pub struct FloorSize(pub f32);
+
+impl Default for FloorSize {
+    fn default() -> Self {
+        Self(DEFAULT_FLOOR_SIZE)
+    }
+}
+
+impl FloorSize {
+    // Width of one tile in texture pixels; the one texture always spans the whole floor
+    pub fn tile_pixels(&self) -> f32 {
+        FLOOR_TEXTURE_SIZE as f32 * TILE_METERS / self.0
+    }
+
+    // How far things laid out for the default floor are moved out (or in) from the middle, to stay on this one
+    pub fn layout_scale(&self) -> f32 {
+        self.0 / DEFAULT_FLOOR_SIZE
+    }
+}
+
+// The floor's meshes, rebuilt when the floor is resized
+#[derive(Component)]
+pub struct Floor;
+
+// A flat `size` x `size` plane, with the tangents the tile normal map needs
+pub fn plane_mesh(size: f32) -> Mesh {
+    Plane3d::default().mesh().size(size, size).build().with_generated_tangents().unwrap()
+}
+
+// The textured floor's mesh and its matching collider: the terrain if it's on, or a flat plane
+pub fn floor_shape(size: f32, terrain: &TerrainSettings) -> (Mesh, Collider) {
+    if terrain.enabled {
+        (terrain.mesh().with_generated_tangents().unwrap(), terrain.collider())
+    } else {
+        (plane_mesh(size), Collider::cuboid(size / 2.0, 0.01, size / 2.0)) // Half-extents
+    }
+}
+
+// Tileable multi-octave gradient noise, same size and format as the checkerboard
+pub fn create_noise_image(seed: u64) -> Image {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let octaves: Vec<(usize, f32, Vec<Vec2>)> = NOISE_OCTAVES
+        .iter()
+        .map(|&(cells, weight)| {
+            let gradients = (0..cells * cells)
+                .map(|_| Vec2::from_angle(rng.gen_range(0.0..TAU)))
+                .collect();
+            (cells, weight, gradients)
+        })
+        .collect();
+
+    grayscale_image(|x, y| {
+        let noise: f32 = octaves
+            .iter()
+            .map(|(cells, weight, gradients)| {
+                let cell_size = FLOOR_TEXTURE_SIZE as f32 / *cells as f32;
+                let point = Vec2::new(x as f32, y as f32) / cell_size;
+                weight * gradient_noise(point, *cells, gradients)
+            })
+            .sum();
+        // Noise is roughly in -1..1; keep it a light, fairly flat grey so shadows still read
+        (170.0 + noise * 110.0).clamp(0.0, 255.0) as u8
+    })
+}
+
+// Perlin-style noise on a `cells` x `cells` lattice that wraps around at the edges
+pub fn gradient_noise(point: Vec2, cells: usize, gradients: &[Vec2]) -> f32 {
+    let cell = point.floor();
+    let local = point - cell;
+    let (cx, cy) = (cell.x as usize, cell.y as usize);
+
+    let corner = |dx: usize, dy: usize| {
+        let gradient = gradients[((cy + dy) % cells) * cells + (cx + dx) % cells];
+        gradient.dot(local - Vec2::new(dx as f32, dy as f32))
+    };
+
+    // Smootherstep so the lattice doesn't show through
+    let fade = local * local * local * (local * (local * 6.0 - 15.0) + 10.0);
+    let top = corner(0, 0).lerp(corner(1, 0), fade.x);
+    let bottom = corner(0, 1).lerp(corner(1, 1), fade.x);
+    top.lerp(bottom, fade.y) * std::f32::consts::SQRT_2
+}
+
+fn grayscale_image(shade: impl Fn(usize, usize) -> u8) -> Image {
+    let mut pixels = vec![0; FLOOR_TEXTURE_SIZE * FLOOR_TEXTURE_SIZE * 4];
+    for y in 0..FLOOR_TEXTURE_SIZE {
+        for x in 0..FLOOR_TEXTURE_SIZE {
+            let i = (y * FLOOR_TEXTURE_SIZE + x) * 4;
+            let value = shade(x, y);
+            pixels[i..i + 4].copy_from_slice(&[value, value, value, 255]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: FLOOR_TEXTURE_SIZE as u32,
+            height: FLOOR_TEXTURE_SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+// V cycles the floor between checkerboard, noise and plain grey (Shift+V resizes it instead).
+// The floor texture is repainted in place, so the floor material and any wet patches carry over.
+pub fn cycle_floor_pattern(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut current: ResMut<CurrentFloorPattern>,
+    floor_size: Res<FloorSize>,
+    mut wetness: ResMut<FloorWetness>,
+    mut images: ResMut<Assets<Image>>,
+    rng: Res<SimulationRng>,
+) {
+    if !bindings.just_pressed(Action::FloorPattern, &keys) {
+        return;
+    }
+
+    current.0 = current.0.next();
+    info!("Floor: {:?}", current.0);
+
+    let pattern = current.0.image(rng.seed, floor_size.tile_pixels());
+    if let Some(image) = images.get_mut(wetness.image()) {
+        *image = pattern;
+        wetness.set_dry(image);
+    }
+}
+
+// Width of the bevel around each checkerboard tile, as a share of the tile
+const TILE_BEVEL_SHARE: f32 = 0.1;
+
+// Normal map that raises every checkerboard tile into a slab with bevelled edges.
+// `strength` 0.0 is perfectly flat; around 1.0 the bevels catch grazing light clearly.
+// Each tile's relief falls to zero at its border, so it lines up with the base texture and tiles seamlessly.
+pub fn create_tile_normal_map(strength: f32, tile_pixels: f32) -> Image {
+    let bevel = tile_pixels * TILE_BEVEL_SHARE;
+
+    // Height across one tile along a single axis, and its slope
+    let profile = |p: usize| {
+        let u = (p as f32 + 0.5).rem_euclid(tile_pixels);
+        let (distance, direction) = if u < tile_pixels / 2.0 {
+            (u, 1.0)
+        } else {
+            (tile_pixels - u, -1.0)
+        };
+        let t = (distance / bevel).min(1.0);
+        let height = t * t * (3.0 - 2.0 * t);
+        let slope = if t < 1.0 { direction * 6.0 * t * (1.0 - t) / bevel } else { 0.0 };
+        (height, slope)
+    };
+
+    let mut pixels = vec![0; FLOOR_TEXTURE_SIZE * FLOOR_TEXTURE_SIZE * 4];
+    for y in 0..FLOOR_TEXTURE_SIZE {
+        let (height_y, slope_y) = profile(y);
+        for x in 0..FLOOR_TEXTURE_SIZE {
+            let (height_x, slope_x) = profile(x);
+            // The slab height is the product of both axes, so corners round off smoothly
+            let gradient = Vec2::new(slope_x * height_y, height_x * slope_y) * strength * bevel;
+            let normal = Vec3::new(-gradient.x, -gradient.y, 1.0).normalize();
+            let encoded = (normal * 0.5 + 0.5) * 255.0;
+
+            let i = (y * FLOOR_TEXTURE_SIZE + x) * 4;
+            pixels[i..i + 4].copy_from_slice(&[encoded.x as u8, encoded.y as u8, encoded.z as u8, 255]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: FLOOR_TEXTURE_SIZE as u32,
+            height: FLOOR_TEXTURE_SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        // Normals are data, not colour, so they must stay linear
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+// Shift+V steps the floor through `FLOOR_SIZES`, rebuilding its meshes, collider and texture to match.
+// The wet patches are mopped up, since they no longer line up with the new floor; the ramp and the pool move with it.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn resize_floor(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut floor_size: ResMut<FloorSize>,
+    mut terrain: ResMut<TerrainSettings>,
+    current: Res<CurrentFloorPattern>,
+    rng: Res<SimulationRng>,
+    mut wetness: ResMut<FloorWetness>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut floors: Query<(&Handle<Mesh>, &Handle<StandardMaterial>, Option<&mut Collider>), With<Floor>>,
+) {
+    if !bindings.just_pressed(Action::FloorSize, &keys) {
+        return;
+    }
+
+    floor_size.0 = FLOOR_SIZES.iter().copied().find(|&size| size > floor_size.0).unwrap_or(FLOOR_SIZES[0]);
+    terrain.size = floor_size.0;
+    info!("Floor size: {} m", floor_size.0);
+
+    let tile_pixels = floor_size.tile_pixels();
+    for (mesh, material, collider) in floors.iter_mut() {
+        match collider {
+            // The textured floor
+            Some(mut collider) => {
+                let (floor_mesh, floor_collider) = floor_shape(floor_size.0, &terrain);
+                meshes.insert(mesh, floor_mesh);
+                *collider = floor_collider;
+                let normal_map = materials.get(material).and_then(|material| material.normal_map_texture.clone());
+                if let Some(normal_map) = normal_map {
+                    images.insert(&normal_map, create_tile_normal_map(FLOOR_NORMAL_STRENGTH, tile_pixels));
+                }
+            }
+            None => {
+                meshes.insert(mesh, plane_mesh(floor_size.0));
+            }
+        }
+    }
+
+    *wetness = FloorWetness::new(wetness.image().clone(), Vec::new(), FLOOR_TEXTURE_SIZE, floor_size.0);
+    if let Some(image) = images.get_mut(wetness.image()) {
+        *image = current.0.image(rng.seed, tile_pixels);
+        wetness.set_dry(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ramp, scene_config, terrain, tuning, water_pool};
+
+    #[test]
+    fn floor_collider_and_tiles_follow_the_floor_size() {
+        let terrain = terrain::TerrainSettings::default();
+        // World width of the first checkerboard tile along a row of the texture
+        let tile_meters = |size: f32| {
+            let image = create_checkerboard_image(FloorSize(size).tile_pixels());
+            let first = image.data[0];
+            let tile = (0..FLOOR_TEXTURE_SIZE).find(|&x| image.data[x * 4] != first).unwrap();
+            tile as f32 * size / FLOOR_TEXTURE_SIZE as f32
+        };
+
+        for size in [10.0, 20.0, 40.0] {
+            let (mesh, collider) = floor_shape(size, &terrain);
+            let half_extents = Vec3::from(mesh.compute_aabb().unwrap().half_extents);
+            let cuboid = collider.as_cuboid().unwrap().half_extents();
+            assert_eq!((cuboid.x, cuboid.z), (half_extents.x, half_extents.z), "floor size {size}");
+            assert!((tile_meters(size) - tile_meters(20.0)).abs() < 0.1, "tiles stretched at floor size {size}");
+        }
+    }
+
+    #[test]
+    fn the_ramp_and_the_pool_stay_on_a_shrunk_floor() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<FloorSize>()
+            .init_resource::<ramp::RampSettings>()
+            .init_resource::<scene_config::SceneConfig>()
+            .init_resource::<tuning::DropletTuning>()
+            .add_systems(Update, (ramp::apply_ramp_settings, water_pool::fit_pool_to_floor));
+        let floor_size = FloorSize::default();
+        let ramp = ramp::spawn_ramp(&mut app.world_mut().commands(), &ramp::RampSettings::default(), &floor_size);
+        let pool = water_pool::spawn_water_volume(&mut app.world_mut().commands(), &floor_size);
+        app.world_mut().flush();
+        app.update();
+        let translation = |app: &App, entity| app.world().get::<Transform>(entity).unwrap().translation;
+        let (ramp_before, pool_before) = (translation(&app, ramp), translation(&app, pool));
+
+        // Shift+V wraps round from the largest floor to the smallest
+        app.world_mut().resource_mut::<FloorSize>().0 = 10.0;
+        app.update();
+
+        let half_size = 5.0;
+        let pool_position = translation(&app, pool);
+        let pool_extents = app.world().get::<water_pool::WaterVolume>(pool).unwrap().half_extents;
+        assert!((pool_position.xz().abs() + pool_extents).cmple(Vec2::splat(half_size)).all());
+        assert_ne!(pool_position, pool_before);
+        let ramp_transform = app.world().get::<Transform>(ramp).unwrap();
+        let far_end = ramp_transform.transform_point(Vec3::new(0.5, 0.0, 0.5));
+        assert!(far_end.xz().abs().cmple(Vec2::splat(half_size)).all());
+        assert_ne!(ramp_transform.translation, ramp_before);
+    }
+}
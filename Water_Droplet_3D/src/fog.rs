@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+
+// Distance haze, so the far edges of the floor and stray particles fade into the sky
+#[derive(Resource)]
+pub struct FogConfig {
+    pub enabled: bool,
+    // Squared-exponential falloff: the droplet at the default camera distance stays almost clear,
+    // while the floor edges ten or so units further out are mostly hazed over
+    pub density: f32,
+}
+
+impl Default for FogConfig {
+    fn default() -> Self {
+        Self { enabled: true, density: 0.07 }
+    }
+}
+
+// J switches the fog off and on, to compare the two
+pub fn toggle_fog(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut config: ResMut<FogConfig>,
+) {
+    if bindings.just_pressed(Action::Fog, &keys) {
+        config.enabled = !config.enabled;
+        info!("Fog {}", if config.enabled { "on" } else { "off" });
+    }
+}
+
+// Keeps the camera's fog in line with the config, tinted to the current sky colour as the day goes by
+pub fn apply_fog(
+    mut commands: Commands,
+    config: Res<FogConfig>,
+    clear_color: Res<ClearColor>,
+    mut cameras: Query<(Entity, Option<&mut FogSettings>), With<Camera3d>>,
+) {
+    for (camera, fog) in cameras.iter_mut() {
+        match (config.enabled, fog) {
+            (true, Some(mut fog)) => {
+                if fog.color != clear_color.0 {
+                    fog.color = clear_color.0;
+                }
+                if config.is_changed() {
+                    fog.falloff = FogFalloff::ExponentialSquared { density: config.density };
+                }
+            }
+            (true, None) => {
+                commands.entity(camera).insert(FogSettings {
+                    color: clear_color.0,
+                    falloff: FogFalloff::ExponentialSquared { density: config.density },
+                    ..default()
+                });
+            }
+            (false, Some(_)) => {
+                commands.entity(camera).remove::<FogSettings>();
+            }
+            (false, None) => {}
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::SplashParticle;
+
+// Whether the spray is frozen where it is. Droplets and everything else carry on as usual.
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct FrozenParticles(pub bool);
+
+// A particle held in place, with the velocity it had, to carry on with once it's thawed
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Frozen(Velocity);
+
+impl Frozen {
+    pub fn velocity(&self) -> Velocity {
+        self.0
+    }
+}
+
+// Shift+P freezes the spray and thaws it again
+pub fn toggle_freeze(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut frozen: ResMut<FrozenParticles>,
+    mut particles: Query<(Entity, &Frozen, &mut Velocity)>,
+) {
+    if !bindings.just_pressed(Action::FreezeParticles, &keys) {
+        return;
+    }
+    frozen.0 = !frozen.0;
+    info!("Particles {}", if frozen.0 { "frozen" } else { "thawed" });
+    if frozen.0 {
+        return;
+    }
+
+    for (entity, thawed, mut velocity) in particles.iter_mut() {
+        *velocity = thawed.0;
+        // Woken, in case it was asleep when it froze
+        commands.entity(entity).insert((RigidBody::Dynamic, Sleeping::default())).remove::<Frozen>();
+    }
+}
+
+// Holds every particle in the air in place while frozen, including ones launched since
+#[allow(clippy::type_complexity)]
+pub fn freeze_particles(
+    mut commands: Commands,
+    frozen: Res<FrozenParticles>,
+    particles: Query<(Entity, &Velocity), (With<SplashParticle>, Without<Frozen>, Without<RigidBodyDisabled>)>,
+) {
+    if !frozen.0 {
+        return;
+    }
+    for (entity, velocity) in particles.iter() {
+        commands.entity(entity).insert((RigidBody::Fixed, Frozen(*velocity)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_particles_hold_still_and_thaw_with_the_velocity_they_had() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<FrozenParticles>()
+            .add_systems(Update, (toggle_freeze, freeze_particles).chain());
+        let particle = |app: &mut App, velocity: Vec3| {
+            let particle = SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 };
+            app.world_mut().spawn((particle, RigidBody::Dynamic, Velocity::linear(velocity))).id()
+        };
+        let flying = particle(&mut app, Vec3::new(1.0, 2.0, 3.0));
+        let press_shift_p = |app: &mut App| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.release(KeyCode::KeyP);
+            keys.clear();
+            keys.press(KeyCode::ShiftLeft);
+            keys.press(KeyCode::KeyP);
+            app.update();
+        };
+
+        press_shift_p(&mut app);
+        assert_eq!(app.world().get::<RigidBody>(flying), Some(&RigidBody::Fixed));
+        // Rapier reports a fixed body as still
+        app.world_mut().get_mut::<Velocity>(flying).unwrap().linvel = Vec3::ZERO;
+
+        // One launched while frozen freezes too
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+        let launched = particle(&mut app, Vec3::Y);
+        app.update();
+        assert_eq!(app.world().get::<RigidBody>(launched), Some(&RigidBody::Fixed));
+
+        press_shift_p(&mut app);
+        for (entity, velocity) in [(flying, Vec3::new(1.0, 2.0, 3.0)), (launched, Vec3::Y)] {
+            assert_eq!(app.world().get::<RigidBody>(entity), Some(&RigidBody::Dynamic));
+            assert_eq!(app.world().get::<Velocity>(entity).unwrap().linvel, velocity);
+            assert!(app.world().get::<Frozen>(entity).is_none());
+        }
+    }
+}
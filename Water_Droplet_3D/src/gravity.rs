@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+
+// Gravity presets as (name, downward acceleration in m/s²), ordered from weakest to strongest.
+// A negative value pulls things up instead.
+const GRAVITY_PRESETS: [(&str, f32); 4] = [
+    ("Reverse", -9.81),
+    ("Moon", 1.6),
+    ("Earth", 9.81),
+    ("Jupiter", 24.8),
+];
+
+// Index into `GRAVITY_PRESETS` for the gravity currently applied to Rapier.
+// Lives outside the droplet state so pressing R keeps whatever gravity was chosen.
+#[derive(Resource)]
+pub struct GravityPreset(usize);
+
+impl Default for GravityPreset {
+    fn default() -> Self {
+        Self(2) // Earth, which is also Rapier's default
+    }
+}
+
+impl GravityPreset {
+    // The preset nearest to a downward acceleration, e.g. one read from the scene file
+    pub fn closest_to(acceleration: f32) -> Self {
+        let distance = |index: &usize| (GRAVITY_PRESETS[*index].1 - acceleration).abs();
+        Self((0..GRAVITY_PRESETS.len()).min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap_or(2))
+    }
+}
+
+// +/- step through the presets (Shift+- and Shift+= are the volume's)
+pub fn cycle_gravity(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut preset: ResMut<GravityPreset>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    bodies: Query<(Entity, &RigidBody)>,
+) {
+    let previous = preset.0;
+    if bindings.just_pressed(Action::GravityUp, &keys) {
+        preset.0 = (preset.0 + 1).min(GRAVITY_PRESETS.len() - 1);
+    }
+    if bindings.just_pressed(Action::GravityDown, &keys) {
+        preset.0 = preset.0.saturating_sub(1);
+    }
+    if preset.0 == previous {
+        return;
+    }
+
+    let (name, acceleration) = GRAVITY_PRESETS[preset.0];
+    rapier_config.gravity = Vec3::new(0.0, -acceleration, 0.0);
+    info!("Gravity: {name} ({acceleration} m/s²)");
+
+    // Resting bodies are asleep and would ignore the new gravity until something touched them
+    for (entity, body) in bodies.iter() {
+        if *body == RigidBody::Dynamic {
+            commands.entity(entity).insert(Sleeping::default());
+        }
+    }
+}
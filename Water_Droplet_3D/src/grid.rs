@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::floor::FloorSize;
+use crate::keybindings::{Action, KeyBindings};
+use crate::terrain::TerrainSettings;
+
+// Metres between grid lines
+const GRID_SPACING: f32 = 1.0;
+// Just above the floor so the lines aren't lost in it
+const GRID_LIFT: f32 = 0.005;
+const GRID_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.5);
+const X_AXIS_COLOR: Color = Color::srgb(0.9, 0.1, 0.1);
+const Z_AXIS_COLOR: Color = Color::srgb(0.1, 0.2, 0.9);
+
+// A metre grid over the floor for judging where droplets land and how far splashes reach, off until G
+#[derive(Resource, Default)]
+pub struct GridOverlay {
+    pub enabled: bool,
+}
+
+// G shows and hides the grid
+pub fn toggle_grid(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut grid: ResMut<GridOverlay>,
+) {
+    if bindings.just_pressed(Action::Grid, &keys) {
+        grid.enabled = !grid.enabled;
+        info!("Grid {}", if grid.enabled { "on" } else { "off" });
+    }
+}
+
+// Lines every `GRID_SPACING` out from the floor origin, with the X and Z axes through it picked out in red and blue.
+// Each line follows the terrain, so it stays on the floor over the hills.
+pub fn draw_grid(
+    mut gizmos: Gizmos,
+    grid: Res<GridOverlay>,
+    floor_size: Res<FloorSize>,
+    terrain: Res<TerrainSettings>,
+) {
+    if !grid.enabled {
+        return;
+    }
+
+    let half = floor_size.0 / 2.0;
+    let lines = (half / GRID_SPACING).floor() as i32;
+    // Enough points along each line to follow the terrain's bumps
+    let steps = (floor_size.0 / GRID_SPACING * 4.0).ceil() as usize;
+    let height = terrain.sampler();
+    let on_floor = |x: f32, z: f32| Vec3::new(x, height(Vec2::new(x, z)) + GRID_LIFT, z);
+
+    for i in -lines..=lines {
+        let offset = i as f32 * GRID_SPACING;
+        let (along_x, along_z) = if i == 0 { (X_AXIS_COLOR, Z_AXIS_COLOR) } else { (GRID_COLOR, GRID_COLOR) };
+        let along = |step: usize| -half + floor_size.0 * step as f32 / steps as f32;
+        gizmos.linestrip((0..=steps).map(|step| on_floor(along(step), offset)), along_x);
+        gizmos.linestrip((0..=steps).map(|step| on_floor(offset, along(step))), along_z);
+    }
+}
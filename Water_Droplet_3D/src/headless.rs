@@ -0,0 +1,244 @@
+//! `--headless`: the simulation stepped without a window, for scripted runs and tests.
+
+use bevy::gltf::GltfPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier3d::prelude::*;
+use std::fmt;
+
+use crate::cli::Cli;
+use crate::keybindings::KeyBindings;
+use crate::pool::ParticlePool;
+use crate::ramp::RampSettings;
+use crate::scene_config::SceneConfig;
+use crate::terrain::TerrainSettings;
+use crate::tuning::Viscosity;
+use crate::{floor, obstacles, ramp, scene_file_plugin, simulation, simulation_plugin, spawn_droplet, water_pool};
+use crate::{DropletAssets, PrimaryDroplet, SimulationRng, SplashAssets, SplashEvent};
+
+/// `--headless`: the droplet, its splashes and the physics, with no window, rendering or input. Runs `--steps`
+/// steps and prints how things ended up.
+pub fn run(cli: Cli) {
+    let steps = cli.steps;
+    let mut app = app(cli);
+    for _ in 0..steps {
+        app.update();
+    }
+    println!("{}", Summary::of(app.world_mut(), steps));
+}
+
+/// The app `run` steps, with its startup done, for stepping by hand
+pub fn app(cli: Cli) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), ScenePlugin, TransformPlugin, HierarchyPlugin))
+        // Rapier looks up meshes and scenes to build colliders from
+        .init_asset::<Mesh>()
+        // The scene's glTF model is loaded for its meshes' colliders. It comes with materials and textures, and its
+        // scene spawns with the components that would draw it, which have to be known here even though they do nothing.
+        .add_plugins(GltfPlugin::default())
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .register_type::<Handle<Mesh>>()
+        .register_type::<Handle<StandardMaterial>>()
+        .register_type::<Visibility>()
+        .register_type::<InheritedVisibility>()
+        .register_type::<ViewVisibility>()
+        .register_type::<bevy::render::primitives::Aabb>()
+        .insert_resource(SimulationRng::from_seed_or_random(cli.seed))
+        .insert_resource(cli)
+        // Never pressed, but the reset reads them
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<KeyBindings>()
+        .init_resource::<Splashes>()
+        .add_plugins((simulation_plugin, scene_file_plugin))
+        .add_systems(PreStartup, step_clock_by_timestep.after(simulation::apply_timestep))
+        .add_systems(Startup, (setup, obstacles::spawn_obstacle_scene))
+        .add_systems(Update, count_splashes);
+    app.finish();
+    app.cleanup();
+    // Runs the startup systems and starts the clock, which only counts from its first update. Every update after this
+    // is one physics step.
+    app.update();
+    app
+}
+
+// With no frames to keep up with, the clock moves on by exactly one physics step per update
+fn step_clock_by_timestep(fixed_time: Res<Time<Fixed>>, mut time_update: ResMut<TimeUpdateStrategy>) {
+    *time_update = TimeUpdateStrategy::ManualDuration(fixed_time.timestep());
+}
+
+// The floor, ramp, water pool and droplet the window has, without their meshes and materials, and the particle pool.
+// Their handles point at nothing, as nothing here is drawn.
+fn setup(
+    mut commands: Commands,
+    mut particle_pool: ResMut<ParticlePool>,
+    scene: Res<SceneConfig>,
+    terrain: Res<TerrainSettings>,
+    floor_size: Res<floor::FloorSize>,
+    ramp_settings: Res<RampSettings>,
+    viscosity: Res<Viscosity>,
+) {
+    let (_, floor_collider) = floor::floor_shape(floor_size.0, &terrain);
+    commands.spawn((TransformBundle::default(), RigidBody::Fixed, floor_collider, floor::Floor));
+    ramp::spawn_ramp(&mut commands, &ramp_settings, &floor_size);
+    water_pool::spawn_water_volume(&mut commands, &floor_size);
+
+    let droplet_assets = DropletAssets {
+        mesh: Handle::default(),
+        material: Handle::default(),
+        surface_materials: vec![Handle::default()],
+    };
+    let start = Vec3::from(scene.droplet_position);
+    let droplet = spawn_droplet(&mut commands, start, scene.droplet_radius, &droplet_assets, &viscosity);
+    commands.entity(droplet).insert(PrimaryDroplet);
+    commands.insert_resource(droplet_assets);
+
+    let splash_assets = SplashAssets {
+        particle_mesh: Handle::default(),
+        particle_material: Handle::default(),
+        particle_fade_materials: Vec::new(),
+        ripple_mesh: Handle::default(),
+        ripple_materials: Vec::new(),
+        puddle_mesh: Handle::default(),
+        puddle_material: Handle::default(),
+    };
+    particle_pool.fill(&mut commands, &splash_assets);
+    commands.insert_resource(splash_assets);
+}
+
+/// Splashes so far
+#[derive(Resource, Default)]
+pub struct Splashes(pub usize);
+
+fn count_splashes(mut splash_events: EventReader<SplashEvent>, mut splashes: ResMut<Splashes>) {
+    splashes.0 += splash_events.read().count();
+}
+
+/// How a headless run ended up
+#[derive(Debug)]
+pub struct Summary {
+    /// Physics steps run
+    pub steps: u32,
+    /// Seconds per step
+    pub timestep: f32,
+    /// Where the primary droplet ended up, if it's still around
+    pub droplet: Option<Vec3>,
+    /// Splashes over the whole run
+    pub splashes: usize,
+    /// Splash particles still in flight
+    pub particles: usize,
+}
+
+impl Summary {
+    /// How `world` has ended up after `steps` steps
+    pub fn of(world: &mut World, steps: u32) -> Self {
+        let droplet = world.query_filtered::<&Transform, With<PrimaryDroplet>>().iter(world).next();
+        Self {
+            steps,
+            timestep: world.resource::<Time<Fixed>>().timestep().as_secs_f32(),
+            droplet: droplet.map(|transform| transform.translation),
+            splashes: world.resource::<Splashes>().0,
+            particles: world.resource::<ParticlePool>().active(),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Simulated {} steps ({:.2} s)", self.steps, self.steps as f32 * self.timestep)?;
+        match self.droplet {
+            Some(position) => writeln!(f, "Droplet at ({:.3}, {:.3}, {:.3})", position.x, position.y, position.z)?,
+            None => writeln!(f, "Droplet gone")?,
+        }
+        match self.splashes {
+            0 => writeln!(f, "No splash")?,
+            splashes => writeln!(f, "Splashed: yes ({splashes})")?,
+        }
+        write!(f, "Particles: {}", self.particles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli, ramp, scene_config, water_pool};
+
+    #[test]
+    fn headless_runs_step_the_droplet_down_without_a_window() {
+        use clap::Parser;
+
+        let cli = cli::Cli::try_parse_from(["droplet", "--headless", "--steps", "30", "--seed", "1"]).unwrap();
+        assert!(cli.headless);
+        assert!(cli::Cli::try_parse_from(["droplet", "--steps", "30"]).is_err());
+
+        let steps = cli.steps;
+        let mut app = app(cli);
+        for _ in 0..steps {
+            app.update();
+        }
+        let start = Vec3::from(app.world().resource::<scene_config::SceneConfig>().droplet_position);
+        let summary = Summary::of(app.world_mut(), steps);
+        let droplet = summary.droplet.unwrap();
+        // Half a second of falling from rest
+        assert!(start.y - droplet.y > 0.5, "fell from {start} to {droplet}");
+        assert_eq!((summary.splashes, summary.particles), (0, 0));
+        assert!(summary.to_string().starts_with("Simulated 30 steps (0.50 s)"), "{summary}");
+    }
+
+    #[test]
+    fn the_physics_takes_the_same_steps_however_fast_the_frames_come() {
+        use bevy::time::TimeUpdateStrategy;
+        use clap::Parser;
+        use std::time::Duration;
+
+        let app = || app(cli::Cli::try_parse_from(["droplet", "--headless", "--seed", "6"]).unwrap());
+        let droplet_at = |app: &mut App| {
+            let mut droplet = app.world_mut().query_filtered::<&Transform, With<PrimaryDroplet>>();
+            droplet.single(app.world()).translation
+        };
+
+        // A second of frames at 144 Hz
+        let mut fast = app();
+        let frame = Duration::from_secs_f64(1.0 / 144.0);
+        fast.insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+        for _ in 0..144 {
+            fast.update();
+        }
+        let fixed = fast.world().resource::<Time<Fixed>>();
+        let steps = (fixed.elapsed().as_secs_f64() / fixed.timestep().as_secs_f64()).round() as usize;
+        assert!((59..=60).contains(&steps), "{steps} steps");
+
+        // One frame per step, as headless runs go
+        let mut stepped = app();
+        for _ in 0..steps {
+            stepped.update();
+        }
+        assert_eq!(droplet_at(&mut fast), droplet_at(&mut stepped));
+    }
+
+    #[test]
+    fn headless_runs_build_the_ramp_the_pool_and_the_model_colliders() {
+        use clap::Parser;
+
+        let mut app = app(cli::Cli::try_parse_from(["droplet", "--headless"]).unwrap());
+        let world = app.world_mut();
+        assert_eq!(world.query_filtered::<(), (With<ramp::Ramp>, With<Collider>)>().iter(world).count(), 1);
+        assert_eq!(world.query::<&water_pool::WaterVolume>().iter(world).count(), 1);
+
+        // `assets/scene.ron` places a rock, which loads in the background. Its meshes are the only colliders with
+        // a parent.
+        let model_colliders = |app: &mut App| {
+            let world = app.world_mut();
+            world.query_filtered::<(), (With<Collider>, With<Parent>)>().iter(world).count()
+        };
+        for _ in 0..500 {
+            if model_colliders(&mut app) > 0 {
+                return;
+            }
+            app.update();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the rock never got a collider");
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+
+// The mouse isn't rebindable, so it's described here rather than in `KeyBindings`
+const MOUSE_HELP: [(&str, &str); 3] = [
+    ("Click", "Drop a droplet there"),
+    ("Drag", "Orbit the camera (right: pan)"),
+    ("Scroll", "Zoom"),
+];
+const HELP_FONT_SIZE: f32 = 15.0;
+
+// The help panel, hidden until H
+#[derive(Component)]
+pub struct HelpOverlay;
+
+// The two columns of the panel, keys on the left and what they do on the right
+#[derive(Component)]
+pub struct HelpKeys;
+
+#[derive(Component)]
+pub struct HelpDescriptions;
+
+pub fn setup_help(mut commands: Commands) {
+    let style = TextStyle { font_size: HELP_FONT_SIZE, color: Color::WHITE, ..default() };
+    let column = || TextBundle::from_section("", style.clone());
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(40.0),
+                    left: Val::Px(40.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.75).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            HelpOverlay,
+        ))
+        .with_children(|panel| {
+            panel.spawn((column(), HelpKeys));
+            panel.spawn((column(), HelpDescriptions));
+        });
+}
+
+// H shows and hides the panel, and Escape closes it. It's only text, so the rest of the controls keep working
+// while it's up.
+pub fn toggle_help(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut overlay: Query<&mut Visibility, With<HelpOverlay>>,
+) {
+    let Ok(mut visibility) = overlay.get_single_mut() else { return };
+    if bindings.just_pressed(Action::Help, &keys) {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    } else if keys.just_pressed(KeyCode::Escape) {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Fills the panel from the bindings, and again whenever they change
+pub fn update_help(
+    bindings: Res<KeyBindings>,
+    mut key_column: Query<&mut Text, (With<HelpKeys>, Without<HelpDescriptions>)>,
+    mut description_column: Query<&mut Text, (With<HelpDescriptions>, Without<HelpKeys>)>,
+) {
+    if !bindings.is_changed() {
+        return;
+    }
+    let lines = bindings.help_lines();
+    let lines = lines.iter().map(|(keys, description)| (keys.as_str(), *description)).chain(MOUSE_HELP);
+    let (keys, descriptions): (Vec<&str>, Vec<&str>) = lines.unzip();
+
+    for mut text in key_column.iter_mut() {
+        text.sections[0].value = keys.join("\n");
+    }
+    for mut text in description_column.iter_mut() {
+        text.sections[0].value = descriptions.join("\n");
+    }
+}
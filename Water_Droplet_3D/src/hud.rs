@@ -0,0 +1,72 @@
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::pool::ParticlePool;
+use crate::wind::Wind;
+use crate::ParticleBudget;
+
+#[derive(Component)]
+pub struct HudText;
+
+pub fn setup_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        }),
+        HudText,
+    ));
+}
+
+pub fn update_hud(
+    diagnostics: Res<DiagnosticsStore>,
+    particle_pool: Res<ParticlePool>,
+    budget: Res<ParticleBudget>,
+    wind: Res<Wind>,
+    mut hud: Query<&mut Text, With<HudText>>,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+
+    for mut text in hud.iter_mut() {
+        text.sections[0].value = format!(
+            "FPS: {fps:.0}\nParticles: {} / {}\nWind: {:.0} m/s towards {:.0}°",
+            particle_pool.active(),
+            budget.max,
+            wind.speed,
+            wind.heading,
+        );
+    }
+}
+
+// F8 shows/hides the overlay
+pub fn toggle_hud(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut hud: Query<&mut Visibility,
+    With<HudText>>,
+) {
+    if !bindings.just_pressed(Action::Hud, &keys) {
+        return;
+    }
+
+    for mut visibility in hud.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
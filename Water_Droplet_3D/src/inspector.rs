@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::screenshot::overlays_shown;
+
+// The world inspector, hidden until F12. The crate's own types are registered by `snapshot::plugin`, so they show
+// up as editable fields rather than opaque entries.
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Inspector>()
+        .add_plugins(WorldInspectorPlugin::new().run_if(inspector_open.and_then(overlays_shown)))
+        .add_systems(Update, toggle_inspector);
+}
+
+#[derive(Resource, Default)]
+pub struct Inspector {
+    pub open: bool,
+}
+
+// F12 shows and hides the inspector window
+pub fn toggle_inspector(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut inspector: ResMut<Inspector>,
+) {
+    if bindings.just_pressed(Action::Inspector, &keys) {
+        inspector.open = !inspector.open;
+    }
+}
+
+fn inspector_open(inspector: Res<Inspector>) -> bool {
+    inspector.open
+}
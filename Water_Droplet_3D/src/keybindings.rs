@@ -0,0 +1,432 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+const KEY_BINDINGS_PATH: &str = "assets/keybindings.ron";
+
+// Everything the keyboard can do. Input systems ask `KeyBindings` about these rather than checking keys
+// themselves, so the help overlay lists exactly what the keys do.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum Action {
+    Reset,
+    Reseed,
+    Record,
+    RecordVideo,
+    ExtraDroplet,
+    ShrinkDroplet,
+    GrowDroplet,
+    SmallDroplets,
+    MediumDroplets,
+    LargeDroplets,
+    NextLiquid,
+    LessBouncy,
+    MoreBouncy,
+    Thinner,
+    Thicker,
+    NextDye,
+    LaunchMode,
+    DropOnRamp,
+    Rain,
+    Pause,
+    FreezeParticles,
+    Step,
+    SlowDown,
+    SpeedUp,
+    GravityDown,
+    GravityUp,
+    WindStronger,
+    WindWeaker,
+    WindLeft,
+    WindRight,
+    Cohesion,
+    Metaballs,
+    FewerParticles,
+    MoreParticles,
+    LowerRamp,
+    RaiseRamp,
+    SpawnBox,
+    SpawnSphere,
+    ClearObstacles,
+    ClearPuddles,
+    FloorPattern,
+    FloorSize,
+    DayNight,
+    // Slots 1 to 9
+    FlyToBookmark(u8),
+    SaveBookmark(u8),
+    FollowCamera,
+    Turntable,
+    Grid,
+    Hud,
+    Bloom,
+    Ssao,
+    SsaoQuality,
+    Fog,
+    TuningPanel,
+    Inspector,
+    Screenshot,
+    CleanScreenshot,
+    ReloadScene,
+    SaveSnapshot,
+    RestoreSnapshot,
+    Help,
+    Mute,
+    Plinks,
+    VolumeDown,
+    VolumeUp,
+}
+
+impl Action {
+    // What the help overlay says the action does. Actions sharing a description (like the two ends of a setting)
+    // are listed on one line.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Reset => "Reset the scene",
+            Action::Reseed => "Replay the first splash's random numbers",
+            Action::Record => "Record a splash as PNG frames",
+            Action::RecordVideo => "Record every frame until pressed again",
+            Action::ExtraDroplet => "Drop another droplet",
+            Action::ShrinkDroplet | Action::GrowDroplet => "Droplet size",
+            Action::SmallDroplets | Action::MediumDroplets | Action::LargeDroplets => "Small / medium / large droplets",
+            Action::NextLiquid => "Next liquid",
+            Action::LessBouncy | Action::MoreBouncy => "Bounciness",
+            Action::Thinner | Action::Thicker => "Viscosity",
+            Action::NextDye => "Dye new droplets",
+            Action::LaunchMode => "Launch mode (drag to aim)",
+            Action::DropOnRamp => "Drop onto the ramp",
+            Action::Rain => "Rain",
+            Action::Pause => "Pause",
+            Action::FreezeParticles => "Freeze the spray in place",
+            Action::Step => "Step while paused",
+            Action::SlowDown | Action::SpeedUp => "Time scale",
+            Action::GravityDown | Action::GravityUp => "Gravity",
+            Action::WindStronger | Action::WindWeaker => "Wind strength",
+            Action::WindLeft | Action::WindRight => "Wind direction",
+            Action::Cohesion => "Particle cohesion",
+            Action::Metaballs => "Metaball particles",
+            Action::FewerParticles | Action::MoreParticles => "Particle budget",
+            Action::LowerRamp | Action::RaiseRamp => "Ramp angle",
+            Action::SpawnBox => "Drop a box",
+            Action::SpawnSphere => "Drop a sphere",
+            Action::ClearObstacles => "Clear obstacles",
+            Action::ClearPuddles => "Clear puddles",
+            Action::FloorPattern => "Floor pattern",
+            Action::FloorSize => "Floor size",
+            Action::DayNight => "Day/night cycle",
+            Action::FlyToBookmark(_) => "Fly to camera bookmark",
+            Action::SaveBookmark(_) => "Save camera bookmark",
+            Action::FollowCamera => "Follow the droplet",
+            Action::Turntable => "Turntable",
+            Action::Grid => "Grid",
+            Action::Hud => "Stats overlay",
+            Action::Bloom => "Bloom",
+            Action::Ssao => "Ambient occlusion",
+            Action::SsaoQuality => "Ambient occlusion quality",
+            Action::Fog => "Fog",
+            Action::TuningPanel => "Tuning panel",
+            Action::Inspector => "World inspector",
+            Action::Screenshot => "Screenshot",
+            Action::CleanScreenshot => "Screenshot without overlays",
+            Action::ReloadScene => "Reload assets/scene.ron",
+            Action::SaveSnapshot => "Save a snapshot",
+            Action::RestoreSnapshot => "Go back to the snapshot",
+            Action::Help => "This help",
+            Action::Mute => "Mute",
+            Action::Plinks => "Particle plinks",
+            Action::VolumeDown | Action::VolumeUp => "Volume",
+        }
+    }
+}
+
+// A key, and whether Shift has to be held with it. Shift is matched exactly, so V and Shift+V are different
+// bindings and holding Shift never also fires the plain one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Binding {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub shift: bool,
+}
+
+impl Binding {
+    pub const fn key(key: KeyCode) -> Self {
+        Self { key, shift: false }
+    }
+
+    pub const fn shift(key: KeyCode) -> Self {
+        Self { key, shift: true }
+    }
+
+    // How the help overlay shows it, like `Shift+[`
+    pub fn label(&self) -> String {
+        let name = match self.key {
+            KeyCode::BracketLeft => "[".to_string(),
+            KeyCode::BracketRight => "]".to_string(),
+            KeyCode::Minus => "-".to_string(),
+            KeyCode::Equal => "=".to_string(),
+            KeyCode::Period => ".".to_string(),
+            KeyCode::Semicolon => ";".to_string(),
+            KeyCode::Quote => "'".to_string(),
+            KeyCode::NumpadAdd => "Num+".to_string(),
+            KeyCode::NumpadSubtract => "Num-".to_string(),
+            KeyCode::ArrowUp => "Up".to_string(),
+            KeyCode::ArrowDown => "Down".to_string(),
+            KeyCode::ArrowLeft => "Left".to_string(),
+            KeyCode::ArrowRight => "Right".to_string(),
+            key => {
+                let name = format!("{key:?}");
+                let digit = name.strip_prefix("Numpad").map(|rest| format!("Num{rest}"));
+                let short = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name);
+                digit.unwrap_or_else(|| short.to_string())
+            }
+        };
+        if self.shift {
+            format!("Shift+{name}")
+        } else {
+            name
+        }
+    }
+}
+
+// Which keys trigger which actions, in the order the help lists them. An action can have more than one key.
+#[derive(Resource)]
+pub struct KeyBindings {
+    bindings: Vec<(Action, Binding)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use Action::*;
+        let (key, shift) = (Binding::key, Binding::shift);
+        let mut bindings = vec![
+            (Reset, key(KeyCode::KeyR)),
+            (Reseed, key(KeyCode::KeyS)),
+            (Record, shift(KeyCode::KeyR)),
+            (RecordVideo, key(KeyCode::F3)),
+            (ExtraDroplet, key(KeyCode::Space)),
+            (ShrinkDroplet, key(KeyCode::KeyZ)),
+            (GrowDroplet, key(KeyCode::KeyX)),
+            (SmallDroplets, key(KeyCode::Digit1)),
+            (MediumDroplets, key(KeyCode::Digit2)),
+            (LargeDroplets, key(KeyCode::Digit3)),
+            (NextLiquid, key(KeyCode::KeyL)),
+            (LessBouncy, key(KeyCode::KeyW)),
+            (MoreBouncy, key(KeyCode::KeyE)),
+            (Thinner, key(KeyCode::Semicolon)),
+            (Thicker, key(KeyCode::Quote)),
+            (NextDye, key(KeyCode::KeyD)),
+            (LaunchMode, key(KeyCode::KeyQ)),
+            (DropOnRamp, key(KeyCode::KeyA)),
+            (Rain, key(KeyCode::KeyT)),
+            (Pause, key(KeyCode::KeyP)),
+            (FreezeParticles, shift(KeyCode::KeyP)),
+            (Step, key(KeyCode::Period)),
+            (SlowDown, key(KeyCode::BracketLeft)),
+            (SpeedUp, key(KeyCode::BracketRight)),
+            (GravityDown, key(KeyCode::Minus)),
+            (GravityDown, key(KeyCode::NumpadSubtract)),
+            (GravityUp, key(KeyCode::Equal)),
+            (GravityUp, key(KeyCode::NumpadAdd)),
+            (WindWeaker, key(KeyCode::ArrowDown)),
+            (WindStronger, key(KeyCode::ArrowUp)),
+            (WindLeft, key(KeyCode::ArrowLeft)),
+            (WindRight, key(KeyCode::ArrowRight)),
+            (Cohesion, key(KeyCode::KeyU)),
+            (Metaballs, key(KeyCode::KeyY)),
+            (FewerParticles, key(KeyCode::PageDown)),
+            (MoreParticles, key(KeyCode::PageUp)),
+            (LowerRamp, shift(KeyCode::BracketLeft)),
+            (RaiseRamp, shift(KeyCode::BracketRight)),
+            (SpawnBox, key(KeyCode::KeyB)),
+            (SpawnSphere, key(KeyCode::KeyN)),
+            (ClearObstacles, shift(KeyCode::KeyC)),
+            (ClearPuddles, key(KeyCode::KeyC)),
+            (FloorPattern, key(KeyCode::KeyV)),
+            (FloorSize, shift(KeyCode::KeyV)),
+            (DayNight, key(KeyCode::KeyK)),
+        ];
+        // The digit row picks droplet sizes, so bookmarks are on the numpad
+        let digits = [
+            KeyCode::Numpad1,
+            KeyCode::Numpad2,
+            KeyCode::Numpad3,
+            KeyCode::Numpad4,
+            KeyCode::Numpad5,
+            KeyCode::Numpad6,
+            KeyCode::Numpad7,
+            KeyCode::Numpad8,
+            KeyCode::Numpad9,
+        ];
+        bindings.extend((1..).zip(digits).map(|(slot, digit)| (FlyToBookmark(slot), key(digit))));
+        bindings.extend((1..).zip(digits).map(|(slot, digit)| (SaveBookmark(slot), shift(digit))));
+        bindings.extend([
+            (FollowCamera, key(KeyCode::KeyF)),
+            (Turntable, key(KeyCode::F4)),
+            (Grid, key(KeyCode::KeyG)),
+            (Hud, key(KeyCode::F8)),
+            (Bloom, key(KeyCode::KeyO)),
+            (Ssao, key(KeyCode::KeyI)),
+            (SsaoQuality, shift(KeyCode::KeyI)),
+            (Fog, key(KeyCode::KeyJ)),
+            (TuningPanel, key(KeyCode::F1)),
+            (Inspector, key(KeyCode::F12)),
+            (Screenshot, key(KeyCode::F2)),
+            (CleanScreenshot, shift(KeyCode::F2)),
+            (ReloadScene, key(KeyCode::F5)),
+            (SaveSnapshot, key(KeyCode::F6)),
+            (RestoreSnapshot, key(KeyCode::F7)),
+            (Help, key(KeyCode::KeyH)),
+            (Mute, key(KeyCode::KeyM)),
+            (Plinks, shift(KeyCode::KeyM)),
+            (VolumeDown, shift(KeyCode::Minus)),
+            (VolumeDown, shift(KeyCode::NumpadSubtract)),
+            (VolumeUp, shift(KeyCode::Equal)),
+            (VolumeUp, shift(KeyCode::NumpadAdd)),
+        ]);
+        Self { bindings }
+    }
+}
+
+impl KeyBindings {
+    // The built-in bindings, with each action in `overrides` moved to the keys given there instead
+    pub fn with_overrides(overrides: &HashMap<Action, Vec<Binding>>) -> Self {
+        let mut bindings = Vec::new();
+        for (action, binding) in Self::default().bindings {
+            match overrides.get(&action) {
+                // In the built-in action's place, so the help keeps its order
+                Some(keys) if !bindings.iter().any(|(bound, _)| *bound == action) => {
+                    bindings.extend(keys.iter().map(|binding| (action, *binding)));
+                }
+                Some(_) => {}
+                None => bindings.push((action, binding)),
+            }
+        }
+        Self { bindings }
+    }
+
+    // Keys bound to more than one action, with the actions each one would fire
+    pub fn conflicts(&self) -> Vec<(Binding, Vec<Action>)> {
+        let mut conflicts: Vec<(Binding, Vec<Action>)> = Vec::new();
+        for (action, binding) in &self.bindings {
+            match conflicts.iter_mut().find(|(bound, _)| bound == binding) {
+                Some((_, actions)) => actions.push(*action),
+                None => conflicts.push((*binding, vec![*action])),
+            }
+        }
+        conflicts.retain(|(_, actions)| actions.len() > 1);
+        conflicts
+    }
+
+    // Whether one of the action's keys went down this frame, with Shift held or not as the binding says
+    pub fn just_pressed(&self, action: Action, keys: &ButtonInput<KeyCode>) -> bool {
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        self.bindings
+            .iter()
+            .any(|(bound, binding)| *bound == action && binding.shift == shift && keys.just_pressed(binding.key))
+    }
+
+    // One line per description, with every key that does it: `Z / X  Droplet size`
+    pub fn help_lines(&self) -> Vec<(String, &'static str)> {
+        let mut lines: Vec<(String, &'static str)> = Vec::new();
+        for (action, binding) in &self.bindings {
+            let description = action.description();
+            match lines.iter_mut().find(|(_, listed)| *listed == description) {
+                Some((keys, _)) => {
+                    keys.push_str(" / ");
+                    keys.push_str(&binding.label());
+                }
+                None => lines.push((binding.label(), description)),
+            }
+        }
+        lines
+    }
+}
+
+// Reads `assets/keybindings.ron` over the built-in bindings. A missing file leaves them all as they are, and a
+// broken one is reported and ignored.
+pub fn load_key_bindings(mut commands: Commands) {
+    let path = FileAssetReader::get_base_path().join(KEY_BINDINGS_PATH);
+    let overrides = match std::fs::read_to_string(&path) {
+        Ok(text) => match ron::from_str::<HashMap<Action, Vec<Binding>>>(&text) {
+            Ok(overrides) => {
+                info!("Loaded key bindings from {}", path.display());
+                overrides
+            }
+            Err(err) => {
+                error!("Couldn't parse {} ({err}); using the built-in keys", path.display());
+                HashMap::new()
+            }
+        },
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+        Err(err) => {
+            error!("Couldn't read {} ({err}); using the built-in keys", path.display());
+            HashMap::new()
+        }
+    };
+
+    let bindings = KeyBindings::with_overrides(&overrides);
+    for (binding, actions) in bindings.conflicts() {
+        warn!("{} is bound to more than one action: {actions:?}", binding.label());
+    }
+    commands.insert_resource(bindings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_lists_every_key_once_and_shift_picks_a_different_action() {
+        let bindings = KeyBindings::default();
+        let lines = bindings.help_lines();
+        assert!(lines.contains(&("Z / X".to_string(), "Droplet size")));
+        assert!(lines.contains(&("Shift+[ / Shift+]".to_string(), "Ramp angle")));
+        let numpad = "Num1 / Num2 / Num3 / Num4 / Num5 / Num6 / Num7 / Num8 / Num9";
+        assert!(lines.contains(&(numpad.to_string(), "Fly to camera bookmark")));
+        assert!(lines.contains(&("1 / 2 / 3".to_string(), "Small / medium / large droplets")));
+        assert_eq!(lines.iter().filter(|(_, description)| *description == "Droplet size").count(), 1);
+
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::KeyV);
+        assert!(bindings.just_pressed(Action::FloorPattern, &keys));
+        assert!(!bindings.just_pressed(Action::FloorSize, &keys));
+
+        // Holding Shift turns the same key into the other action, and never fires both
+        keys.release(KeyCode::KeyV);
+        keys.clear();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::KeyV);
+        assert!(bindings.just_pressed(Action::FloorSize, &keys));
+        assert!(!bindings.just_pressed(Action::FloorPattern, &keys));
+    }
+
+    #[test]
+    fn rebound_actions_lose_their_built_in_keys_and_clashes_are_reported() {
+        use bevy::utils::HashMap;
+        use Binding;
+
+        assert!(KeyBindings::default().conflicts().is_empty());
+
+        let text = "{ Reset: [(key: KeyP)], FloorSize: [(key: KeyV, shift: false), (key: F9)] }";
+        let overrides: HashMap<Action, Vec<Binding>> = ron::from_str(text).unwrap();
+        let bindings = KeyBindings::with_overrides(&overrides);
+
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::KeyR);
+        assert!(!bindings.just_pressed(Action::Reset, &keys));
+        keys.press(KeyCode::KeyP);
+        assert!(bindings.just_pressed(Action::Reset, &keys));
+        assert!(bindings.help_lines().contains(&("V / F9".to_string(), "Floor size")));
+
+        let mut conflicts = bindings.conflicts();
+        conflicts.sort_by_key(|(binding, _)| binding.label());
+        assert_eq!(
+            conflicts,
+            [
+                (Binding::key(KeyCode::KeyP), vec![Action::Reset, Action::Pause]),
+                (Binding::key(KeyCode::KeyV), vec![Action::FloorPattern, Action::FloorSize]),
+            ]
+        );
+    }
+}
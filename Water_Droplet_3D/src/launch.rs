@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+use bevy_rapier3d::prelude::*;
+
+use crate::camera::Turntable;
+use crate::keybindings::{Action, KeyBindings};
+use crate::terrain::TerrainSettings;
+use crate::tuning::Viscosity;
+use crate::{spawn_droplet, DropletAssets, DropletSize};
+
+// Launches start this far above the surface under the cursor
+const LAUNCH_HEIGHT: f32 = 1.0;
+// Launch speed (m/s) per pixel of drag, and the most a drag can give
+const SPEED_PER_PIXEL: f32 = 0.04;
+const MAX_LAUNCH_SPEED: f32 = 15.0;
+// The predicted arc is stepped at this interval (s) for at most `MAX_PREDICTION_SECONDS`
+const PREDICTION_STEP: f32 = 1.0 / 60.0;
+const MAX_PREDICTION_SECONDS: f32 = 5.0;
+const ARC_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+// Q switches the left mouse button from dropping droplets to launching them: press to pick the launch point,
+// drag back like a slingshot to aim (the arc shows where it will go), and release to fire
+#[derive(Resource, Default)]
+pub struct LaunchMode {
+    pub enabled: bool,
+    aim: Option<Aim>,
+}
+
+struct Aim {
+    origin: Vec3,
+    // Cursor position (px) where the drag started
+    pressed: Vec2,
+    velocity: Vec3,
+}
+
+pub fn launch_mode_off(mode: Res<LaunchMode>) -> bool {
+    !mode.enabled
+}
+
+pub fn toggle_launch_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut mode: ResMut<LaunchMode>,
+    turntable: Res<Turntable>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+) {
+    if bindings.just_pressed(Action::LaunchMode, &keys) {
+        mode.enabled = !mode.enabled;
+        // Leaving mid-aim drops the shot and hands the mouse back to the camera, unless the turntable has it
+        if mode.aim.take().is_some() {
+            for mut orbit in cameras.iter_mut() {
+                orbit.enabled = !turntable.enabled;
+            }
+        }
+        info!("Launch mode {}", if mode.enabled { "on" } else { "off" });
+    }
+}
+
+// Where a droplet launched at `velocity` from `origin` will go, until it reaches the floor.
+// Steps the same gravity and linear damping the physics applies, so the arc matches the flight.
+pub fn predict_trajectory(
+    origin: Vec3,
+    velocity: Vec3,
+    gravity: Vec3,
+    linear_damping: f32,
+    floor_height: impl Fn(Vec2) -> f32,
+) -> Vec<Vec3> {
+    let mut position = origin;
+    let mut velocity = velocity;
+    let mut points = vec![position];
+    let steps = (MAX_PREDICTION_SECONDS / PREDICTION_STEP) as usize;
+    for _ in 0..steps {
+        velocity = (velocity + gravity * PREDICTION_STEP) / (1.0 + PREDICTION_STEP * linear_damping);
+        position += velocity * PREDICTION_STEP;
+        points.push(position);
+        if position.y <= floor_height(position.xz()) {
+            break;
+        }
+    }
+    points
+}
+
+// Picks the launch point and aims. Presses over the panel are the panel's, and the turntable keeps the camera, so
+// this only runs outside the panel with the turntable off; the release is left to `fire_launch`.
+#[allow(clippy::too_many_arguments)]
+pub fn aim_and_launch(
+    mut mode: ResMut<LaunchMode>,
+    mut gizmos: Gizmos,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    mut cameras: Query<(&Camera, &GlobalTransform, &mut PanOrbitCamera)>,
+    rapier_context: Res<RapierContext>,
+    rapier_config: Res<RapierConfiguration>,
+    terrain: Res<TerrainSettings>,
+    droplet_size: Res<DropletSize>,
+    viscosity: Res<Viscosity>,
+) {
+    if !mode.enabled {
+        return;
+    }
+    let Ok(window) = windows.get_single() else { return };
+    let Some(cursor) = window.cursor_position() else { return };
+    let Ok((camera, camera_transform, mut orbit)) = cameras.get_single_mut() else { return };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else { return };
+        // Only the static scene counts, not droplets or particles in the way
+        let filter = QueryFilter::only_fixed();
+        if let Some((_, toi)) = rapier_context.cast_ray(ray.origin, *ray.direction, f32::MAX, true, filter) {
+            let origin = ray.get_point(toi) + Vec3::Y * LAUNCH_HEIGHT;
+            mode.aim = Some(Aim { origin, pressed: cursor, velocity: Vec3::ZERO });
+            // The drag aims instead of orbiting the camera
+            orbit.enabled = false;
+        }
+    }
+    let Some(aim) = mode.aim.as_mut() else { return };
+
+    // Pulling back fires the other way: left on screen launches right, down launches up
+    let drag = cursor - aim.pressed;
+    let right = camera_transform.right().with_y(0.0).normalize_or_zero();
+    aim.velocity = ((-drag.x * right + drag.y * Vec3::Y) * SPEED_PER_PIXEL).clamp_length_max(MAX_LAUNCH_SPEED);
+
+    let height = terrain.sampler();
+    let arc = predict_trajectory(aim.origin, aim.velocity, rapier_config.gravity, viscosity.linear_damping(), height);
+    gizmos.linestrip(arc, ARC_COLOR);
+    gizmos.sphere(aim.origin, Quat::IDENTITY, droplet_size.0, ARC_COLOR);
+}
+
+// Fires on the release wherever it happens, outside the window or over the panel too, with the aim from the last
+// frame the cursor was over the scene. Otherwise the shot would never go and the camera would stay locked.
+#[allow(clippy::too_many_arguments)]
+pub fn fire_launch(
+    mut commands: Commands,
+    mut mode: ResMut<LaunchMode>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    turntable: Res<Turntable>,
+    mut cameras: Query<&mut PanOrbitCamera>,
+    droplet_assets: Res<DropletAssets>,
+    droplet_size: Res<DropletSize>,
+    viscosity: Res<Viscosity>,
+) {
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(aim) = mode.aim.take() else { return };
+
+    let droplet = spawn_droplet(&mut commands, aim.origin, droplet_size.0, &droplet_assets, &viscosity);
+    commands.entity(droplet).insert(Velocity::linear(aim.velocity));
+    for mut orbit in cameras.iter_mut() {
+        orbit.enabled = !turntable.enabled;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicted_arc_peaks_and_lands_where_the_physics_says() {
+        let gravity = Vec3::new(0.0, -9.81, 0.0);
+        let velocity = Vec3::new(3.0, 8.0, 0.0);
+        let arc = predict_trajectory(Vec3::ZERO, velocity, gravity, 0.0, |_| 0.0);
+
+        let apex = arc.iter().map(|point| point.y).fold(f32::MIN, f32::max);
+        let expected_apex = velocity.y * velocity.y / (2.0 * 9.81);
+        assert!((apex - expected_apex).abs() < 0.1, "apex {apex} should be near {expected_apex}");
+        let landing = arc.last().unwrap();
+        let expected_range = velocity.x * 2.0 * velocity.y / 9.81;
+        assert!((landing.x - expected_range).abs() < 0.2, "landed at {landing}, expected x near {expected_range}");
+
+        // Stronger gravity pulls the arc in
+        let heavy = predict_trajectory(Vec3::ZERO, velocity, gravity * 2.0, 0.0, |_| 0.0);
+        assert!(heavy.last().unwrap().x < landing.x);
+    }
+}
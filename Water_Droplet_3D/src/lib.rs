@@ -0,0 +1,2147 @@
+//! A water droplet that falls, wobbles and splashes, on Bevy and Rapier. `WaterDropletPlugin` is the droplet itself,
+//! to drop into a scene of your own; `EnvironmentPlugin` and `SandboxPlugin` add the floor, sky and controls the
+//! `water_droplet_renderer` binary runs with.
+
+use bevy::prelude::*;
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin, PanOrbitCameraSystemSet};
+use bevy_rapier3d::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::{PI, TAU};
+
+mod audio;
+mod bloom;
+mod camera;
+pub mod cli;
+mod coalesce;
+mod cohesion;
+mod daynight;
+mod droplet_color;
+mod environment;
+mod floor;
+mod fog;
+mod freeze;
+mod gravity;
+mod grid;
+pub mod headless;
+mod help;
+mod hud;
+#[cfg(feature = "inspector")]
+mod inspector;
+mod keybindings;
+mod launch;
+mod liquid;
+mod metaballs;
+#[cfg(feature = "hanabi")]
+mod mist;
+mod obstacles;
+mod pool;
+mod puddle;
+mod rain;
+mod ramp;
+mod recording;
+mod ripple;
+mod scene_config;
+mod screenshot;
+mod secondary_splash;
+mod simulation;
+mod skybox;
+mod snapshot;
+mod splash_height;
+mod split;
+mod ssao;
+mod surface_tension;
+mod terrain;
+#[cfg(not(feature = "cpu_wobble"))]
+mod surface_ripple;
+mod trail;
+mod trajectory_log;
+mod tuning;
+#[cfg(feature = "egui")]
+mod tuning_panel;
+mod water_pool;
+mod wetness;
+mod wind;
+
+use keybindings::{Action, KeyBindings};
+use liquid::CurrentLiquid;
+use simulation::simulation_running;
+
+pub use audio::listener as audio_listener;
+pub use tuning::Viscosity;
+
+// Droplets ripple through a vertex shader, unless `cpu_wobble` swaps it for the plain material and a scaling wobble
+#[cfg(not(feature = "cpu_wobble"))]
+use surface_ripple::{plugin as droplet_surface_plugin, DropletMaterial};
+#[cfg(feature = "cpu_wobble")]
+type DropletMaterial = StandardMaterial;
+#[cfg(feature = "cpu_wobble")]
+fn droplet_surface_plugin(_app: &mut App) {}
+
+// A GPU spray burst on each splash when built with `hanabi`; without it the rigid particles are the whole splash
+#[cfg(feature = "hanabi")]
+use mist::plugin as mist_plugin;
+#[cfg(not(feature = "hanabi"))]
+fn mist_plugin(_app: &mut App) {}
+
+// The F1 tuning panel needs egui, which only comes with the `egui` feature
+#[cfg(feature = "egui")]
+use tuning_panel::{plugin as tuning_panel_plugin, pointer_outside_panel};
+#[cfg(not(feature = "egui"))]
+fn tuning_panel_plugin(_app: &mut App) {}
+#[cfg(not(feature = "egui"))]
+fn pointer_outside_panel() -> bool {
+    true
+}
+
+// The F12 world inspector, only built with `inspector`
+#[cfg(feature = "inspector")]
+use inspector::plugin as inspector_plugin;
+#[cfg(not(feature = "inspector"))]
+fn inspector_plugin(_app: &mut App) {}
+
+/// The droplet, its wobble, its splashes and R to drop it again, with the physics they run on. Added to an app with
+/// `DefaultPlugins`, a camera and a light, it drops a droplet into a scene of your own. The physics steps in
+/// `FixedUpdate`, at whatever fixed timestep the app has.
+#[derive(Default)]
+pub struct WaterDropletPlugin {
+    /// Replays a run's random numbers, which are random otherwise
+    pub seed: Option<u64>,
+    /// Takes the droplet, splash and physics settings from `assets/scene.ron` in the working directory, if there is
+    /// one, and puts the app's fixed timestep on the scene's. Left off, the built-in settings are used and the app's
+    /// clocks are left alone.
+    pub scene_file: bool,
+}
+
+impl Plugin for WaterDropletPlugin {
+    fn build(&self, app: &mut App) {
+        if self.scene_file {
+            app.add_plugins(scene_file_plugin);
+        }
+        app.add_plugins(simulation_plugin)
+            .add_plugins(droplet_surface_plugin)
+            .insert_resource(SimulationRng::from_seed_or_random(self.seed))
+            // For R; `SandboxPlugin` swaps in the ones from `assets/keybindings.ron`
+            .init_resource::<KeyBindings>()
+            .add_systems(Startup, setup_droplet)
+            // The droplet shape compares `ImpactVelocity`, from before the latest physics step, with the velocity
+            // after it to spot landings
+            .add_systems(Update, animate_droplet.run_if(simulation_running));
+    }
+}
+
+/// The floor the droplet lands on, with its checkerboard, and the sun and sky dome over it. The sky and the light it
+/// reflects go on whichever 3D cameras are in by `PostStartup`.
+pub struct EnvironmentPlugin;
+
+impl Plugin for EnvironmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<skybox::StackedCubemaps>()
+            .init_resource::<environment::EnvironmentSettings>()
+            .add_systems(Startup, setup_environment)
+            // Once the camera is in
+            .add_systems(PostStartup, (skybox::setup_skybox, environment::setup_environment_map))
+            .add_systems(Update, (skybox::finish_loading_cubemaps, environment::update_environment_intensity));
+    }
+}
+
+/// Everything the app does on top of the droplet and its scene: the keys, panels and overlays, rain, sound, camera
+/// controls and the rest. It builds on `EnvironmentPlugin` and `WaterDropletPlugin`, and watches the scene file for
+/// edits when `scene_file` is on. Options in a `Cli` resource, if the app has one, go over the edited file too.
+pub struct SandboxPlugin;
+
+impl Plugin for SandboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(snapshot::plugin)
+            .add_plugins(tuning_panel_plugin)
+            // After the panel, so both share its `EguiPlugin`
+            .add_plugins(inspector_plugin)
+            .add_plugins(PanOrbitCameraPlugin)
+            .add_plugins(bevy::diagnostic::FrameTimeDiagnosticsPlugin)
+            .add_plugins(mist_plugin)
+            // .add_plugins(RapierDebugRenderPlugin::default()) // Uncomment for debugging
+            .init_resource::<launch::LaunchMode>()
+            .init_resource::<recording::Recording>()
+            .init_resource::<splash_height::MaxSplashHeight>()
+            .init_resource::<screenshot::ScreenshotQueue>()
+            .init_resource::<screenshot::OverlaysHidden>()
+            .init_resource::<droplet_color::DropletDye>()
+            .init_resource::<rain::RainSettings>()
+            .init_resource::<simulation::TimeScale>()
+            .init_resource::<camera::CameraBookmarks>()
+            .init_resource::<camera::CameraFollow>()
+            .init_resource::<camera::Turntable>()
+            .init_resource::<floor::CurrentFloorPattern>()
+            .init_resource::<grid::GridOverlay>()
+            .init_resource::<bloom::BloomConfig>()
+            .init_resource::<ssao::SsaoConfig>()
+            .init_resource::<fog::FogConfig>()
+            .init_resource::<cohesion::CohesionSettings>()
+            .init_resource::<cohesion::SpatialHash>()
+            .init_resource::<metaballs::MetaballSettings>()
+            .init_resource::<wind::Wind>()
+            .init_resource::<audio::PatterWindow>()
+            .init_resource::<audio::RainLoop>()
+            .init_resource::<audio::AudioSettings>()
+            .add_systems(PreStartup, keybindings::load_key_bindings)
+            .add_systems(
+                Startup,
+                (
+                    hud::setup_hud,
+                    splash_height::setup_splash_height,
+                    help::setup_help,
+                    audio::setup_audio,
+                    audio::setup_volume_overlay,
+                    screenshot::setup_screenshot_notice,
+                    recording::setup_recording_indicator,
+                    trail::setup_trail,
+                    ramp::setup_ramp,
+                    obstacles::setup_obstacle_assets,
+                    obstacles::spawn_obstacle_scene,
+                    water_pool::setup_water_pool,
+                ),
+            )
+            .add_systems(Update, (bloom::toggle_bloom, bloom::apply_bloom).chain())
+            .add_systems(Update, (ssao::control_ssao, ssao::apply_ssao).chain())
+            // After the day/night cycle has picked this frame's sky colour
+            .add_systems(Update, (fog::toggle_fog, fog::apply_fog).chain().after(daynight::cycle_sun))
+            .add_systems(Update, animate_light.run_if(simulation_running))
+            .add_systems(Update, (daynight::toggle_day_night, daynight::cycle_sun.run_if(simulation_running)))
+            .add_systems(
+                Update,
+                (
+                    spawn_droplet_at_cursor.run_if(pointer_outside_panel).run_if(launch::launch_mode_off),
+                    despawn_drop_markers,
+                    spawn_extra_droplet,
+                    resize_droplet,
+                    gravity::cycle_gravity,
+                    liquid::cycle_liquid,
+                ),
+            )
+            .add_systems(Update, (simulation::control_simulation, simulation::control_time_scale))
+            // A new drop height has to be in place before the reset it triggers
+            .add_systems(
+                Update,
+                (
+                    tuning::adjust_bounciness,
+                    tuning::adjust_viscosity,
+                    tuning::apply_droplet_tuning,
+                    tuning::apply_drop_height.before(reset_droplet),
+                )
+                    .chain(),
+            )
+            // An edited scene file is applied before the tuning it changes is put on the droplets, and before the reset
+            .add_systems(
+                Update,
+                (
+                    scene_config::watch_scene_config.run_if(resource_exists::<scene_config::SceneConfigWatcher>),
+                    (scene_config::apply_scene_config, simulation::apply_timestep, scene_config::apply_reloaded_scene)
+                        .chain()
+                        .run_if(on_event::<scene_config::SceneConfigReloaded>()),
+                )
+                    .chain()
+                    .before(tuning::apply_droplet_tuning)
+                    .before(reset_droplet),
+            )
+            .add_systems(Update, reseed_and_replay.before(reset_droplet))
+            // A new drop point has to be in place before the reset it triggers
+            .add_systems(Update, (ramp::control_ramp, ramp::apply_ramp_settings).chain().before(reset_droplet))
+            // Colours mix before merging despawns the droplets that touched
+            .add_systems(FixedUpdate, droplet_color::mix_droplet_colors.before(coalesce::merge_droplets))
+            // This frame's splashes, from however many steps it took, ahead of splitting hiding or despawning the
+            // droplets that threw them
+            .add_systems(
+                Update,
+                (ripple::spawn_ripple, puddle::accumulate_puddles, wetness::wet_floor, rain::despawn_splashed_raindrops)
+                    .chain()
+                    .before(split::split_on_impact),
+            )
+            .add_systems(
+                Update,
+                (droplet_color::cycle_dye, droplet_color::dye_new_droplets, droplet_color::apply_droplet_colors)
+                    .chain()
+                    // A restored droplet has its dye before it has a material to show it on
+                    .after(snapshot::rebuild_restored),
+            )
+            .add_systems(Update, (puddle::clear_puddles, floor::cycle_floor_pattern, floor::resize_floor))
+            .add_systems(Update, water_pool::fit_pool_to_floor.after(floor::resize_floor))
+            .add_systems(Update, (obstacles::spawn_obstacle, obstacles::clear_obstacles))
+            .add_systems(Update, wetness::dry_floor.run_if(simulation_running))
+            .add_systems(Update, (wind::control_wind, wind::apply_wind).chain())
+            .add_systems(Update, (grid::toggle_grid, grid::draw_grid).chain())
+            .add_systems(
+                Update,
+                (cohesion::toggle_cohesion, cohesion::apply_cohesion.run_if(simulation_running)).chain(),
+            )
+            // After the frame's particles have been released or merged, so no blob is built over a particle that's gone
+            .add_systems(
+                Update,
+                (metaballs::toggle_metaballs, metaballs::update_metaballs)
+                    .chain()
+                    .after(surface_tension::merge_resting_particles),
+            )
+            .add_systems(Update, (rain::toggle_rain, rain::spawn_raindrops.run_if(simulation_running)).chain())
+            .add_systems(Update, (hud::toggle_hud, hud::update_hud, adjust_particle_budget))
+            .add_systems(Update, fade_expiring_particles.after(tick_particle_lifetime))
+            // Once this frame's particles have launched and the spent ones are gone
+            .add_systems(
+                Update,
+                (splash_height::track_splash_height, splash_height::update_splash_height)
+                    .chain()
+                    .after(tick_particle_lifetime),
+            )
+            // A recording drops the droplets again and captures the frame that starts from, ahead of any F2 screenshot
+            .add_systems(
+                Update,
+                (
+                    (recording::start_recording, recording::toggle_recording).chain().before(reset_droplet),
+                    (recording::capture_frame, recording::update_recording_indicator)
+                        .chain()
+                        .after(reset_droplet)
+                        .before(screenshot::capture_queued_screenshot),
+                ),
+            )
+            .add_systems(
+                Update,
+                (screenshot::take_screenshot, screenshot::show_screenshot_notice, screenshot::capture_queued_screenshot)
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (snapshot::save_snapshot, snapshot::restore_snapshot, snapshot::rebuild_restored).chain(),
+            )
+            .add_systems(Update, (help::toggle_help, help::update_help))
+            .add_systems(Update, (trail::spawn_trail, trail::fade_trail).run_if(simulation_running))
+            .add_systems(
+                Update,
+                (
+                    audio::control_audio,
+                    audio::apply_audio_settings,
+                    audio::show_volume,
+                    (
+                        audio::play_splash_sound,
+                        audio::play_patter_sound,
+                        audio::fade_rain_loop.after(rain::toggle_rain),
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    camera::camera_bookmarks,
+                    camera::animate_camera_transition,
+                    camera::toggle_follow,
+                    camera::follow_droplet,
+                    // After bookmarks and following, so it wins over them while it's on
+                    (camera::toggle_turntable, camera::turn_turntable).chain(),
+                    launch::toggle_launch_mode,
+                    launch::aim_and_launch.run_if(pointer_outside_panel).run_if(camera::turntable_off),
+                    launch::fire_launch,
+                )
+                    .chain()
+                    .before(PanOrbitCameraSystemSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    ripple::animate_ripples.run_if(simulation_running),
+                    // Last, to catch every particle launched this frame
+                    (freeze::toggle_freeze, freeze::freeze_particles).chain(),
+                )
+                    .after(tick_particle_lifetime),
+            );
+    }
+}
+
+// The droplet, its splashes and the physics they run on, which both the window and `--headless` build on. Nothing
+// here draws anything or reads a window.
+fn simulation_plugin(app: &mut App) {
+    // Physics steps on its own fixed clock, however fast the frames come; `simulation::apply_timestep` sets how often.
+    // Set up front so Rapier starts out on a fixed step.
+    app.insert_resource(RapierConfiguration {
+        timestep_mode: simulation::fixed_step(simulation::DEFAULT_TIMESTEP),
+        ..RapierConfiguration::new(1.0)
+    })
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule())
+        .init_resource::<SplashThreshold>()
+        .init_resource::<SplashConfig>()
+        .init_resource::<DropletSize>()
+        .init_resource::<tuning::DropletTuning>()
+        .init_resource::<tuning::Bounciness>()
+        .init_resource::<tuning::Viscosity>()
+        .init_resource::<ParticleLifetimeSettings>()
+        .init_resource::<ParticleBudget>()
+        .init_resource::<pool::ParticlePool>()
+        .init_resource::<gravity::GravityPreset>()
+        .init_resource::<CurrentLiquid>()
+        .init_resource::<simulation::SimState>()
+        // The built-in scene, unless `scene_file_plugin` reads one in first
+        .init_resource::<scene_config::SceneConfig>()
+        .init_resource::<freeze::FrozenParticles>()
+        .init_resource::<terrain::TerrainSettings>()
+        .init_resource::<floor::FloorSize>()
+        // Set from the scene file along with the rest
+        .init_resource::<daynight::DayNightSettings>()
+        .init_resource::<camera::TurntableSettings>()
+        .init_resource::<split::SplitThreshold>()
+        .init_resource::<ramp::RampSettings>()
+        .add_event::<SplashEvent>()
+        .add_event::<secondary_splash::ParticleSplashEvent>()
+        .add_event::<ResetDroplets>()
+        .add_event::<scene_config::SceneConfigReloaded>()
+        .add_systems(PreStartup, scene_config::apply_scene_config)
+        .add_systems(FixedUpdate, simulation::gate_physics_step.before(PhysicsSet::SyncBackend))
+        .add_systems(Startup, trajectory_log::open_trajectory_log)
+        .add_systems(
+            FixedUpdate,
+            trajectory_log::log_droplet_steps
+                .after(PhysicsSet::Writeback)
+                .run_if(resource_exists::<trajectory_log::TrajectoryLog>),
+        )
+        .add_systems(
+            Last,
+            trajectory_log::flush_trajectory_log.run_if(resource_exists::<trajectory_log::TrajectoryLog>),
+        )
+        // Ahead of each physics step, reading the collisions from the one before. Landings are judged by the velocity
+        // from before that step, so it's only updated after.
+        .add_systems(
+            FixedUpdate,
+            (
+                (splash_on_impact, track_impact_velocity, spawn_splash).chain(),
+                animate_squash.after(spawn_splash).run_if(simulation_running),
+                coalesce::merge_droplets.before(splash_on_impact),
+                (water_pool::splash_into_water, secondary_splash::splash_landed_particles)
+                    .chain()
+                    .after(splash_on_impact)
+                    .before(track_impact_velocity),
+            )
+                .before(PhysicsSet::SyncBackend),
+        )
+        .add_systems(Update, split::split_on_impact.before(reset_droplet))
+        .add_systems(Update, water_pool::apply_buoyancy.run_if(simulation_running))
+        .add_systems(Update, obstacles::attach_mesh_colliders)
+        // Reset runs first so its despawns are applied before the lifetime checks see the same entities.
+        // All of these return particles to the pool or despawn droplets. The frame's physics steps, and the
+        // splashes with them, are over by `Update`: a particle is never released and relaunched in the same
+        // frame, and a droplet is never despawned twice.
+        .add_systems(
+            Update,
+            (
+                reset_droplet,
+                despawn_out_of_bounds,
+                enforce_particle_budget,
+                surface_tension::merge_resting_particles.run_if(simulation_running),
+                tick_particle_lifetime.run_if(simulation_running),
+            )
+                .chain(),
+        );
+}
+
+// `assets/scene.ron`, read before the scene is built, and the fixed timestep the physics steps at taken from it
+fn scene_file_plugin(app: &mut App) {
+    app.add_systems(
+        PreStartup,
+        (scene_config::load_scene_config, simulation::apply_timestep)
+            .chain()
+            .before(scene_config::apply_scene_config),
+    );
+}
+
+fn setup_environment(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    scene: Res<scene_config::SceneConfig>,
+    terrain: Res<terrain::TerrainSettings>,
+    floor_size: Res<floor::FloorSize>,
+) {
+    commands.insert_resource(ClearColor(scene.noon_sky()));
+
+    // Main Light (Sun-like), moved across the sky by `daynight::cycle_sun`
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: scene.illuminance,
+                shadows_enabled: scene.shadows,
+                ..default()
+            },
+            transform: Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -1.0, -0.5, 0.0)),
+            ..default()
+        },
+        daynight::Sun,
+    ));
+    
+    // Ambient Light (Soft fill)
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 500.0,
+    });
+
+    // Floor (Checkerboard pattern would be nice, but simple light gray for now to show shadows)
+    // Flat, so it would poke through the terrain's valleys
+    if !terrain.enabled {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(floor::plane_mesh(floor_size.0)),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.8, 0.8, 0.8),
+                    perceptual_roughness: 0.5,
+                    reflectance: 0.2,
+                    ..default()
+                }),
+                ..default()
+            },
+            floor::Floor,
+        ));
+    }
+
+    // Floor with Checkerboard Pattern
+    let checkerboard = create_checkerboard_image(floor_size.tile_pixels());
+    let dry_pixels = checkerboard.data.clone();
+    let checkerboard = images.add(checkerboard);
+    // Splashes darken the texture in place where they land
+    commands.insert_resource(wetness::FloorWetness::new(
+        checkerboard.clone(),
+        dry_pixels,
+        FLOOR_TEXTURE_SIZE,
+        floor_size.0,
+    ));
+    let debug_material = materials.add(StandardMaterial {
+        base_color_texture: Some(checkerboard),
+        normal_map_texture: Some(images.add(floor::create_tile_normal_map(FLOOR_NORMAL_STRENGTH, floor_size.tile_pixels()))),
+        perceptual_roughness: 0.8,
+        reflectance: 0.2,
+        ..default()
+    });
+
+    let (floor_mesh, floor_collider) = floor::floor_shape(floor_size.0, &terrain);
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(floor_mesh),
+            material: debug_material,
+            ..default()
+        },
+        RigidBody::Fixed,
+        floor_collider,
+        floor::Floor,
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn setup_droplet(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    #[cfg(not(feature = "cpu_wobble"))] mut droplet_materials: ResMut<Assets<DropletMaterial>>,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    liquid: Res<CurrentLiquid>,
+    rng: Res<SimulationRng>,
+    scene: Res<scene_config::SceneConfig>,
+    viscosity: Res<tuning::Viscosity>,
+) {
+    info!("Simulation seed: {} (pass --seed {} to replay)", rng.seed, rng.seed);
+
+    // Water Droplet
+    let droplet_material = liquid.0.material(liquid::DROPLET_THICKNESS);
+    #[cfg(not(feature = "cpu_wobble"))]
+    let surface_materials = surface_ripple::surface_materials(&droplet_material, &mut droplet_materials);
+    let material = materials.add(droplet_material);
+    #[cfg(feature = "cpu_wobble")]
+    let surface_materials = vec![material.clone()];
+    let droplet_assets = DropletAssets {
+        mesh: meshes.add(Mesh::from(Sphere::new(1.0))),
+        material,
+        surface_materials,
+    };
+    let start = Vec3::from(scene.droplet_position);
+    let droplet = spawn_droplet(&mut commands, start, scene.droplet_radius, &droplet_assets, &viscosity);
+    commands.entity(droplet).insert(PrimaryDroplet);
+    commands.insert_resource(droplet_assets);
+
+    // Everything a splash spawns is built once here and cloned per splash
+    let particle_material = liquid.0.material(liquid::PARTICLE_THICKNESS);
+    let splash_assets = SplashAssets {
+        particle_mesh: meshes.add(Mesh::from(Sphere::new(0.1))),
+        particle_fade_materials: particle_fade_steps(&particle_material).map(|faded| materials.add(faded)).collect(),
+        particle_material: materials.add(particle_material),
+        ripple_mesh: meshes.add(Annulus::new(0.92, 1.0)),
+        ripple_materials: ripple::fade_materials(&mut materials),
+        puddle_mesh: meshes.add(Circle::new(1.0)),
+        puddle_material: materials.add(liquid.0.material(liquid::PUDDLE_THICKNESS)),
+    };
+    particle_pool.fill(&mut commands, &splash_assets);
+    commands.insert_resource(splash_assets);
+}
+
+/// Shared mesh and material for every droplet, so spawning more of them doesn't add assets.
+/// The mesh is a unit sphere; each droplet is scaled to its own radius.
+/// `material` is the one to edit; droplets are drawn with `surface_materials`, which follow it.
+#[derive(Resource)]
+pub struct DropletAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    // From full ripples down to a still surface
+    surface_materials: Vec<Handle<DropletMaterial>>,
+}
+
+const DROPLET_RADIUS: f32 = 0.5;
+const MIN_DROPLET_RADIUS: f32 = 0.2;
+const MAX_DROPLET_RADIUS: f32 = 1.5;
+const DROPLET_RADIUS_STEP: f32 = 0.1;
+
+/// Radius for new droplets, including the primary one. Z and X shrink and grow it.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DropletSize(pub f32);
+
+impl Default for DropletSize {
+    fn default() -> Self {
+        Self(DROPLET_RADIUS)
+    }
+}
+
+// The sizes 1, 2 and 3 pick for new droplets, and how hard each splashes: how many particles it throws (for the
+// default 20-particle splash; a scene's own count scales them all) and how far they spread
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SizeTier {
+    Small,
+    Medium,
+    Large,
+}
+
+impl SizeTier {
+    const ALL: [SizeTier; 3] = [SizeTier::Small, SizeTier::Medium, SizeTier::Large];
+
+    fn radius(self) -> f32 {
+        match self {
+            SizeTier::Small => 0.3,
+            SizeTier::Medium => DROPLET_RADIUS,
+            SizeTier::Large => 0.8,
+        }
+    }
+
+    fn particles(self) -> usize {
+        match self {
+            SizeTier::Small => 8,
+            SizeTier::Medium => 20,
+            SizeTier::Large => 40,
+        }
+    }
+
+    fn spread(self) -> f32 {
+        match self {
+            SizeTier::Small => 0.75,
+            SizeTier::Medium => 1.0,
+            SizeTier::Large => 1.3,
+        }
+    }
+
+    fn action(self) -> Action {
+        match self {
+            SizeTier::Small => Action::SmallDroplets,
+            SizeTier::Medium => Action::MediumDroplets,
+            SizeTier::Large => Action::LargeDroplets,
+        }
+    }
+
+    // How a droplet of `radius` splashes next to a medium one, as (particle count, spread) multipliers. Sizes
+    // between the tiers, from Z and X or broken-off fragments, fall on straight lines between them, so a heavier
+    // droplet always splashes harder. Past the large tier the line carries on; below the small one the count
+    // shrinks with the radius.
+    fn splash_scale(radius: f32) -> (f32, f32) {
+        let count = |tier: SizeTier| tier.particles() as f32 / SizeTier::Medium.particles() as f32;
+        let small = SizeTier::Small;
+        if radius < small.radius() {
+            return (count(small) * radius / small.radius(), small.spread());
+        }
+        let medium = SizeTier::Medium;
+        let (a, b) = if radius < medium.radius() { (small, medium) } else { (medium, SizeTier::Large) };
+        let t = (radius - a.radius()) / (b.radius() - a.radius());
+        (count(a).lerp(count(b), t), a.spread().lerp(b.spread(), t))
+    }
+}
+
+// Resizes the primary droplet and drops it again, so the new size can be seen from the start. Z and X step the
+// radius, and 1, 2 and 3 jump to a size tier.
+fn resize_droplet(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut size: ResMut<DropletSize>,
+    mut primary: Query<&mut DropletRadius, With<PrimaryDroplet>>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    let tier = SizeTier::ALL.into_iter().find(|tier| bindings.just_pressed(tier.action(), &keys));
+    size.0 = if bindings.just_pressed(Action::GrowDroplet, &keys) {
+        (size.0 + DROPLET_RADIUS_STEP).clamp(MIN_DROPLET_RADIUS, MAX_DROPLET_RADIUS)
+    } else if bindings.just_pressed(Action::ShrinkDroplet, &keys) {
+        (size.0 - DROPLET_RADIUS_STEP).clamp(MIN_DROPLET_RADIUS, MAX_DROPLET_RADIUS)
+    } else if let Some(tier) = tier {
+        info!("{tier:?} droplets");
+        tier.radius()
+    } else {
+        return;
+    };
+
+    info!("Droplet radius: {:.1}", size.0);
+    for mut radius in primary.iter_mut() {
+        radius.0 = size.0;
+    }
+    resets.send(ResetDroplets);
+}
+
+/// Droplets are drawn and collide as a unit sphere scaled by this, so every other change to the
+/// droplet's scale (wobble, flatten, reset) is relative to it
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct DropletRadius(pub f32);
+
+/// The droplet created at startup, which is the only one R keeps around
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct PrimaryDroplet;
+
+// Shared handles for splash particles and ripples, so repeated splashes don't keep adding assets.
+//
+// Sharing the handles is also what keeps particles cheap to draw. Bevy batches neighbouring
+// phase items that use the same mesh, material and pipeline into a single instanced draw, with
+// each entity's transform written into the per-instance mesh uniform buffer during extraction.
+// Rapier writes particle `Transform`s every step and transform propagation feeds them into that
+// buffer, so the particles stay ordinary rigid bodies with no extra syncing. The liquid's
+// transmission puts particles in the sorted transmissive pass, where a batch only breaks when a
+// droplet or other transmissive mesh sorts between two particles.
+// Anything that gives a particle its own material or mesh would split it out of the batch.
+// None of this has been measured: it follows from how Bevy batches, not from a draw-call count.
+#[derive(Resource)]
+struct SplashAssets {
+    particle_mesh: Handle<Mesh>,
+    particle_material: Handle<StandardMaterial>,
+    // Blended copies of `particle_material` for each step of a particle's fade; see `particle_fade_steps`
+    particle_fade_materials: Vec<Handle<StandardMaterial>>,
+    ripple_mesh: Handle<Mesh>,
+    // One material per fade step, from fully visible to almost gone
+    ripple_materials: Vec<Handle<StandardMaterial>>,
+    puddle_mesh: Handle<Mesh>,
+    puddle_material: Handle<StandardMaterial>,
+}
+
+/// A droplet of `radius` at `position`, left to fall from there. Droplets other than the first are left where they
+/// are by R.
+pub fn spawn_droplet(
+    commands: &mut Commands,
+    position: Vec3,
+    radius: f32,
+    assets: &DropletAssets,
+    viscosity: &tuning::Viscosity,
+) -> Entity {
+    let droplet = commands
+        .spawn((
+            Transform::from_translation(position).with_scale(Vec3::splat(radius)),
+            Droplet,
+            DropletRadius(radius),
+            SpawnPoint(position),
+            Velocity::zero(), // Explicitly add Velocity so we can query it later
+            ImpactVelocity::default(),
+        ))
+        .id();
+    attach_droplet_body(commands, droplet, assets, viscosity);
+    droplet
+}
+
+// Everything that draws and simulates a droplet, beyond the state of its own that snapshots save. A restored
+// droplet gets it back from here.
+fn attach_droplet_body(
+    commands: &mut Commands,
+    droplet: Entity,
+    assets: &DropletAssets,
+    viscosity: &tuning::Viscosity,
+) {
+    commands.entity(droplet).insert((
+        assets.mesh.clone(),
+        assets.surface_materials[0].clone(),
+        GlobalTransform::default(),
+        VisibilityBundle::default(),
+        RigidBody::Dynamic,
+        Collider::ball(1.0), // Scaled to `radius` along with the transform
+        // The coefficient comes from `Bounciness` once the droplet is in play. `Max` makes it the bounce the
+        // droplet really gets, instead of being averaged with the floor's 0.0.
+        Restitution { coefficient: 0.0, combine_rule: CoefficientCombineRule::Max },
+        // The tuned angular damping follows from `apply_droplet_tuning`
+        Damping { linear_damping: viscosity.linear_damping(), angular_damping: tuning::DEFAULT_ANGULAR_DAMPING },
+        ExternalForce::default(), // Wind
+        Sleeping::default(),
+        ActiveEvents::COLLISION_EVENTS, // Listen for collisions
+    ));
+    #[cfg(not(feature = "cpu_wobble"))]
+    commands.entity(droplet).insert(surface_ripple::SurfaceRipple::default());
+}
+
+// How far above the clicked surface a new droplet is dropped from
+const CURSOR_DROP_HEIGHT: f32 = 5.0;
+// A press and release further apart than this (in pixels) is a camera drag, not a click
+const CLICK_DRAG_TOLERANCE: f32 = 4.0;
+const DROP_MARKER_SECONDS: f32 = 0.4;
+
+// Briefly shows where a clicked droplet is going to land
+#[derive(Component)]
+struct DropMarker(Timer);
+
+// Left click (without dragging, which orbits the camera) drops a droplet onto the clicked spot
+#[allow(clippy::too_many_arguments)]
+fn spawn_droplet_at_cursor(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<PanOrbitCamera>>,
+    rapier_context: Res<RapierContext>,
+    droplet_assets: Res<DropletAssets>,
+    viscosity: Res<tuning::Viscosity>,
+    droplet_size: Res<DropletSize>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut press_position: Local<Option<Vec2>>,
+    mut marker_assets: Local<Option<(Handle<Mesh>, Handle<StandardMaterial>)>>,
+) {
+    let Ok(window) = windows.get_single() else { return };
+    let cursor = window.cursor_position();
+
+    if mouse.just_pressed(MouseButton::Left) {
+        *press_position = cursor;
+    }
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+
+    let (Some(pressed), Some(cursor)) = (press_position.take(), cursor) else { return };
+    if pressed.distance(cursor) > CLICK_DRAG_TOLERANCE {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else { return };
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else { return };
+
+    // Only the static scene counts, not droplets or particles in the way
+    let filter = QueryFilter::only_fixed();
+    if let Some((_, toi)) = rapier_context.cast_ray(ray.origin, *ray.direction, f32::MAX, true, filter) {
+        let hit_point = ray.get_point(toi);
+        let position = hit_point + Vec3::Y * CURSOR_DROP_HEIGHT;
+        spawn_droplet(&mut commands, position, droplet_size.0, &droplet_assets, &viscosity);
+
+        let (mesh, material) = marker_assets.get_or_insert_with(|| {
+            (
+                meshes.add(Mesh::from(Sphere::new(0.15))),
+                materials.add(StandardMaterial {
+                    base_color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                }),
+            )
+        });
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(hit_point),
+                ..default()
+            },
+            DropMarker(Timer::from_seconds(DROP_MARKER_SECONDS, TimerMode::Once)),
+            bevy::pbr::NotShadowCaster,
+        ));
+    }
+}
+
+fn despawn_drop_markers(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut DropMarker)>) {
+    for (entity, mut marker) in query.iter_mut() {
+        if marker.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Space drops another droplet from a random spot near the middle of the floor
+const EXTRA_DROPLET_SPREAD: f32 = 2.0;
+const EXTRA_DROPLET_HEIGHT: f32 = 5.0;
+
+fn spawn_extra_droplet(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    droplet_assets: Res<DropletAssets>,
+    viscosity: Res<tuning::Viscosity>,
+    droplet_size: Res<DropletSize>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    if !bindings.just_pressed(Action::ExtraDroplet, &keys) {
+        return;
+    }
+
+    let x = rng.rng.gen_range(-EXTRA_DROPLET_SPREAD..EXTRA_DROPLET_SPREAD);
+    let z = rng.rng.gen_range(-EXTRA_DROPLET_SPREAD..EXTRA_DROPLET_SPREAD);
+    let position = Vec3::new(x, EXTRA_DROPLET_HEIGHT, z);
+    spawn_droplet(&mut commands, position, droplet_size.0, &droplet_assets, &viscosity);
+}
+
+const FLOOR_TEXTURE_SIZE: usize = 512;
+// How pronounced the raised tiles look; 0.0 is flat
+const FLOOR_NORMAL_STRENGTH: f32 = 0.8;
+
+// `tile_pixels` is the width of one square; see `FloorSize::tile_pixels`
+fn create_checkerboard_image(tile_pixels: f32) -> Image {
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    const TEXTURE_SIZE: usize = FLOOR_TEXTURE_SIZE;
+    let mut palette: [u8; TEXTURE_SIZE * TEXTURE_SIZE * 4] = [0; TEXTURE_SIZE * TEXTURE_SIZE * 4];
+
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            let i = (y * TEXTURE_SIZE + x) * 4;
+            let (column, row) = ((x as f32 / tile_pixels) as usize, (y as f32 / tile_pixels) as usize);
+            let is_white = (column + row) % 2 == 0;
+            let color = if is_white { 255 } else { 150 }; // White and Grey
+
+            palette[i] = color;
+            palette[i + 1] = color;
+            palette[i + 2] = color;
+            palette[i + 3] = 255;
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: TEXTURE_SIZE as u32,
+            height: TEXTURE_SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        palette.to_vec(),
+        TextureFormat::Rgba8UnormSrgb,
+        // Kept in the main world too, so the wetness can be painted into it
+        bevy::render::render_asset::RenderAssetUsages::default(),
+    )
+}
+
+/// Any droplet: the primary one, extra ones dropped by hand or by rain, and the fragments of split ones
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Droplet;
+
+// Where a droplet was originally dropped from, so R can put it back there
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SpawnPoint(Vec3);
+
+#[derive(Component)]
+struct RotateLight;
+
+fn animate_light(
+    time: Res<Time>,
+    mut query: Query<&mut Transform, With<RotateLight>>,
+) {
+    for mut transform in query.iter_mut() {
+        transform.translation = Vec3::new(
+            4.0 * time.elapsed_seconds().cos(),
+            8.0,
+            4.0 * time.elapsed_seconds().sin(),
+        );
+    }
+}
+
+// Falling droplets stretch vertically by this much per m/s, up to `MAX_STRETCH` taller
+const STRETCH_PER_SPEED: f32 = 0.03;
+const MAX_STRETCH: f32 = 0.35;
+// Landings faster than this (m/s) squash the droplet for a moment, by up to `MAX_SQUASH`
+const SQUASH_MIN_SPEED: f32 = 1.0;
+const MAX_SQUASH: f32 = 0.3;
+// How quickly the droplet eases back into shape after a squash
+const SHAPE_RELAX_RATE: f32 = 15.0;
+
+// Taller by `stretch` (or flatter, below 1.0) with the same volume
+fn stretched(stretch: f32) -> Vec3 {
+    let sideways = 1.0 / stretch.sqrt();
+    Vec3::new(sideways, stretch, sideways)
+}
+
+// Without the ripple shader, scaling on each axis stands in for ripples passing through the droplet
+#[cfg(feature = "cpu_wobble")]
+fn idle_wobble(time: &Time) -> Vec3 {
+    let t = time.elapsed_seconds();
+    Vec3::new((t * 5.0).sin(), (t * 4.3).cos(), (t * 3.5).sin()) * 0.02
+}
+
+// The ripple shader moves the surface itself, so the overall shape stays put
+#[cfg(not(feature = "cpu_wobble"))]
+fn idle_wobble(_time: &Time) -> Vec3 {
+    Vec3::ZERO
+}
+
+// Stretches droplets while they fall, squashes them as they land, and otherwise lets them wobble.
+// Splashed droplets are left to `animate_squash`.
+#[allow(clippy::type_complexity)]
+fn animate_droplet(
+    time: Res<Time>,
+    tuning: Res<tuning::DropletTuning>,
+    mut query: Query<(&mut Transform, &DropletRadius, &Velocity, &ImpactVelocity), (With<Droplet>, Without<Squash>)>,
+) {
+    for (mut transform, radius, velocity, last_velocity) in query.iter_mut() {
+        // Lost most of a fast downward speed since last frame, so it just landed
+        let landing_speed = -last_velocity.0.y;
+        if landing_speed > SQUASH_MIN_SPEED && velocity.linvel.y > -0.5 * landing_speed {
+            let squash = 1.0 - (landing_speed * STRETCH_PER_SPEED).min(MAX_SQUASH);
+            transform.scale = radius.0 * stretched(squash);
+            continue;
+        }
+
+        // The faster it falls the more it stretches, and the less it wobbles
+        let stretch = (velocity.linvel.y.abs() * STRETCH_PER_SPEED).min(MAX_STRETCH);
+        let wobble = idle_wobble(&time) * tuning.wobble * (1.0 - stretch / MAX_STRETCH);
+        let target = radius.0 * (stretched(1.0 + stretch) + wobble);
+
+        // Eases out of a squash instead of snapping back
+        let blend = 1.0 - (-SHAPE_RELAX_RATE * time.delta_seconds()).exp();
+        transform.scale = transform.scale.lerp(target, blend);
+    }
+}
+
+/// `splash_depth` counts how many splashes deep a particle is: 0 for particles thrown by a droplet,
+/// 1 for the ones those throw when they land, and so on up to `MAX_SPLASH_DEPTH`.
+/// `spawned_at` (elapsed seconds) lets the particle budget evict the oldest particles first.
+/// `size` scales the shared particle mesh and collider: bigger droplets throw bigger particles,
+/// and resting particles grow as they merge.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SplashParticle {
+    splash_depth: u8,
+    spawned_at: f32,
+    size: f32,
+}
+
+/// A droplet or particle that has splashed already, and won't again until R
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct HasSplashed;
+
+// Seconds a splashing droplet takes to flatten out and recoil, before what's left of it joins the puddle
+const SQUASH_SECONDS: f32 = 0.2;
+// Share of the squash spent flattening; the rest is the rebound
+const SQUASH_FLATTEN_SHARE: f32 = 0.6;
+// How much of the deepest squash the droplet keeps once it has recoiled
+const SQUASH_REST_SHARE: f32 = 0.8;
+
+// A splashed droplet flattening against whatever it hit. Once it has recoiled the droplet is gone,
+// its water already poured into the puddle under it; R brings the primary droplet back.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Squash {
+    elapsed: f32,
+    // 0.0 barely dents the droplet; 1.0 is a full pancake, reached at the reference impact speed
+    amount: f32,
+}
+
+impl Squash {
+    fn new(impact_speed: f32) -> Self {
+        Self { elapsed: 0.0, amount: (impact_speed / REFERENCE_IMPACT_SPEED).min(1.0) }
+    }
+}
+
+// Scale (relative to the round droplet) of a droplet squashed flat by `amount`
+fn squashed(amount: f32) -> Vec3 {
+    Vec3::new(1.0 + amount, 1.0 - 0.9 * amount, 1.0 + amount)
+}
+
+// Shape `t` (0 to 1) of the way through a squash: flattens quickly, overshoots, then springs back a little
+fn squash_shape(amount: f32, t: f32) -> Vec3 {
+    let deepest = squashed(amount);
+    if t < SQUASH_FLATTEN_SHARE {
+        let u = t / SQUASH_FLATTEN_SHARE;
+        // Ease out, so the contact itself is the fastest part
+        return Vec3::ONE.lerp(deepest, 1.0 - (1.0 - u) * (1.0 - u));
+    }
+
+    let rest = squashed(amount * SQUASH_REST_SHARE);
+    let u = ((t - SQUASH_FLATTEN_SHARE) / (1.0 - SQUASH_FLATTEN_SHARE)).min(1.0);
+    // A damped half-wobble from the deepest point that settles on the resting shape
+    rest + (deepest - rest) * (u * 1.5 * PI).cos() * (1.0 - u)
+}
+
+#[allow(clippy::type_complexity)]
+fn animate_squash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut droplets: Query<
+        (Entity, &mut Transform, &DropletRadius, &mut Squash, &mut Velocity, Has<PrimaryDroplet>),
+        Without<RigidBodyDisabled>,
+    >,
+) {
+    for (entity, mut transform, radius, mut squash, mut velocity, is_primary) in droplets.iter_mut() {
+        squash.elapsed += time.delta_seconds();
+        if squash.elapsed < SQUASH_SECONDS {
+            transform.scale = radius.0 * squash_shape(squash.amount, squash.elapsed / SQUASH_SECONDS);
+            continue;
+        }
+
+        // Like a droplet that splits, the primary one is only hidden so R can bring it back
+        if is_primary {
+            *velocity = Velocity::zero();
+            commands.entity(entity).insert((RigidBodyDisabled, Visibility::Hidden));
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// How long a splash particle sticks around before being despawned.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Lifetime(Timer);
+
+/// How long splash particles last, and how they go
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ParticleLifetimeSettings {
+    /// Seconds from launch until the particle goes back to the pool
+    pub seconds: f32,
+    /// Shrink the particle over the last `SHRINK_SECONDS` instead of popping out of existence
+    pub shrink: bool,
+    /// Fade the particle out over the last `FADE_SECONDS`, alongside or instead of the shrink
+    pub fade: bool,
+}
+
+impl Default for ParticleLifetimeSettings {
+    fn default() -> Self {
+        Self { seconds: 3.0, shrink: true, fade: true }
+    }
+}
+
+const SHRINK_SECONDS: f32 = 0.5;
+const FADE_SECONDS: f32 = 0.5;
+// Particles fade by stepping through a fixed set of materials, like ripples, so fading ones still batch together
+const FADE_STEPS: usize = 8;
+
+// The fade materials for particles drawn with `particle_material`, from as opaque as it is to almost gone
+fn particle_fade_steps(particle_material: &StandardMaterial) -> impl Iterator<Item = StandardMaterial> + '_ {
+    let alpha = particle_material.base_color.alpha();
+    (0..FADE_STEPS).map(move |step| {
+        let mut faded = particle_material.clone();
+        faded.alpha_mode = AlphaMode::Blend;
+        faded.base_color.set_alpha(alpha * (1.0 - step as f32 / FADE_STEPS as f32));
+        faded
+    })
+}
+
+/// How many splash particles may be alive at once. When a splash goes over it the oldest
+/// particles are evicted to make room; PageUp/PageDown change it at runtime, up to the pool size.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ParticleBudget {
+    /// Most particles alive at once
+    pub max: usize,
+}
+
+impl Default for ParticleBudget {
+    fn default() -> Self {
+        Self { max: 500 }
+    }
+}
+
+const PARTICLE_BUDGET_STEP: usize = 100;
+
+fn adjust_particle_budget(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut budget: ResMut<ParticleBudget>,
+    particle_pool: Res<pool::ParticlePool>,
+) {
+    let max = if bindings.just_pressed(Action::MoreParticles, &keys) {
+        (budget.max + PARTICLE_BUDGET_STEP).min(particle_pool.size)
+    } else if bindings.just_pressed(Action::FewerParticles, &keys) {
+        budget.max.saturating_sub(PARTICLE_BUDGET_STEP)
+    } else {
+        return;
+    };
+
+    budget.max = max;
+    info!("Particle budget: {max}");
+}
+
+// Returns the oldest particles to the pool until the live count is back within the budget
+fn enforce_particle_budget(
+    mut commands: Commands,
+    budget: Res<ParticleBudget>,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    particles: Query<(Entity, &SplashParticle), Without<RigidBodyDisabled>>,
+) {
+    let excess = particles.iter().len().saturating_sub(budget.max);
+    if excess == 0 {
+        return;
+    }
+
+    let mut by_age: Vec<(Entity, f32)> = particles.iter().map(|(entity, particle)| (entity, particle.spawned_at)).collect();
+    by_age.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for (entity, _) in by_age.into_iter().take(excess) {
+        particle_pool.release(&mut commands, entity);
+    }
+}
+
+/// Impact speed (m/s) the droplet must exceed for a contact to count as a splash.
+/// Slow rolls and resting contacts stay below it.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct SplashThreshold(pub f32);
+
+impl Default for SplashThreshold {
+    fn default() -> Self {
+        Self(3.0)
+    }
+}
+
+// A drop from the default 5m height lands at roughly 8 m/s; splashes are scaled relative to that.
+const REFERENCE_IMPACT_SPEED: f32 = 8.0;
+// Keeps particle speeds sane for very soft or very hard hits
+const MAX_SPLASH_ENERGY_SCALE: f32 = 2.5;
+
+/// The shape of a splash for a reference-speed impact, tunable from the side panel.
+/// `count` particles are thrown, most of them as a crown: evenly around a ring `ring_radius` wide,
+/// leaving at `crown_angle` degrees from vertical with one upward speed picked from
+/// `upward_velocity_range`. The rest (`inner_fraction`) fill the middle, slower and scattered up to
+/// `horizontal_spread` m/s sideways. Harder and softer hits scale all of it.
+#[derive(Resource, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct SplashConfig {
+    /// Particles thrown
+    pub count: usize,
+    /// Most sideways speed (m/s) for the particles in the middle
+    pub horizontal_spread: f32,
+    /// Upward speeds (m/s) the crown's is picked from
+    pub upward_velocity_range: std::ops::Range<f32>,
+    /// Degrees from vertical the crown leaves at
+    pub crown_angle: f32,
+    /// Width (m) of the ring the crown leaves from
+    pub ring_radius: f32,
+    /// Share of the particles thrown from the middle rather than the crown
+    pub inner_fraction: f32,
+}
+
+impl Default for SplashConfig {
+    fn default() -> Self {
+        Self {
+            count: 20,
+            horizontal_spread: 2.0,
+            upward_velocity_range: 2.0..5.0,
+            crown_angle: 35.0,
+            ring_radius: 0.25,
+            inner_fraction: 0.25,
+        }
+    }
+}
+
+// How far crown particles may stray from their even spacing, as a fraction of the gap between them
+const CROWN_ANGLE_JITTER: f32 = 0.3;
+// Crown particles' speeds vary this much around the shared one
+const CROWN_SPEED_JITTER: f32 = 0.1;
+// The inner group is thrown this much slower than the crown
+const INNER_SPEED_SCALE: f32 = 0.5;
+// A droplet that bounces keeps most of its water, so it throws this share of the particles a splat would
+const BOUNCED_PARTICLE_SHARE: f32 = 0.25;
+
+// The droplet's velocity from before the latest physics step.
+// By the time we read a `CollisionEvent::Started`, Rapier has already resolved the contact and
+// `Velocity` is the post-bounce value, so the splash check needs the velocity we had going in.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+struct ImpactVelocity(Vec3);
+
+fn track_impact_velocity(mut query: Query<(&Velocity, &mut ImpactVelocity)>) {
+    for (velocity, mut impact_velocity) in query.iter_mut() {
+        impact_velocity.0 = velocity.linvel;
+    }
+}
+
+/// Fired once per droplet impact that is hard enough to splash.
+/// Anything that wants to react to a splash (particles, sound, ripples...) should read these
+/// instead of re-doing the collision filtering.
+#[derive(Event)]
+pub struct SplashEvent {
+    /// Where the droplet was when it hit
+    pub position: Vec3,
+    /// How fast it was going into the hit (m/s)
+    pub impact_speed: f32,
+    /// The droplet's full velocity going into the hit, so sideways motion can carry into the splash
+    pub impact_velocity: Vec3,
+    /// Points out of the surface that was hit, towards the droplet
+    pub normal: Vec3,
+    /// Landed in a pool rather than on something solid: the droplet floats on instead of squashing
+    pub into_water: bool,
+    /// Rebounded off the surface rather than splatting on it: the droplet throws some spray but flies on whole,
+    /// keeping its water, and can splash again on its next landing
+    pub bounced: bool,
+    /// The droplet that splashed
+    pub droplet: Entity,
+}
+
+// A droplet that comes back off a surface slower than this (m/s) has splatted, not bounced
+const MIN_BOUNCE_SPEED: f32 = 1.5;
+
+// Speed a droplet comes back off a surface at, from how fast it was going into it and its restitution
+fn rebound_speed(impact_velocity: Vec3, normal: Vec3, restitution: f32) -> f32 {
+    (-impact_velocity.dot(normal)).max(0.0) * restitution
+}
+
+// Turns raw collision events into `SplashEvent`s.
+// Every droplet is looked up by the entities in the event, so any number of them can splash independently.
+// Each landing is judged on its own: a hard enough hit splashes, and the droplet either bounces on (and is judged
+// again next time, with whatever speed it has left) or splats and sticks, which ends its flight.
+#[allow(clippy::type_complexity)]
+fn splash_on_impact(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut splash_events: EventWriter<SplashEvent>,
+    // Hidden droplets (broken apart or merged away) are out of play
+    droplet_query: Query<
+        (&Transform, &ImpactVelocity, Option<&Restitution>),
+        (With<Droplet>, Without<HasSplashed>, Without<RigidBodyDisabled>),
+    >,
+    threshold: Res<SplashThreshold>,
+    rapier_context: Res<RapierContext>,
+) {
+    // Several contacts can start in the same frame; only the first one per droplet splashes
+    let mut splashed: Vec<Entity> = Vec::new();
+
+    for event in collision_events.read() {
+        if let CollisionEvent::Started(e1, e2, _) = event {
+            // Either side of the contact may be a droplet
+            for droplet_entity in [*e1, *e2] {
+                if splashed.contains(&droplet_entity) {
+                    continue;
+                }
+                let Ok((transform, impact_velocity, restitution)) = droplet_query.get(droplet_entity) else {
+                    continue;
+                };
+
+                // Only splash if we hit while falling fast enough (to avoid splashing while rolling or resting)
+                let impact_speed = impact_velocity.0.length();
+                if impact_speed > threshold.0 {
+                    splashed.push(droplet_entity);
+                    let normal = surface_normal(&rapier_context, droplet_entity, *e1, *e2);
+                    let restitution = restitution.map_or(0.0, |restitution| restitution.coefficient);
+                    let bounced = rebound_speed(impact_velocity.0, normal, restitution) > MIN_BOUNCE_SPEED;
+                    // A splat is final, so mark it to not splash again
+                    if !bounced {
+                        commands.entity(droplet_entity).insert(HasSplashed);
+                    }
+
+                    splash_events.send(SplashEvent {
+                        position: transform.translation,
+                        impact_speed,
+                        impact_velocity: impact_velocity.0,
+                        normal,
+                        into_water: false,
+                        bounced,
+                        droplet: droplet_entity,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// The normal of the surface a droplet just hit, pointing back out towards the droplet.
+// Falls back to straight up if Rapier has no contact for the pair.
+fn surface_normal(rapier_context: &RapierContext, droplet: Entity, e1: Entity, e2: Entity) -> Vec3 {
+    let Some(pair) = rapier_context.contact_pair(e1, e2) else { return Vec3::Y };
+    let Some(manifold) = pair.manifolds().find(|manifold| manifold.num_points() > 0) else { return Vec3::Y };
+    // Rapier's normal points from the pair's first collider to its second
+    let normal = if pair.collider1() == droplet { -manifold.normal() } else { manifold.normal() };
+    normal.try_normalize().unwrap_or(Vec3::Y)
+}
+
+// Share of a droplet's sideways speed that carries on into the particles thrown forwards / backwards
+const FORWARD_CARRY: f32 = 0.8;
+const BACKWARD_CARRY: f32 = 0.2;
+
+// Motion along the surface that the droplet hands on to a particle launched at `velocity`: a
+// droplet hitting the floor on the move splashes mostly in the direction it was going.
+// A straight drop has nothing to hand on, so its splash stays symmetric.
+fn carried_velocity(velocity: Vec3, impact_velocity: Vec3, normal: Vec3) -> Vec3 {
+    let travel = impact_velocity.reject_from_normalized(normal);
+    let Some(direction) = travel.try_normalize() else { return Vec3::ZERO };
+    let forwardness = velocity.reject_from_normalized(normal).normalize_or_zero().dot(direction);
+    travel * BACKWARD_CARRY.lerp(FORWARD_CARRY, 0.5 + 0.5 * forwardness)
+}
+
+// Starts the droplet squashing and throws out particles for every splash.
+#[allow(clippy::too_many_arguments)]
+fn spawn_splash(
+    mut commands: Commands,
+    mut splash_events: EventReader<SplashEvent>,
+    mut droplet_query: Query<(&mut Transform, &DropletRadius), With<Droplet>>,
+    budget: Res<ParticleBudget>,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    time: Res<Time>,
+    lifetime: Res<ParticleLifetimeSettings>,
+    config: Res<SplashConfig>,
+    liquid: Res<CurrentLiquid>,
+    viscosity: Res<tuning::Viscosity>,
+    mut rng: ResMut<SimulationRng>,
+    mut trajectory_log: Option<ResMut<trajectory_log::TrajectoryLog>>,
+) {
+    // New splashes win over old particles, which get evicted afterwards, but a single
+    // frame's splashes never spawn more than the whole budget
+    let mut budget_left = budget.max;
+
+    for splash in splash_events.read() {
+        // Squash the droplet against the surface it hit, harder the faster it was going
+        let surface_rotation = Quat::from_rotation_arc(Vec3::Y, splash.normal);
+        let mut size_scale = 1.0;
+        if let Ok((mut transform, radius)) = droplet_query.get_mut(splash.droplet) {
+            if !splash.into_water && !splash.bounced {
+                commands.entity(splash.droplet).insert(Squash::new(splash.impact_speed));
+                transform.rotation = surface_rotation;
+            }
+            size_scale = radius.0 / DROPLET_RADIUS;
+        }
+
+        // Harder hits throw more water, further
+        // and thick liquids like honey barely splash at all
+        let energy_scale = (splash.impact_speed / REFERENCE_IMPACT_SPEED).min(MAX_SPLASH_ENERGY_SCALE)
+            * liquid.0.splash_scale();
+        // Bigger droplets throw more particles, further out; see `SizeTier`.
+        // Even a soft hit throws a few, and a very hard one no more than three times the usual amount for its size.
+        let (count_scale, spread) = SizeTier::splash_scale(size_scale * DROPLET_RADIUS);
+        let share = if splash.bounced { BOUNCED_PARTICLE_SHARE } else { 1.0 };
+        let usual_count = config.count as f32 * count_scale * share;
+        let fewest = (config.count as f32 * share / 4.0) as usize;
+        let most = ((usual_count * 3.0) as usize).max(fewest);
+        let particle_count = ((usual_count * energy_scale) as usize).clamp(fewest, most).min(budget_left);
+        budget_left -= particle_count;
+        // A droplet made thicker than its liquid usually is throws them slower and less far, and a thinner one
+        // further
+        let spray_scale = energy_scale * viscosity.splash_scale(liquid.0);
+
+        let rng = &mut rng.rng;
+        let upward = &config.upward_velocity_range;
+        let inner_count = (particle_count as f32 * config.inner_fraction.clamp(0.0, 1.0)).round() as usize;
+        let crown_count = particle_count - inner_count;
+        let crown_speed = upward.start.lerp(upward.end, rng.gen()) * spray_scale * spread;
+        let crown_tilt = config.crown_angle.to_radians();
+
+        let mut thrown = 0;
+        for launched in 0..particle_count {
+            let (position, velocity) = if launched < crown_count {
+                let spacing = TAU / crown_count as f32;
+                let angle = launched as f32 * spacing + rng.gen_range(-1.0..1.0) * CROWN_ANGLE_JITTER * spacing;
+                let outward = Vec3::new(angle.cos(), 0.0, angle.sin());
+                let speed = crown_speed * (1.0 + rng.gen_range(-1.0..1.0) * CROWN_SPEED_JITTER);
+                let position = splash.position + outward * config.ring_radius * size_scale;
+                (position, outward * speed * crown_tilt.sin() + Vec3::Y * speed * crown_tilt.cos())
+            } else {
+                let x_vel = rng.gen_range(-1.0..1.0) * config.horizontal_spread * spray_scale * spread;
+                let z_vel = rng.gen_range(-1.0..1.0) * config.horizontal_spread * spray_scale * spread;
+                let y_vel = upward.start.lerp(upward.end, rng.gen()) * spray_scale;
+                (splash.position, Vec3::new(x_vel, y_vel, z_vel) * INNER_SPEED_SCALE)
+            };
+
+            // The splash is built around +Y, then turned to spray away from whatever was hit
+            let position = splash.position + surface_rotation * (position - splash.position);
+            let velocity = surface_rotation * velocity;
+            let velocity = velocity + carried_velocity(velocity, splash.impact_velocity, splash.normal);
+
+            let particle = SplashParticle { splash_depth: 0, spawned_at: time.elapsed_seconds(), size: size_scale };
+            if !particle_pool.launch(&mut commands, position, velocity, particle, lifetime.seconds) {
+                debug!("Particle pool ran dry, splash lost {} particles", particle_count - launched);
+                break;
+            }
+            thrown += 1;
+        }
+        if let Some(log) = trajectory_log.as_mut() {
+            log.splash(time.elapsed_seconds(), splash, thrown);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn tick_particle_lifetime(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<ParticleLifetimeSettings>,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    // Frozen particles wait for the thaw before they start running out
+    mut query: Query<
+        (Entity, &SplashParticle, &mut Lifetime, &mut Transform),
+        (Without<RigidBodyDisabled>, Without<freeze::Frozen>),
+    >,
+) {
+    for (entity, particle, mut lifetime, mut transform) in query.iter_mut() {
+        lifetime.0.tick(time.delta());
+
+        if lifetime.0.finished() {
+            particle_pool.release(&mut commands, entity);
+            continue;
+        }
+
+        // Shrink towards nothing at the end so particles don't pop out of existence
+        let remaining = lifetime.0.remaining_secs();
+        if settings.shrink && remaining < SHRINK_SECONDS {
+            transform.scale = Vec3::splat(particle.size * remaining / SHRINK_SECONDS);
+        }
+    }
+}
+
+// Particles share one material so they batch. Over its last `FADE_SECONDS` a particle steps through the shared
+// fade materials instead, and goes back to the plain one once it's relaunched.
+#[allow(clippy::type_complexity)]
+fn fade_expiring_particles(
+    settings: Res<ParticleLifetimeSettings>,
+    splash_assets: Res<SplashAssets>,
+    mut query: Query<(&Lifetime, &mut Handle<StandardMaterial>), (With<SplashParticle>, Without<RigidBodyDisabled>)>,
+) {
+    for (lifetime, mut material) in query.iter_mut() {
+        let remaining = lifetime.0.remaining_secs();
+        let target = if !settings.fade || remaining >= FADE_SECONDS {
+            &splash_assets.particle_material
+        } else {
+            let step = ((1.0 - remaining / FADE_SECONDS) * FADE_STEPS as f32) as usize;
+            &splash_assets.particle_fade_materials[step.min(FADE_STEPS - 1)]
+        };
+        if *material != *target {
+            *material = target.clone();
+        }
+    }
+}
+
+// Every random number the simulation uses comes from here, so a run can be replayed from its seed.
+// The seed is taken from `--seed <n>`, then the `DROPLET_SEED` environment variable, and is random otherwise.
+#[derive(Resource)]
+struct SimulationRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SimulationRng {
+    fn new(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    fn from_seed_or_random(seed: Option<u64>) -> Self {
+        Self::new(seed.unwrap_or_else(|| rand::thread_rng().gen()))
+    }
+
+    // Back to the first of the seed's numbers
+    fn reseed(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+// S re-seeds the random numbers and drops the droplets again, so the splash throws its particles exactly as the
+// first one did, to compare against after changing a setting
+fn reseed_and_replay(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut rng: ResMut<SimulationRng>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    if !bindings.just_pressed(Action::Reseed, &keys) {
+        return;
+    }
+    rng.reseed();
+    info!("Re-seeded with {}", rng.seed);
+    resets.send(ResetDroplets);
+}
+
+// Asks `reset_droplet` to reset the scene as if R had been pressed
+#[derive(Event)]
+struct ResetDroplets;
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn reset_droplet(
+    mut commands: Commands,
+    mut query: Query<
+        (Entity, &SpawnPoint, &DropletRadius, &mut Transform, &mut Velocity, &mut ImpactVelocity),
+        With<PrimaryDroplet>,
+    >,
+    extra_droplets: Query<Entity, (With<Droplet>, Without<PrimaryDroplet>)>,
+    particle_query: Query<Entity, (With<SplashParticle>, Without<RigidBodyDisabled>)>,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    droplet_assets: Res<DropletAssets>,
+    ripple_query: Query<Entity, With<ripple::Ripple>>,
+    puddle_query: Query<Entity, With<puddle::Puddle>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut reset_events: EventReader<ResetDroplets>,
+) {
+    let reset_requested = reset_events.read().count() > 0;
+    if bindings.just_pressed(Action::Reset, &keys) || reset_requested {
+        // Reset the original droplet back to where it was dropped from
+        for (entity, spawn_point, radius, mut transform, mut velocity, mut impact_velocity) in query.iter_mut() {
+            transform.translation = spawn_point.0;
+            transform.scale = Vec3::splat(radius.0); // Un-flatten
+            transform.rotation = Quat::IDENTITY;
+            velocity.linvel = Vec3::ZERO;
+            velocity.angvel = Vec3::ZERO;
+            impact_velocity.0 = Vec3::ZERO;
+            
+            // Brings it back if it had broken apart, and washes off any dye it picked up from touching dyed droplets
+            commands
+                .entity(entity)
+                .remove::<(HasSplashed, Squash, RigidBodyDisabled, droplet_color::DropletColor)>()
+                .insert((Visibility::Inherited, droplet_assets.surface_materials[0].clone()));
+        }
+
+        // Everything dropped since then goes away, fragments included
+        for entity in extra_droplets.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        // Remove old particles, ripples and puddles
+        for entity in particle_query.iter() {
+            particle_pool.release(&mut commands, entity);
+        }
+        for entity in ripple_query.iter().chain(puddle_query.iter()) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Anything this far below the floor has fallen off the edge, and anything this far out has flown off
+const OUT_OF_BOUNDS_Y: f32 = -10.0;
+const OUT_OF_BOUNDS_RADIUS: f32 = 50.0;
+
+// Stops simulating droplets and particles that have left the scene.
+// Particles go back to the pool, and the primary droplet is put back where it started, the same way R does.
+#[allow(clippy::type_complexity)]
+fn despawn_out_of_bounds(
+    mut commands: Commands,
+    mut particle_pool: ResMut<pool::ParticlePool>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            Option<&SpawnPoint>,
+            Option<&DropletRadius>,
+            Option<&mut Velocity>,
+            Has<PrimaryDroplet>,
+            Has<SplashParticle>,
+        ),
+        (Or<(With<SplashParticle>, With<Droplet>)>, Without<RigidBodyDisabled>),
+    >,
+) {
+    for (entity, mut transform, spawn_point, radius, velocity, is_primary, is_particle) in query.iter_mut() {
+        let position = transform.translation;
+        if position.y >= OUT_OF_BOUNDS_Y && position.xz().length() <= OUT_OF_BOUNDS_RADIUS {
+            continue;
+        }
+
+        if let (true, Some(spawn_point), Some(radius), Some(mut velocity)) = (is_primary, spawn_point, radius, velocity) {
+            debug!("Primary droplet left the world at {position}, moving it back");
+            transform.translation = spawn_point.0;
+            transform.scale = Vec3::splat(radius.0);
+            // Upright and still, spin included, as R leaves it
+            transform.rotation = Quat::IDENTITY;
+            *velocity = Velocity::zero();
+            commands.entity(entity).remove::<(HasSplashed, Squash)>().insert(ImpactVelocity::default());
+        } else if is_particle {
+            debug!("Returning {entity:?} to the pool, out of bounds at {position}");
+            particle_pool.release(&mut commands, entity);
+        } else {
+            debug!("Despawning {entity:?}, out of bounds at {position}");
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    fn splash_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<CollisionEvent>()
+            .add_event::<SplashEvent>()
+            .init_resource::<SplashThreshold>()
+            .init_resource::<RapierContext>()
+            .add_systems(Update, splash_on_impact);
+        app
+    }
+
+    // Handles to nothing, for splashes that aren't drawn
+    pub(crate) fn stub_splash_assets() -> SplashAssets {
+        SplashAssets {
+            particle_mesh: Handle::default(),
+            particle_material: Handle::default(),
+            particle_fade_materials: Vec::new(),
+            ripple_mesh: Handle::default(),
+            ripple_materials: Vec::new(),
+            puddle_mesh: Handle::default(),
+            puddle_material: Handle::default(),
+        }
+    }
+
+    fn fill_particle_pool(world: &mut World) {
+        use bevy::ecs::system::RunSystemOnce;
+
+        world.run_system_once(
+            |mut commands: Commands, mut particle_pool: ResMut<pool::ParticlePool>, assets: Res<SplashAssets>| {
+                particle_pool.fill(&mut commands, &assets);
+            },
+        );
+    }
+
+    #[test]
+    fn every_droplet_splashes_independently() {
+        let mut app = splash_test_app();
+
+        let floor = app.world_mut().spawn_empty().id();
+        let droplets: Vec<Entity> = [-2.0, 0.0, 2.0]
+            .into_iter()
+            .map(|x| {
+                app.world_mut()
+                    .spawn((
+                        Droplet,
+                        Transform::from_xyz(x, 0.5, 0.0),
+                        ImpactVelocity(Vec3::new(0.0, -8.0, 0.0)),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        for &droplet in &droplets {
+            app.world_mut()
+                .send_event(CollisionEvent::Started(droplet, floor, CollisionEventFlags::empty()));
+        }
+        app.update();
+
+        for &droplet in &droplets {
+            assert!(app.world().get::<HasSplashed>(droplet).is_some());
+        }
+        let splashes = app.world().resource::<Events<SplashEvent>>();
+        let mut reader = splashes.get_reader();
+        assert_eq!(reader.read(splashes).count(), droplets.len());
+    }
+
+    #[test]
+    fn only_the_colliding_droplet_is_marked_splashed() {
+        let mut app = splash_test_app();
+
+        let floor = app.world_mut().spawn_empty().id();
+        let falling = Vec3::new(0.0, -8.0, 0.0);
+        let hit = app.world_mut().spawn((Droplet, Transform::default(), ImpactVelocity(falling))).id();
+        let airborne = app.world_mut().spawn((Droplet, Transform::default(), ImpactVelocity(falling))).id();
+
+        app.world_mut()
+            .send_event(CollisionEvent::Started(floor, hit, CollisionEventFlags::empty()));
+        app.update();
+
+        assert!(app.world().get::<HasSplashed>(hit).is_some());
+        assert!(app.world().get::<HasSplashed>(airborne).is_none());
+    }
+
+    #[test]
+    fn splashes_reuse_pooled_particles_instead_of_spawning() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.insert_resource(stub_splash_assets());
+        let mut particle_pool = pool::ParticlePool::default();
+        particle_pool.size = 3;
+        world.insert_resource(particle_pool);
+        fill_particle_pool(&mut world);
+
+        let launch = |mut commands: Commands, mut particle_pool: ResMut<pool::ParticlePool>| {
+            let particle = SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 };
+            particle_pool.launch(&mut commands, Vec3::ZERO, Vec3::Y, particle, 1.0)
+        };
+        let mut particles = world.query_filtered::<Entity, With<SplashParticle>>();
+        let mut parked = world.query_filtered::<(), (With<SplashParticle>, With<RigidBodyDisabled>)>();
+        assert_eq!(parked.iter(&world).count(), 3);
+
+        // Only as many particles as the pool holds can be out at once
+        for _ in 0..3 {
+            assert!(world.run_system_once(launch));
+        }
+        assert!(!world.run_system_once(launch));
+        assert_eq!(parked.iter(&world).count(), 0);
+        assert_eq!(world.resource::<pool::ParticlePool>().active(), 3);
+
+        // A released particle is parked again and is the next one launched
+        let released = particles.iter(&world).next().unwrap();
+        world.run_system_once(move |mut commands: Commands, mut particle_pool: ResMut<pool::ParticlePool>| {
+            particle_pool.release(&mut commands, released);
+        });
+        assert!(world.get::<RigidBodyDisabled>(released).is_some());
+        assert_eq!(world.get::<Visibility>(released), Some(&Visibility::Hidden));
+
+        assert!(world.run_system_once(launch));
+        assert!(world.get::<RigidBodyDisabled>(released).is_none());
+        assert_eq!(particles.iter(&world).count(), 3);
+    }
+
+    // Runs `spawn_splash` for one reference-size droplet hitting the floor at `impact_velocity`,
+    // returning the launched particles' velocities
+    fn splash_velocities(config: SplashConfig, impact_velocity: Vec3, normal: Vec3) -> Vec<Vec3> {
+        sized_splash_velocities(DROPLET_RADIUS, config, impact_velocity, normal)
+    }
+
+    fn sized_splash_velocities(radius: f32, config: SplashConfig, impact_velocity: Vec3, normal: Vec3) -> Vec<Vec3> {
+        let mut app = splash_spawning_app(config, 1);
+        let droplet = app.world_mut().spawn((Droplet, Transform::default(), DropletRadius(radius))).id();
+        throw_splash(&mut app, droplet, impact_velocity, normal).into_iter().map(|(_, velocity)| velocity).collect()
+    }
+
+    // Throws splashes with `spawn_splash` from a full pool, drawing on a generator seeded with `seed`
+    fn splash_spawning_app(config: SplashConfig, seed: u64) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<SplashEvent>()
+            .insert_resource(config)
+            .insert_resource(stub_splash_assets())
+            .init_resource::<ParticleBudget>()
+            .init_resource::<ParticleLifetimeSettings>()
+            .init_resource::<pool::ParticlePool>()
+            .init_resource::<CurrentLiquid>()
+            .init_resource::<tuning::Viscosity>()
+            .insert_resource(SimulationRng::new(seed))
+            .add_systems(Update, spawn_splash);
+        fill_particle_pool(app.world_mut());
+        app
+    }
+
+    // Every particle in flight after `droplet` splashes once, with its velocity
+    fn throw_splash(app: &mut App, droplet: Entity, impact_velocity: Vec3, normal: Vec3) -> Vec<(Entity, Vec3)> {
+        app.world_mut().send_event(SplashEvent {
+            position: Vec3::ZERO,
+            impact_speed: impact_velocity.length(),
+            impact_velocity,
+            normal,
+            into_water: false,
+            bounced: false,
+            droplet,
+        });
+        app.update();
+
+        let mut particles = app
+            .world_mut()
+            .query_filtered::<(Entity, &Velocity), (With<SplashParticle>, Without<RigidBodyDisabled>)>();
+        particles.iter(app.world()).map(|(entity, velocity)| (entity, velocity.linvel)).collect()
+    }
+
+    #[test]
+    fn crown_particles_leave_evenly_around_the_ring_at_the_crown_angle() {
+        let config = SplashConfig { inner_fraction: 0.0, ..default() };
+        let straight_down = Vec3::NEG_Y * REFERENCE_IMPACT_SPEED;
+
+        let spacing = TAU / config.count as f32;
+        let mut sectors = Vec::new();
+        for v in splash_velocities(config.clone(), straight_down, Vec3::Y) {
+            let from_vertical = v.xz().length().atan2(v.y).to_degrees();
+            assert!((from_vertical - config.crown_angle).abs() < 0.01, "left at {from_vertical}°");
+            sectors.push((v.z.atan2(v.x).rem_euclid(TAU) / spacing).round() as usize % config.count);
+        }
+
+        // One particle in each slot around the ring
+        sectors.sort();
+        assert_eq!(sectors, (0..config.count).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sideways_impacts_splash_mostly_forwards() {
+        let config = SplashConfig { inner_fraction: 0.0, ..default() };
+        let velocities = splash_velocities(config, Vec3::new(4.0, -REFERENCE_IMPACT_SPEED, 0.0), Vec3::Y);
+
+        let forward = velocities.iter().filter(|v| v.x > 0.0).count();
+        assert!(forward > velocities.len() * 3 / 5, "{forward} of {} went forwards", velocities.len());
+        // Every particle is carried along at least a little
+        let mean_x = velocities.iter().map(|v| v.x).sum::<f32>() / velocities.len() as f32;
+        assert!(mean_x > 1.0, "mean sideways speed {mean_x}");
+    }
+
+    #[test]
+    fn wall_impacts_splash_away_from_the_wall() {
+        // Flying into a wall on its +X side, a little downwards
+        let normal = Vec3::X;
+        let velocities = splash_velocities(SplashConfig::default(), Vec3::new(-8.0, -2.0, 0.0), normal);
+
+        assert!(!velocities.is_empty());
+        for v in velocities {
+            assert!(v.dot(normal) > 0.0, "{v} goes into the wall");
+        }
+    }
+
+    #[test]
+    fn squash_flattens_then_recoils_harder_for_faster_impacts() {
+        let gentle = Squash::new(0.25 * REFERENCE_IMPACT_SPEED);
+        let hard = Squash::new(2.0 * REFERENCE_IMPACT_SPEED);
+        assert!(squashed(hard.amount).y < squashed(gentle.amount).y);
+
+        for squash in [&gentle, &hard] {
+            assert_eq!(squash_shape(squash.amount, 0.0), Vec3::ONE);
+            let deepest = squash_shape(squash.amount, SQUASH_FLATTEN_SHARE);
+            let settled = squash_shape(squash.amount, 1.0);
+            assert!(deepest.y < settled.y, "recoils from the deepest point");
+            assert!(settled.y < 1.0 && settled.x > 1.0, "stays squashed once settled");
+        }
+        // A full-speed hit ends up the flat pancake the droplet used to snap straight to, near enough
+        assert!(squash_shape(hard.amount, SQUASH_FLATTEN_SHARE).abs_diff_eq(Vec3::new(2.0, 0.1, 2.0), 1e-5));
+    }
+
+    #[test]
+    fn bouncy_droplets_splash_on_each_hard_landing_and_splat_ones_stick() {
+        let mut app = splash_test_app();
+        let floor = app.world_mut().spawn_empty().id();
+        let falling = |speed: f32| ImpactVelocity(Vec3::new(0.0, -speed, 0.0));
+        let mut droplet = |restitution: f32| {
+            let bundle = (Droplet, Transform::default(), falling(8.0), Restitution::coefficient(restitution));
+            app.world_mut().spawn(bundle).id()
+        };
+        let bouncy = droplet(0.9);
+        let splat = droplet(0.05);
+
+        let mut reader = app.world().resource::<Events<SplashEvent>>().get_reader();
+        let mut land = |app: &mut App, droplet: Entity, speed: f32| {
+            app.world_mut().get_mut::<ImpactVelocity>(droplet).unwrap().0 = falling(speed).0;
+            app.world_mut().send_event(CollisionEvent::Started(droplet, floor, CollisionEventFlags::empty()));
+            app.update();
+            let splashes = app.world().resource::<Events<SplashEvent>>();
+            reader.read(splashes).find(|splash| splash.droplet == droplet).map(|splash| splash.bounced)
+        };
+
+        // Each bounce comes down slower, until there's too little left to splash
+        assert_eq!(land(&mut app, bouncy, 8.0), Some(true));
+        assert_eq!(land(&mut app, bouncy, 6.0), Some(true));
+        assert_eq!(land(&mut app, bouncy, 2.0), None);
+        assert!(app.world().get::<HasSplashed>(bouncy).is_none());
+
+        assert_eq!(land(&mut app, splat, 8.0), Some(false));
+        assert!(app.world().get::<HasSplashed>(splat).is_some());
+    }
+
+    #[test]
+    fn each_size_tier_splashes_its_own_particle_count_and_bigger_ones_spread_further() {
+        let straight_down = Vec3::NEG_Y * REFERENCE_IMPACT_SPEED;
+        let mut last_reach = 0.0;
+        for (tier, particles) in SizeTier::ALL.into_iter().zip([8, 20, 40]) {
+            let velocities = sized_splash_velocities(tier.radius(), SplashConfig::default(), straight_down, Vec3::Y);
+            assert_eq!(velocities.len(), particles, "{tier:?}");
+            let reach = velocities.iter().map(|v| v.xz().length()).sum::<f32>() / velocities.len() as f32;
+            assert!(reach > last_reach, "{tier:?} spreads {reach}, no further than the size below");
+            last_reach = reach;
+        }
+
+        // Sizes from Z and X land between the tiers
+        let (between, _) = SizeTier::splash_scale(0.4);
+        assert!(0.4 < between && between < 1.0, "{between}");
+    }
+
+    #[test]
+    fn number_keys_pick_the_size_of_the_primary_droplet() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<ResetDroplets>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<DropletSize>()
+            .add_systems(Update, resize_droplet);
+        let primary = app.world_mut().spawn((PrimaryDroplet, DropletRadius(DROPLET_RADIUS))).id();
+
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Digit3);
+        app.update();
+        assert_eq!(app.world().resource::<DropletSize>().0, SizeTier::Large.radius());
+        assert_eq!(app.world().get::<DropletRadius>(primary).unwrap().0, SizeTier::Large.radius());
+        let resets = app.world().resource::<Events<ResetDroplets>>();
+        assert_eq!(resets.get_reader().read(resets).count(), 1);
+    }
+
+    #[test]
+    fn the_same_seed_splashes_the_same_way_twice() {
+        use clap::Parser;
+
+        let run = || {
+            let cli = cli::Cli::try_parse_from(["droplet", "--headless", "--seed", "7"]).unwrap();
+            let mut app = headless::app(cli);
+            // Past the landing, with the spray still in the air
+            for _ in 0..90 {
+                app.update();
+            }
+            let step = app.world().resource::<RapierConfiguration>().timestep_mode;
+            assert_eq!(step, TimestepMode::Fixed { dt: simulation::DEFAULT_TIMESTEP, substeps: 1 });
+            let mut particles = app
+                .world_mut()
+                .query_filtered::<(&Transform, &Velocity), (With<SplashParticle>, Without<RigidBodyDisabled>)>();
+            let particles: Vec<(Vec3, Vec3)> = particles
+                .iter(app.world())
+                .map(|(transform, velocity)| (transform.translation, velocity.linvel))
+                .collect();
+            particles
+        };
+
+        let first = run();
+        assert!(!first.is_empty());
+        assert_eq!(first, run());
+    }
+
+    #[test]
+    fn s_reseeds_so_the_next_splash_throws_its_particles_like_the_first() {
+        let mut app = splash_spawning_app(SplashConfig::default(), 5);
+        app.add_event::<ResetDroplets>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, reseed_and_replay.before(spawn_splash));
+        let droplet = app.world_mut().spawn((Droplet, Transform::default(), DropletRadius(DROPLET_RADIUS))).id();
+
+        let mut thrown = bevy::utils::HashSet::new();
+        let mut splash = |app: &mut App, reseed: bool| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.clear();
+            keys.release(KeyCode::KeyS);
+            if reseed {
+                keys.press(KeyCode::KeyS);
+            }
+            let mut velocities: Vec<[f32; 3]> = throw_splash(app, droplet, Vec3::new(0.0, -8.0, 0.0), Vec3::Y)
+                .into_iter()
+                .filter(|(entity, _)| thrown.insert(*entity))
+                .map(|(_, velocity)| velocity.to_array())
+                .collect();
+            velocities.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            velocities
+        };
+
+        let first = splash(&mut app, false);
+        let second = splash(&mut app, false);
+        let replayed = splash(&mut app, true);
+        assert!(!first.is_empty());
+        assert_ne!(first, second);
+        assert_eq!(first, replayed);
+        let resets = app.world().resource::<Events<ResetDroplets>>();
+        assert_eq!(resets.get_reader().read(resets).count(), 1);
+    }
+
+    #[test]
+    fn a_droplet_hitting_the_floor_throws_a_full_splash_of_particles() {
+        use clap::Parser;
+
+        let cli = cli::Cli::try_parse_from(["droplet", "--headless", "--seed", "2"]).unwrap();
+        let mut app = headless::app(cli);
+        app.update();
+        let mut droplet = app.world_mut().query_filtered::<Entity, With<PrimaryDroplet>>();
+        let droplet = droplet.single(app.world());
+        let mut floor = app.world_mut().query_filtered::<Entity, With<floor::Floor>>();
+        let floor = floor.single(app.world());
+        let mut particles =
+            app.world_mut().query_filtered::<(), (With<SplashParticle>, Without<RigidBodyDisabled>)>();
+        assert_eq!(particles.iter(app.world()).count(), 0);
+
+        // A medium droplet of water landing at the reference speed throws the configured count, no more, no less
+        app.world_mut().get_mut::<ImpactVelocity>(droplet).unwrap().0 = Vec3::NEG_Y * REFERENCE_IMPACT_SPEED;
+        app.world_mut().send_event(CollisionEvent::Started(droplet, floor, CollisionEventFlags::empty()));
+        app.update();
+
+        assert!(app.world().get::<HasSplashed>(droplet).is_some());
+        let expected = app.world().resource::<SplashConfig>().count;
+        assert_eq!(particles.iter(app.world()).count(), expected);
+        assert_eq!(app.world().resource::<headless::Splashes>().0, 1);
+    }
+
+    #[test]
+    fn resetting_washes_the_dye_off_the_primary_droplet() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<ResetDroplets>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<pool::ParticlePool>()
+            .insert_resource(DropletAssets {
+                mesh: Handle::default(),
+                material: Handle::default(),
+                surface_materials: vec![Handle::weak_from_u128(1)],
+            })
+            .add_systems(Update, reset_droplet);
+        // Dyed by touching a dyed droplet, with a material of its own
+        let color = droplet_color::DropletColor { base: Color::WHITE, attenuation: Color::WHITE };
+        let primary = app
+            .world_mut()
+            .spawn((
+                PrimaryDroplet,
+                SpawnPoint(Vec3::Y),
+                DropletRadius(0.5),
+                Transform::default(),
+                Velocity::zero(),
+                ImpactVelocity::default(),
+                color,
+                Handle::<DropletMaterial>::weak_from_u128(2),
+            ))
+            .id();
+
+        app.world_mut().send_event(ResetDroplets);
+        app.update();
+        assert!(app.world().get::<droplet_color::DropletColor>(primary).is_none());
+        assert_eq!(app.world().get::<Handle<DropletMaterial>>(primary), Some(&Handle::weak_from_u128(1)));
+    }
+
+    #[test]
+    fn a_primary_droplet_off_the_edge_comes_back_upright_and_still() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<pool::ParticlePool>();
+        let primary = world
+            .spawn((
+                Droplet,
+                PrimaryDroplet,
+                SpawnPoint(Vec3::Y * 5.0),
+                DropletRadius(0.5),
+                Transform::from_xyz(0.0, -20.0, 0.0).with_rotation(Quat::from_rotation_z(1.0)),
+                Velocity { linvel: Vec3::NEG_Y, angvel: Vec3::X },
+            ))
+            .id();
+        world.run_system_once(despawn_out_of_bounds);
+
+        let transform = world.get::<Transform>(primary).unwrap();
+        assert_eq!((transform.translation, transform.rotation), (Vec3::Y * 5.0, Quat::IDENTITY));
+        assert_eq!(*world.get::<Velocity>(primary).unwrap(), Velocity::zero());
+    }
+
+    #[test]
+    fn fading_particles_share_the_fade_materials_and_go_back_to_the_plain_one() {
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<ParticleLifetimeSettings>()
+            .add_systems(Update, fade_expiring_particles);
+        let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+        let plain = StandardMaterial { base_color: Color::srgba(0.5, 0.7, 1.0, 0.8), ..default() };
+        let splash_assets = SplashAssets {
+            particle_fade_materials: particle_fade_steps(&plain).map(|faded| materials.add(faded)).collect(),
+            particle_material: materials.add(plain),
+            ..stub_splash_assets()
+        };
+        app.insert_resource(splash_assets);
+        let particle = |app: &mut App, seconds_left: f32| {
+            let mut lifetime = Timer::from_seconds(3.0, TimerMode::Once);
+            lifetime.set_elapsed(Duration::from_secs_f32(3.0 - seconds_left));
+            let material = app.world().resource::<SplashAssets>().particle_material.clone();
+            let particle = SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 };
+            app.world_mut().spawn((particle, Lifetime(lifetime), material)).id()
+        };
+        let fresh = particle(&mut app, 2.0);
+        let nearly_gone = particle(&mut app, 0.05);
+        let halfway = particle(&mut app, 0.25);
+        app.update();
+
+        let material = |app: &App, entity| app.world().get::<Handle<StandardMaterial>>(entity).unwrap().clone();
+        let splash_assets = app.world().resource::<SplashAssets>();
+        assert_eq!(material(&app, fresh), splash_assets.particle_material);
+        assert_eq!(material(&app, nearly_gone), splash_assets.particle_fade_materials[FADE_STEPS - 1]);
+        assert_eq!(material(&app, halfway), splash_assets.particle_fade_materials[FADE_STEPS / 2]);
+        // No particle got a material of its own
+        assert_eq!(app.world().resource::<Assets<StandardMaterial>>().len(), FADE_STEPS + 1);
+
+        // Relaunched with a fresh lifetime, it's drawn with the plain material again
+        app.world_mut().get_mut::<Lifetime>(nearly_gone).unwrap().0.reset();
+        app.update();
+        assert_eq!(material(&app, nearly_gone), app.world().resource::<SplashAssets>().particle_material);
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::tuning::{Bounciness, Viscosity};
+use crate::{particle_fade_steps, DropletAssets, ResetDroplets, SplashAssets};
+
+// Approximate depth light travels through a droplet / a splash particle / a puddle
+pub const DROPLET_THICKNESS: f32 = 0.9;
+pub const PARTICLE_THICKNESS: f32 = 0.1;
+pub const PUDDLE_THICKNESS: f32 = 0.02;
+
+// The kind of liquid the droplets are made of: drives both their look and how they move and splash.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Reflect)]
+pub enum LiquidType {
+    #[default]
+    Water,
+    Mercury,
+    Oil,
+    Honey,
+}
+
+impl LiquidType {
+    pub fn next(self) -> Self {
+        match self {
+            LiquidType::Water => LiquidType::Mercury,
+            LiquidType::Mercury => LiquidType::Oil,
+            LiquidType::Oil => LiquidType::Honey,
+            LiquidType::Honey => LiquidType::Water,
+        }
+    }
+
+    pub fn material(self, thickness: f32) -> StandardMaterial {
+        match self {
+            LiquidType::Water => StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.01,
+                metallic: 0.0,
+                reflectance: 0.02,
+                ior: 1.33,
+                alpha_mode: AlphaMode::Opaque,
+                specular_transmission: 1.0,
+                thickness,
+                attenuation_color: Color::WHITE,
+                attenuation_distance: 100.0,
+                ..default()
+            },
+            // A liquid metal: mirror-like, nothing gets through
+            LiquidType::Mercury => StandardMaterial {
+                base_color: Color::srgb(0.78, 0.78, 0.8),
+                perceptual_roughness: 0.05,
+                metallic: 1.0,
+                reflectance: 0.9,
+                specular_transmission: 0.0,
+                ..default()
+            },
+            LiquidType::Oil => StandardMaterial {
+                base_color: Color::srgb(0.9, 0.75, 0.25),
+                perceptual_roughness: 0.05,
+                metallic: 0.0,
+                reflectance: 0.05,
+                ior: 1.47,
+                specular_transmission: 0.8,
+                thickness,
+                attenuation_color: Color::srgb(0.85, 0.6, 0.1),
+                attenuation_distance: 2.0,
+                ..default()
+            },
+            LiquidType::Honey => StandardMaterial {
+                base_color: Color::srgb(0.95, 0.6, 0.1),
+                perceptual_roughness: 0.15,
+                metallic: 0.0,
+                reflectance: 0.04,
+                ior: 1.5,
+                specular_transmission: 0.6,
+                thickness,
+                attenuation_color: Color::srgb(0.8, 0.35, 0.02),
+                attenuation_distance: 0.5,
+                ..default()
+            },
+        }
+    }
+
+    pub fn restitution(self) -> f32 {
+        match self {
+            LiquidType::Water => 0.05,
+            LiquidType::Mercury => 0.6,
+            LiquidType::Oil => 0.1,
+            LiquidType::Honey => 0.0,
+        }
+    }
+
+    // Where `Viscosity` starts for the liquid
+    pub fn viscosity(self) -> f32 {
+        match self {
+            LiquidType::Water => 0.35,
+            LiquidType::Mercury => 0.2,
+            LiquidType::Oil => 0.5,
+            LiquidType::Honey => 0.85,
+        }
+    }
+
+    // How much of the impact energy goes into the splash, relative to water
+    pub fn splash_scale(self) -> f32 {
+        match self {
+            LiquidType::Water => 1.0,
+            LiquidType::Mercury => 0.6,
+            LiquidType::Oil => 0.8,
+            LiquidType::Honey => 0.25,
+        }
+    }
+}
+
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct CurrentLiquid(pub LiquidType);
+
+// L switches every droplet, splash particle and puddle to the next liquid and drops the droplets again
+#[allow(clippy::too_many_arguments)]
+pub fn cycle_liquid(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut current: ResMut<CurrentLiquid>,
+    droplet_assets: Res<DropletAssets>,
+    splash_assets: Res<SplashAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut viscosity: ResMut<Viscosity>,
+    mut bounciness: ResMut<Bounciness>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    if !bindings.just_pressed(Action::NextLiquid, &keys) {
+        return;
+    }
+
+    current.0 = current.0.next();
+    let liquid = current.0;
+    info!("Liquid: {liquid:?}");
+
+    restyle_materials(liquid, &droplet_assets, &splash_assets, &mut materials);
+
+    // Every droplet picks up the new bounce and thickness from here
+    viscosity.0 = liquid.viscosity();
+    bounciness.0 = liquid.restitution();
+
+    resets.send(ResetDroplets);
+}
+
+// The materials are shared, so updating them in place restyles everything at once
+pub fn restyle_materials(
+    liquid: LiquidType,
+    droplet_assets: &DropletAssets,
+    splash_assets: &SplashAssets,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    if let Some(material) = materials.get_mut(&droplet_assets.material) {
+        *material = liquid.material(DROPLET_THICKNESS);
+    }
+    let particle_material = liquid.material(PARTICLE_THICKNESS);
+    for (handle, faded) in splash_assets.particle_fade_materials.iter().zip(particle_fade_steps(&particle_material)) {
+        materials.insert(handle, faded);
+    }
+    if let Some(material) = materials.get_mut(&splash_assets.particle_material) {
+        *material = particle_material;
+    }
+    if let Some(material) = materials.get_mut(&splash_assets.puddle_material) {
+        *material = liquid.material(PUDDLE_THICKNESS);
+    }
+}
@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::utils::{HashMap, HashSet};
+use bevy_rapier3d::prelude::*;
+
+use crate::cohesion::SpatialHash;
+use crate::keybindings::{Action, KeyBindings};
+use crate::surface_tension::PARTICLE_RADIUS;
+use crate::{SplashAssets, SplashParticle};
+
+// Particles slower than this (m/s) count as resting
+const REST_SPEED: f32 = 0.2;
+// Fewer resting particles than this together are left as spheres
+const MIN_CLUSTER_SIZE: usize = 3;
+// The surface sits where the summed field reaches 1.0, which is exactly the particle's own sphere when it is alone
+const ISO_LEVEL: f32 = 1.0;
+
+// The 8 corners of a grid cube, and the 6 tetrahedra around its 0-6 diagonal that fill it without gaps
+const CUBE_CORNERS: [IVec3; 8] = [
+    IVec3::new(0, 0, 0),
+    IVec3::new(1, 0, 0),
+    IVec3::new(1, 1, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(1, 0, 1),
+    IVec3::new(1, 1, 1),
+    IVec3::new(0, 1, 1),
+];
+const CUBE_TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 5, 1, 6],
+    [0, 1, 2, 6],
+    [0, 2, 3, 6],
+    [0, 3, 7, 6],
+    [0, 7, 4, 6],
+    [0, 4, 5, 6],
+];
+
+// Draws clusters of resting splash particles as one smooth blob of water instead of a pile of spheres.
+// Only the look changes: the particles stay separate balls for physics.
+#[derive(Resource)]
+pub struct MetaballSettings {
+    pub enabled: bool,
+    // Resting particles closer than this (m, centre to centre) are part of the same blob
+    pub link_distance: f32,
+    // Grid spacing (m) the surface is built on; coarsened for big blobs to stay within `max_cells`
+    pub cell_size: f32,
+    // Most grid cells along any side of one blob's grid
+    pub max_cells: usize,
+    // Seconds between rebuilds
+    pub update_interval: f32,
+}
+
+impl Default for MetaballSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            link_distance: 0.3,
+            cell_size: 0.04,
+            max_cells: 24,
+            update_interval: 0.1,
+        }
+    }
+}
+
+// The mesh for one blob, rebuilt from scratch each update
+#[derive(Component)]
+pub struct Metaball;
+
+// A particle hidden because a blob is drawn over it
+#[derive(Component)]
+pub struct InBlob;
+
+#[derive(Clone, Copy)]
+pub struct Ball {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+fn field(balls: &[Ball], point: Vec3) -> f32 {
+    balls
+        .iter()
+        .map(|ball| ball.radius * ball.radius / point.distance_squared(ball.center).max(1e-6))
+        .sum()
+}
+
+// Points out of the blob
+fn outward_normal(balls: &[Ball], point: Vec3) -> Vec3 {
+    let gradient: Vec3 = balls
+        .iter()
+        .map(|ball| {
+            let offset = point - ball.center;
+            let distance_squared = offset.length_squared().max(1e-6);
+            -2.0 * ball.radius * ball.radius * offset / (distance_squared * distance_squared)
+        })
+        .sum();
+    (-gradient).normalize_or_zero()
+}
+
+// Marching tetrahedra over a grid around the balls, giving a triangle list with normals
+pub fn blob_mesh(balls: &[Ball], cell_size: f32, max_cells: usize) -> Option<Mesh> {
+    let padding = 1.5 * balls.iter().map(|ball| ball.radius).fold(0.0, f32::max);
+    let min = balls.iter().fold(Vec3::INFINITY, |min, ball| min.min(ball.center)) - Vec3::splat(padding);
+    let max = balls.iter().fold(Vec3::NEG_INFINITY, |max, ball| max.max(ball.center)) + Vec3::splat(padding);
+    let extent = max - min;
+    let cell = cell_size.max(extent.max_element() / max_cells as f32);
+    let cells = (extent / cell).ceil().as_ivec3().max(IVec3::ONE);
+
+    let samples = cells + IVec3::ONE;
+    let index = |p: IVec3| ((p.z * samples.y + p.y) * samples.x + p.x) as usize;
+    let point = |p: IVec3| min + p.as_vec3() * cell;
+    let mut values = vec![0.0; (samples.x * samples.y * samples.z) as usize];
+    for z in 0..samples.z {
+        for y in 0..samples.y {
+            for x in 0..samples.x {
+                let p = IVec3::new(x, y, z);
+                values[index(p)] = field(balls, point(p));
+            }
+        }
+    }
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut push_triangle = |mut triangle: [Vec3; 3]| {
+        // Wind each triangle to face out of the blob
+        let centroid = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+        let facing = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+        if facing.dot(outward_normal(balls, centroid)) < 0.0 {
+            triangle.swap(1, 2);
+        }
+        for vertex in triangle {
+            positions.push(vertex.to_array());
+            normals.push(outward_normal(balls, vertex).to_array());
+        }
+    };
+
+    for z in 0..cells.z {
+        for y in 0..cells.y {
+            for x in 0..cells.x {
+                let origin = IVec3::new(x, y, z);
+                let corners = CUBE_CORNERS.map(|corner| (point(origin + corner), values[index(origin + corner)]));
+                for tetrahedron in CUBE_TETRAHEDRA {
+                    let vertices = tetrahedron.map(|i| corners[i]);
+                    let (inside, outside): (Vec<_>, Vec<_>) =
+                        vertices.iter().partition(|(_, value)| *value >= ISO_LEVEL);
+                    let crossing = |(a, va): &(Vec3, f32), (b, vb): &(Vec3, f32)| {
+                        a.lerp(*b, ((ISO_LEVEL - va) / (vb - va)).clamp(0.0, 1.0))
+                    };
+                    match (inside.len(), outside.len()) {
+                        // One corner cut off from the other three
+                        (1, 3) => push_triangle([
+                            crossing(inside[0], outside[0]),
+                            crossing(inside[0], outside[1]),
+                            crossing(inside[0], outside[2]),
+                        ]),
+                        (3, 1) => push_triangle([
+                            crossing(inside[0], outside[0]),
+                            crossing(inside[1], outside[0]),
+                            crossing(inside[2], outside[0]),
+                        ]),
+                        (2, 2) => {
+                            let quad = [
+                                crossing(inside[0], outside[0]),
+                                crossing(inside[0], outside[1]),
+                                crossing(inside[1], outside[1]),
+                                crossing(inside[1], outside[0]),
+                            ];
+                            push_triangle([quad[0], quad[1], quad[2]]);
+                            push_triangle([quad[0], quad[2], quad[3]]);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    if positions.is_empty() {
+        return None;
+    }
+    Some(
+        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals),
+    )
+}
+
+// Groups of resting balls close enough to link up, as indices into `balls`
+fn clusters(balls: &[Ball], link_distance: f32) -> Vec<Vec<usize>> {
+    let mut grid = SpatialHash::default();
+    let centers: Vec<Vec3> = balls.iter().map(|ball| ball.center).collect();
+    grid.rebuild(link_distance, &centers);
+
+    // Union-find over the links
+    let mut parent: Vec<usize> = (0..balls.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for (i, &center) in centers.iter().enumerate() {
+        for j in grid.nearby(center) {
+            if j > i && centers[j].distance(center) < link_distance {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = default();
+    for i in 0..balls.len() {
+        let group = root(&mut parent, i);
+        groups.entry(group).or_default().push(i);
+    }
+    groups.into_values().filter(|group| group.len() >= MIN_CLUSTER_SIZE).collect()
+}
+
+// Y switches the blobs off and on
+pub fn toggle_metaballs(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut settings: ResMut<MetaballSettings>,
+) {
+    if bindings.just_pressed(Action::Metaballs, &keys) {
+        settings.enabled = !settings.enabled;
+        info!("Metaball blobs {}", if settings.enabled { "on" } else { "off" });
+    }
+}
+
+// Every `update_interval`, replaces the blobs with fresh ones over the current clusters
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_metaballs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut since_last: Local<f32>,
+    settings: Res<MetaballSettings>,
+    splash_assets: Res<SplashAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    blobs: Query<Entity, With<Metaball>>,
+    particles: Query<(Entity, &Transform, &Velocity, Has<InBlob>), (With<SplashParticle>, Without<RigidBodyDisabled>)>,
+) {
+    *since_last += time.delta_seconds();
+    if *since_last < settings.update_interval && !settings.is_changed() {
+        return;
+    }
+    *since_last = 0.0;
+
+    for blob in blobs.iter() {
+        commands.entity(blob).despawn();
+    }
+
+    let resting: Vec<(Entity, Ball)> = if settings.enabled {
+        particles
+            .iter()
+            .filter(|(_, _, velocity, _)| velocity.linvel.length() < REST_SPEED)
+            .map(|(entity, transform, _, _)| {
+                (entity, Ball { center: transform.translation, radius: PARTICLE_RADIUS * transform.scale.x })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let balls: Vec<Ball> = resting.iter().map(|(_, ball)| *ball).collect();
+
+    let mut hidden = HashSet::new();
+    for cluster in clusters(&balls, settings.link_distance) {
+        let cluster_balls: Vec<Ball> = cluster.iter().map(|&i| balls[i]).collect();
+        let Some(mesh) = blob_mesh(&cluster_balls, settings.cell_size, settings.max_cells) else { continue };
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(mesh),
+                material: splash_assets.particle_material.clone(),
+                ..default()
+            },
+            Metaball,
+        ));
+        hidden.extend(cluster.iter().map(|&i| resting[i].0));
+    }
+
+    // Hide the spheres under a blob, and bring back the ones that have left theirs
+    for (entity, _, _, in_blob) in particles.iter() {
+        match (hidden.contains(&entity), in_blob) {
+            (true, false) => {
+                commands.entity(entity).insert((InBlob, Visibility::Hidden));
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<InBlob>().insert(Visibility::Visible);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_lone_metaball_is_its_particle_sphere_facing_out() {
+        use bevy::render::mesh::VertexAttributeValues;
+
+        let ball = Ball { center: Vec3::new(1.0, 0.1, -2.0), radius: 0.1 };
+        let mesh = blob_mesh(&[ball], 0.01, 24).expect("a surface around the ball");
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("blob mesh has no positions");
+        };
+
+        for triangle in positions.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(triangle[i]));
+            for vertex in [a, b, c] {
+                assert!((vertex.distance(ball.center) - ball.radius).abs() < 0.01, "{vertex} is off the sphere");
+            }
+            let facing = (b - a).cross(c - a);
+            assert!(facing.dot((a + b + c) / 3.0 - ball.center) >= 0.0, "triangle faces into the blob");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy_hanabi::prelude::*;
+use bevy_rapier3d::prelude::RapierConfiguration;
+
+use crate::SplashEvent;
+
+// A burst of fine spray on the GPU with each splash, on top of the rigid-body particles.
+// Everything about the look is tuned here; the effect is built once from it at startup.
+#[derive(Resource)]
+pub struct MistSettings {
+    // Particles in each burst
+    pub count: f32,
+    // Seconds each one lives, fading out as it goes
+    pub lifetime: f32,
+    // Launch speed (m/s) out of the surface that was hit
+    pub speed: f32,
+    // Largest sideways speed (m/s) added at random, so the burst fans out
+    pub spread: f32,
+    // Billboard size (m) at launch; shrinks to nothing by the end
+    pub size: f32,
+}
+
+impl Default for MistSettings {
+    fn default() -> Self {
+        Self {
+            count: 300.0,
+            lifetime: 0.8,
+            speed: 2.5,
+            spread: 1.5,
+            size: 0.02,
+        }
+    }
+}
+
+// The one mist effect, moved to wherever the latest splash was before it fires
+#[derive(Component)]
+struct Mist;
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins(HanabiPlugin)
+        .init_resource::<MistSettings>()
+        .add_systems(Startup, setup_mist)
+        .add_systems(Update, burst_mist);
+}
+
+fn setup_mist(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>, settings: Res<MistSettings>) {
+    let writer = ExprWriter::new();
+
+    let normal = writer.add_property("normal", Vec3::Y.into());
+    // Set from the physics with each burst, so the spray falls like the droplets do
+    let gravity = writer.add_property("gravity", Vec3::new(0.0, -9.81, 0.0).into());
+
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.0).expr());
+    let lifetime = writer.lit(settings.lifetime) * (writer.lit(0.5) + writer.rand(ScalarType::Float) * writer.lit(0.5));
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime.expr());
+    let init_position = SetAttributeModifier::new(Attribute::POSITION, writer.lit(Vec3::ZERO).expr());
+
+    // Out of the surface at up to `speed`, plus a random sideways kick of up to `spread`
+    let kick = (writer.rand(VectorType::VEC3F) * writer.lit(2.0) - writer.lit(1.0)) * writer.lit(settings.spread);
+    let velocity = writer.prop(normal) * writer.rand(ScalarType::Float) * writer.lit(settings.speed) + kick;
+    let init_velocity = SetAttributeModifier::new(Attribute::VELOCITY, velocity.expr());
+
+    let gravity = AccelModifier::new(writer.prop(gravity).expr());
+    let drag = LinearDragModifier::new(writer.lit(2.0).expr());
+
+    let mut color = Gradient::new();
+    color.add_key(0.0, Vec4::new(0.85, 0.92, 1.0, 0.7));
+    color.add_key(1.0, Vec4::new(0.85, 0.92, 1.0, 0.0));
+    let mut size = Gradient::new();
+    size.add_key(0.0, Vec2::splat(settings.size));
+    size.add_key(1.0, Vec2::ZERO);
+
+    // `false`: nothing spawns until a splash resets the spawner
+    let spawner = Spawner::once(settings.count.into(), false);
+    let effect = effects.add(
+        EffectAsset::new(vec![settings.count as u32 * 4], spawner, writer.finish())
+            .with_name("splash_mist")
+            .init(init_position)
+            .init(init_velocity)
+            .init(init_age)
+            .init(init_lifetime)
+            .update(gravity)
+            .update(drag)
+            .render(ColorOverLifetimeModifier { gradient: color })
+            .render(SizeOverLifetimeModifier {
+                gradient: size,
+                screen_space_size: false,
+            })
+            .render(OrientModifier::new(OrientMode::FaceCameraPosition)),
+    );
+
+    commands.spawn((ParticleEffectBundle::new(effect), Mist));
+}
+
+// Fires a burst from each frame's last splash; several in one frame share it
+fn burst_mist(
+    mut splash_events: EventReader<SplashEvent>,
+    rapier_config: Res<RapierConfiguration>,
+    mut mist: Query<(&mut Transform, &mut EffectProperties, &mut EffectSpawner), With<Mist>>,
+) {
+    let Some(splash) = splash_events.read().last() else { return };
+    // The spawner is added by the plugin in PostUpdate, so the very first frame can miss it
+    let Ok((mut transform, mut properties, mut spawner)) = mist.get_single_mut() else { return };
+    transform.translation = splash.position;
+    properties.set("normal", splash.normal.into());
+    properties.set("gravity", rapier_config.gravity.into());
+    spawner.reset();
+}
@@ -0,0 +1,193 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::scene_config::SceneConfig;
+
+const BOX_SIZE: f32 = 0.8;
+const SPHERE_RADIUS: f32 = 0.5;
+// Obstacles are dropped onto whatever static surface is below the camera's focus, searching from this high up
+const PLACEMENT_RAY_HEIGHT: f32 = 20.0;
+
+// Something placed with B or N for droplets to bounce off; R leaves these alone, Shift+C clears them
+#[derive(Component)]
+pub struct Obstacle;
+
+// Shared by every obstacle, like the droplet and splash assets
+#[derive(Resource)]
+pub struct ObstacleAssets {
+    box_mesh: Handle<Mesh>,
+    sphere_mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub fn setup_obstacle_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ObstacleAssets {
+        box_mesh: meshes.add(Cuboid::new(BOX_SIZE, BOX_SIZE, BOX_SIZE)),
+        sphere_mesh: meshes.add(Sphere::new(SPHERE_RADIUS)),
+        // Matte, so the droplets stay the shiny thing in the scene
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.45, 0.5, 0.6),
+            perceptual_roughness: 0.95,
+            reflectance: 0.1,
+            ..default()
+        }),
+    });
+}
+
+// B places a box and N a sphere at the point the camera orbits around, resting on whatever is under it
+pub fn spawn_obstacle(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    assets: Res<ObstacleAssets>,
+    rapier_context: Res<RapierContext>,
+    cameras: Query<&PanOrbitCamera>,
+) {
+    let (mesh, collider, half_height) = if bindings.just_pressed(Action::SpawnBox, &keys) {
+        let half = BOX_SIZE / 2.0;
+        (assets.box_mesh.clone(), Collider::cuboid(half, half, half), half)
+    } else if bindings.just_pressed(Action::SpawnSphere, &keys) {
+        (assets.sphere_mesh.clone(), Collider::ball(SPHERE_RADIUS), SPHERE_RADIUS)
+    } else {
+        return;
+    };
+    let Ok(camera) = cameras.get_single() else { return };
+
+    // Land on the floor, the ramp or an earlier obstacle, but not on droplets or particles
+    let origin = camera.focus.with_y(PLACEMENT_RAY_HEIGHT);
+    let filter = QueryFilter::only_fixed();
+    let ground = rapier_context
+        .cast_ray(origin, Vec3::NEG_Y, f32::MAX, true, filter)
+        .map_or(0.0, |(_, toi)| origin.y - toi);
+    let position = camera.focus.with_y(ground + half_height);
+
+    commands.spawn((
+        PbrBundle {
+            mesh,
+            material: assets.material.clone(),
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        Obstacle,
+        RigidBody::Fixed,
+        collider,
+    ));
+    info!("Placed an obstacle at {position}");
+}
+
+// Shift+C removes every obstacle (plain C mops up puddles)
+pub fn clear_obstacles(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    obstacles: Query<Entity, With<Obstacle>>,
+) {
+    if !bindings.just_pressed(Action::ClearObstacles, &keys) {
+        return;
+    }
+
+    for entity in obstacles.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// The root of the glTF model named in the scene config. Each of its meshes gets a trimesh collider once loaded.
+#[derive(Component)]
+pub struct ImportedObstacle;
+
+// Marks a mesh that couldn't be turned into a collider, so it isn't retried every frame
+#[derive(Component)]
+pub struct NoMeshCollider;
+
+pub fn spawn_obstacle_scene(mut commands: Commands, asset_server: Res<AssetServer>, scene: Res<SceneConfig>) {
+    let Some(obstacle) = &scene.obstacle_scene else { return };
+
+    commands.spawn((
+        SceneBundle {
+            scene: asset_server.load(GltfAssetLabel::Scene(0).from_asset(obstacle.path.clone())),
+            transform: Transform::from_translation(Vec3::from(obstacle.position)).with_scale(Vec3::splat(obstacle.scale)),
+            ..default()
+        },
+        ImportedObstacle,
+        // The meshes' colliders attach to this body as they are spawned under it
+        RigidBody::Fixed,
+    ));
+}
+
+// The model arrives asynchronously: first the file loads, then the scene spawns its mesh entities,
+// and only then can their colliders be built
+pub fn attach_mesh_colliders(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    meshes: Res<Assets<Mesh>>,
+    roots: Query<(Entity, &Handle<Scene>), With<ImportedObstacle>>,
+    children: Query<&Children>,
+    pending: Query<&Handle<Mesh>, (Without<Collider>, Without<NoMeshCollider>)>,
+) {
+    for (root, scene) in roots.iter() {
+        if let Some(LoadState::Failed(err)) = asset_server.get_load_state(scene) {
+            error!("Couldn't load the obstacle scene ({err})");
+            commands.entity(root).remove::<ImportedObstacle>();
+            continue;
+        }
+
+        for entity in children.iter_descendants(root) {
+            let Ok(handle) = pending.get(entity) else { continue };
+            let Some(mesh) = meshes.get(handle) else { continue };
+            match Collider::from_bevy_mesh(mesh, &ComputedColliderShape::TriMesh) {
+                Some(collider) => {
+                    commands.entity(entity).insert(collider);
+                }
+                None => {
+                    warn!("An obstacle scene mesh has no usable triangles, so nothing will collide with it");
+                    commands.entity(entity).insert(NoMeshCollider);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene_config;
+
+    #[test]
+    fn imported_obstacle_meshes_get_colliders_once_loaded() {
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<Scene>()
+            .add_systems(Update, attach_mesh_colliders);
+
+        // The scene has spawned its mesh entity, but the mesh itself hasn't arrived yet
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().reserve_handle();
+        let root = app.world_mut().spawn((ImportedObstacle, Handle::<Scene>::default())).id();
+        let part = app.world_mut().spawn(mesh.clone()).set_parent(root).id();
+        app.update();
+        assert!(app.world().get::<Collider>(part).is_none());
+
+        app.world_mut().resource_mut::<Assets<Mesh>>().insert(&mesh, Cuboid::new(1.0, 2.0, 1.0).into());
+        app.update();
+        let collider = app.world().get::<Collider>(part).expect("collider once the mesh is loaded");
+        assert!(collider.as_trimesh().is_some());
+    }
+
+    #[test]
+    fn shipped_obstacle_model_exists() {
+        let text = std::fs::read_to_string(format!("{}/assets/scene.ron", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let config = scene_config::SceneConfig::parse(&text).unwrap();
+        let obstacle = config.obstacle_scene.expect("the shipped scene includes a model");
+        let path = format!("{}/assets/{}", env!("CARGO_MANIFEST_DIR"), obstacle.path);
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"glTF", "{path} should be a binary glTF");
+    }
+}
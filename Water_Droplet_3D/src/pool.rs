@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::freeze::Frozen;
+use crate::{HasSplashed, ImpactVelocity, Lifetime, SplashAssets, SplashParticle};
+
+// Where free particles wait, out of sight under the floor
+const PARK_POSITION: Vec3 = Vec3::new(0.0, -5.0, 0.0);
+
+// Splash particles are spawned once in `setup` and recycled, instead of being spawned and
+// despawned for every splash. A free particle is hidden and has its rigid body disabled.
+#[derive(Resource)]
+pub struct ParticlePool {
+    pub size: usize,
+    free: Vec<Entity>,
+}
+
+impl Default for ParticlePool {
+    fn default() -> Self {
+        // Some headroom over the default particle budget, for the frame a splash goes over it
+        Self { size: 600, free: Vec::new() }
+    }
+}
+
+impl ParticlePool {
+    pub fn fill(&mut self, commands: &mut Commands, assets: &SplashAssets) {
+        self.free = (0..self.size)
+            .map(|_| {
+                commands
+                    .spawn((
+                        PbrBundle {
+                            mesh: assets.particle_mesh.clone(),
+                            material: assets.particle_material.clone(),
+                            transform: Transform::from_translation(PARK_POSITION),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        RigidBody::Dynamic,
+                        RigidBodyDisabled,
+                        // Scaled along with the transform, like the droplets
+                        Collider::ball(0.1),
+                        Velocity::zero(),
+                        // Wind
+                        ExternalForce::default(),
+                        // Lets resting particles be found and merged
+                        Sleeping::default(),
+                        ImpactVelocity::default(),
+                        // Landing particles throw secondary splashes
+                        ActiveEvents::COLLISION_EVENTS,
+                        SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 },
+                        Lifetime(Timer::default()),
+                    ))
+                    .id()
+            })
+            .collect();
+    }
+
+    // Number of particles currently out in the world
+    pub fn active(&self) -> usize {
+        self.size - self.free.len()
+    }
+
+    // Puts a free particle into the world, moving again even if it was frozen when it was released. Returns false
+    // when every particle is already in use.
+    pub fn launch(
+        &mut self,
+        commands: &mut Commands,
+        position: Vec3,
+        velocity: Vec3,
+        particle: SplashParticle,
+        lifetime_seconds: f32,
+    ) -> bool {
+        let Some(entity) = self.free.pop() else { return false };
+
+        commands
+            .entity(entity)
+            .insert((
+                Transform::from_translation(position).with_scale(Vec3::splat(particle.size)),
+                RigidBody::Dynamic,
+                Velocity::linear(velocity),
+                // Wakes it, and stops it looking asleep until physics says otherwise
+                Sleeping::default(),
+                ImpactVelocity::default(),
+                particle,
+                Lifetime(Timer::from_seconds(lifetime_seconds, TimerMode::Once)),
+                Visibility::Visible,
+            ))
+            .remove::<(RigidBodyDisabled, HasSplashed, Frozen)>();
+        true
+    }
+
+    // Hides a particle and hands it back for the next splash
+    pub fn release(&mut self, commands: &mut Commands, entity: Entity) {
+        commands.entity(entity).insert((RigidBodyDisabled, Visibility::Hidden));
+        self.free.push(entity);
+    }
+}
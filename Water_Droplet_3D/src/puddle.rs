@@ -0,0 +1,104 @@
+use bevy::ecs::query::QueryFilter;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::ripple::floor_transform;
+use crate::terrain::TerrainSettings;
+use crate::{DropletRadius, SplashAssets, SplashEvent};
+
+// Below the ripples (0.011) but above the floor
+const PUDDLE_HEIGHT: f32 = 0.006;
+// How deep the water is spread, which sets how much floor a given volume covers
+const PUDDLE_DEPTH: f32 = 0.25;
+// Splashes this far outside an existing puddle's edge still land in it
+const PUDDLE_MERGE_DISTANCE: f32 = 0.5;
+
+// Water left on the floor by splashes; it keeps growing as more droplets land in it
+#[derive(Component)]
+pub struct Puddle {
+    volume: f32,
+}
+
+impl Puddle {
+    fn radius(&self) -> f32 {
+        (self.volume / (PI * PUDDLE_DEPTH)).sqrt()
+    }
+}
+
+fn puddle_scale(radius: f32) -> Vec3 {
+    // The mesh is a unit circle in XY, laid flat, so only X and Y of the scale matter
+    Vec3::new(radius, radius, 1.0)
+}
+
+pub fn accumulate_puddles(
+    mut commands: Commands,
+    mut splash_events: EventReader<SplashEvent>,
+    droplets: Query<&DropletRadius>,
+    mut puddles: Query<(&mut Puddle, &mut Transform)>,
+    splash_assets: Res<SplashAssets>,
+    terrain: Res<TerrainSettings>,
+) {
+    // Droplets that land in a pool join it rather than leaving a puddle, and bouncing ones keep their water
+    for splash in splash_events.read().filter(|splash| !splash.into_water && !splash.bounced) {
+        let Ok(droplet_radius) = droplets.get(splash.droplet) else { continue };
+        let volume = 4.0 / 3.0 * PI * droplet_radius.0.powi(3);
+        pour(&mut commands, &mut puddles, &splash_assets, &terrain, splash.position, volume);
+    }
+}
+
+// Adds water at `position` to the nearest puddle in reach, or starts a new puddle there
+pub fn pour<F: QueryFilter>(
+    commands: &mut Commands,
+    puddles: &mut Query<(&mut Puddle, &mut Transform), F>,
+    splash_assets: &SplashAssets,
+    terrain: &TerrainSettings,
+    position: Vec3,
+    volume: f32,
+) {
+    let impact = position.xz();
+
+    let nearest = puddles
+        .iter_mut()
+        .map(|(puddle, transform)| {
+            let distance = impact.distance(transform.translation.xz());
+            (distance - puddle.radius(), puddle, transform)
+        })
+        .filter(|(gap, _, _)| *gap < PUDDLE_MERGE_DISTANCE)
+        .min_by(|(a, _, _), (b, _, _)| a.total_cmp(b));
+
+    if let Some((_, mut puddle, mut transform)) = nearest {
+        puddle.volume += volume;
+        transform.scale = puddle_scale(puddle.radius());
+        return;
+    }
+
+    let puddle = Puddle { volume };
+    commands.spawn((
+        PbrBundle {
+            mesh: splash_assets.puddle_mesh.clone(),
+            material: splash_assets.puddle_material.clone(),
+            transform: floor_transform(terrain, position, PUDDLE_HEIGHT).with_scale(puddle_scale(puddle.radius())),
+            ..default()
+        },
+        puddle,
+        NotShadowCaster,
+    ));
+}
+
+// C mops up every puddle without resetting the droplet (R does both). Shift+C clears obstacles instead.
+pub fn clear_puddles(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    puddles: Query<Entity, With<Puddle>>,
+) {
+    if !bindings.just_pressed(Action::ClearPuddles, &keys) {
+        return;
+    }
+
+    for entity in puddles.iter() {
+        commands.entity(entity).despawn();
+    }
+}
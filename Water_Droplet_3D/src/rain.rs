@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::VecDeque;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::tuning::Viscosity;
+use crate::{spawn_droplet, DropletAssets, HasSplashed, SimulationRng};
+
+// Seconds between raindrops unless configured otherwise
+const DEFAULT_INTERVAL: f32 = 0.15;
+
+#[derive(Resource)]
+pub struct RainSettings {
+    pub enabled: bool,
+    // Seconds between raindrops
+    pub interval: f32,
+    // Most raindrops falling at once; past this the oldest make way for new ones
+    pub max_raindrops: usize,
+    pub radius: f32,
+    pub height: f32,
+    // Raindrops land within this distance of the origin on x and z
+    pub half_extent: f32,
+}
+
+impl Default for RainSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: DEFAULT_INTERVAL,
+            max_raindrops: 60,
+            radius: 0.1,
+            height: 6.0,
+            half_extent: 8.0,
+        }
+    }
+}
+
+impl RainSettings {
+    // How heavy the rain is: 1.0 at the default rate, 2.0 with drops twice as often
+    pub fn intensity(&self) -> f32 {
+        if self.interval > 0.0 {
+            DEFAULT_INTERVAL / self.interval
+        } else {
+            0.0
+        }
+    }
+}
+
+// A droplet spawned by rain mode; it is cleaned up as soon as it has splashed.
+// `spawned_at` (elapsed seconds) picks the oldest raindrop to go when there are too many.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Raindrop {
+    spawned_at: f32,
+}
+
+// T starts and stops the rain
+pub fn toggle_rain(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut settings: ResMut<RainSettings>,
+    raindrops: Query<Entity, With<Raindrop>>,
+) {
+    if !bindings.just_pressed(Action::Rain, &keys) {
+        return;
+    }
+
+    settings.enabled = !settings.enabled;
+    info!("Rain {}", if settings.enabled { "on" } else { "off" });
+
+    if !settings.enabled {
+        for entity in raindrops.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_raindrops(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<RainSettings>,
+    droplet_assets: Res<DropletAssets>,
+    viscosity: Res<Viscosity>,
+    mut rng: ResMut<SimulationRng>,
+    // Splashed raindrops are already on their way out, so they don't count towards the cap
+    raindrops: Query<(Entity, &Raindrop), Without<HasSplashed>>,
+    // Time owed to the next raindrop
+    mut pending: Local<f32>,
+) {
+    if !settings.enabled || settings.interval <= 0.0 {
+        *pending = 0.0;
+        return;
+    }
+
+    *pending += time.delta_seconds() / settings.interval;
+    if *pending < 1.0 {
+        return;
+    }
+
+    // Oldest first
+    let mut falling: VecDeque<(f32, Entity)> =
+        raindrops.iter().map(|(entity, raindrop)| (raindrop.spawned_at, entity)).collect();
+    falling.make_contiguous().sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    while *pending >= 1.0 {
+        *pending -= 1.0;
+
+        while falling.len() >= settings.max_raindrops.max(1) {
+            let Some((_, oldest)) = falling.pop_front() else { break };
+            commands.entity(oldest).despawn();
+        }
+
+        let x = rng.rng.gen_range(-settings.half_extent..settings.half_extent);
+        let z = rng.rng.gen_range(-settings.half_extent..settings.half_extent);
+        let position = Vec3::new(x, settings.height, z);
+        let raindrop = spawn_droplet(&mut commands, position, settings.radius, &droplet_assets, &viscosity);
+        commands.entity(raindrop).insert(Raindrop { spawned_at: time.elapsed_seconds() });
+        falling.push_back((time.elapsed_seconds(), raindrop));
+    }
+}
+
+// Raindrops have done their job once they splash; the particles and ripple carry on without them
+pub fn despawn_splashed_raindrops(
+    mut commands: Commands,
+    raindrops: Query<Entity, (With<Raindrop>, With<HasSplashed>)>,
+) {
+    for entity in raindrops.iter() {
+        commands.entity(entity).despawn();
+    }
+}
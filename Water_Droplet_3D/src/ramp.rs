@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::floor::FloorSize;
+use crate::keybindings::{Action, KeyBindings};
+use crate::scene_config::SceneConfig;
+use crate::tuning::DropletTuning;
+use crate::{PrimaryDroplet, ResetDroplets, SpawnPoint};
+
+// The low edge of the ramp rests on the floor here and it rises towards +X, clear of the default drop.
+// This is for the default floor; see `FloorSize::layout_scale`.
+const RAMP_BASE: Vec3 = Vec3::new(1.5, 0.0, 0.0);
+const RAMP_WIDTH: f32 = 1.5;
+const RAMP_THICKNESS: f32 = 0.1;
+const RAMP_ANGLE_STEP: f32 = 5.0;
+const MAX_RAMP_ANGLE: f32 = 60.0;
+// How high above the middle of the ramp the droplet is dropped from when A puts it there
+const DROP_HEIGHT_ABOVE_RAMP: f32 = 3.0;
+
+#[derive(Resource)]
+pub struct RampSettings {
+    // Degrees up from the floor
+    pub angle: f32,
+    pub length: f32,
+    pub friction: f32,
+    // Whether the primary droplet is dropped onto the ramp instead of its usual spot
+    pub drop_on_ramp: bool,
+}
+
+impl Default for RampSettings {
+    fn default() -> Self {
+        Self {
+            angle: 20.0,
+            length: 4.0,
+            friction: 0.3,
+            drop_on_ramp: false,
+        }
+    }
+}
+
+impl RampSettings {
+    // The unit cube mesh and collider are scaled out to the ramp's size
+    fn transform(&self, floor_size: &FloorSize) -> Transform {
+        let rotation = Quat::from_rotation_z(self.angle.to_radians());
+        // Rotating about the base edge: the centre sits half a length along and half a thickness up from it
+        let center =
+            RAMP_BASE * floor_size.layout_scale() + rotation * Vec3::new(self.length / 2.0, RAMP_THICKNESS / 2.0, 0.0);
+        Transform::from_translation(center)
+            .with_rotation(rotation)
+            .with_scale(Vec3::new(self.length, RAMP_THICKNESS, RAMP_WIDTH))
+    }
+
+    fn drop_point(&self, floor_size: &FloorSize) -> Vec3 {
+        let rotation = Quat::from_rotation_z(self.angle.to_radians());
+        RAMP_BASE * floor_size.layout_scale()
+            + rotation * Vec3::new(self.length / 2.0, RAMP_THICKNESS, 0.0)
+            + Vec3::Y * DROP_HEIGHT_ABOVE_RAMP
+    }
+}
+
+#[derive(Component)]
+pub struct Ramp;
+
+pub fn setup_ramp(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    settings: Res<RampSettings>,
+    floor_size: Res<FloorSize>,
+) {
+    let ramp = spawn_ramp(&mut commands, &settings, &floor_size);
+    commands.entity(ramp).insert((
+        meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+        // Matte wood-ish, so it stands out from the checkerboard
+        materials.add(StandardMaterial {
+            base_color: Color::srgb(0.6, 0.45, 0.3),
+            perceptual_roughness: 0.9,
+            ..default()
+        }),
+        VisibilityBundle::default(),
+    ));
+}
+
+// The ramp's body, with nothing to draw it; `--headless` uses it as it is
+pub fn spawn_ramp(commands: &mut Commands, settings: &RampSettings, floor_size: &FloorSize) -> Entity {
+    commands
+        .spawn((
+            TransformBundle::from_transform(settings.transform(floor_size)),
+            Ramp,
+            RigidBody::Fixed,
+            Collider::cuboid(0.5, 0.5, 0.5), // Scaled with the transform
+            Friction::coefficient(settings.friction),
+        ))
+        .id()
+}
+
+// Shift+[ / Shift+] lower and raise the ramp (plain [ ] change the time scale);
+// A toggles dropping the droplet onto the ramp
+pub fn control_ramp(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut settings: ResMut<RampSettings>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    let mut angle = settings.angle;
+    if bindings.just_pressed(Action::LowerRamp, &keys) {
+        angle -= RAMP_ANGLE_STEP;
+    }
+    if bindings.just_pressed(Action::RaiseRamp, &keys) {
+        angle += RAMP_ANGLE_STEP;
+    }
+    let angle = angle.clamp(0.0, MAX_RAMP_ANGLE);
+    if angle != settings.angle {
+        settings.angle = angle;
+        info!("Ramp angle: {angle}°");
+    }
+
+    if bindings.just_pressed(Action::DropOnRamp, &keys) {
+        settings.drop_on_ramp = !settings.drop_on_ramp;
+        info!("Dropping onto the ramp: {}", if settings.drop_on_ramp { "on" } else { "off" });
+        resets.send(ResetDroplets);
+    }
+}
+
+// Moves the ramp (and the droplet's drop point, if it is over the ramp) to match the settings and the floor
+pub fn apply_ramp_settings(
+    settings: Res<RampSettings>,
+    floor_size: Res<FloorSize>,
+    scene: Res<SceneConfig>,
+    tuning: Res<DropletTuning>,
+    mut ramps: Query<(&mut Transform, &mut Friction), With<Ramp>>,
+    mut primary: Query<&mut SpawnPoint, With<PrimaryDroplet>>,
+) {
+    if !settings.is_changed() && !floor_size.is_changed() {
+        return;
+    }
+
+    for (mut transform, mut friction) in ramps.iter_mut() {
+        *transform = settings.transform(&floor_size);
+        friction.coefficient = settings.friction;
+    }
+
+    let drop_point = if settings.drop_on_ramp {
+        settings.drop_point(&floor_size)
+    } else {
+        Vec3::from(scene.droplet_position).with_y(tuning.drop_height)
+    };
+    for mut spawn_point in primary.iter_mut() {
+        spawn_point.0 = drop_point;
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::PrimaryWindow;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::screenshot::{timestamp, OverlaysHidden};
+use crate::ResetDroplets;
+
+const RECORDING_FRAMES: u32 = 90;
+// Simulated time between recorded frames, however long each one takes to render and save, so the frames play
+// back at an even 30 fps
+const RECORDING_FRAME_SECONDS: f64 = 1.0 / 30.0;
+// The same for F3's open-ended recordings, made into videos at 60 fps
+const VIDEO_FRAME_SECONDS: f64 = 1.0 / 60.0;
+
+// Shift+R records the next `RECORDING_FRAMES` frames as numbered PNGs, to be put together into a GIF elsewhere.
+// F3 records every frame until it's pressed again, e.g. for `ffmpeg -framerate 60 -i frame_%04d.png`.
+#[derive(Resource)]
+pub struct Recording {
+    // Each recording gets its own folder in here
+    pub root: PathBuf,
+    capture: Option<Capture>,
+}
+
+struct Capture {
+    dir: PathBuf,
+    frame: u32,
+    // Where it stops by itself, if it does
+    frames: Option<u32>,
+    // The clock from before, for when it's done
+    resume: TimeUpdateStrategy,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self { root: PathBuf::from("recordings"), capture: None }
+    }
+}
+
+impl Recording {
+    pub fn is_recording(&self) -> bool {
+        self.capture.is_some()
+    }
+
+    // Puts each frame `frame_seconds` of simulated time after the last, which Rapier steps through too, so the
+    // physics keeps in time with the frames however slowly they're saved
+    fn start(
+        &mut self,
+        name: &str,
+        frames: Option<u32>,
+        frame_seconds: f64,
+        time_update: &mut TimeUpdateStrategy,
+    ) -> Option<&Path> {
+        let dir = self.root.join(format!("{name}-{}", timestamp()));
+        if let Err(err) = std::fs::create_dir_all(&dir) {
+            error!("Couldn't create {} ({err}); not recording", dir.display());
+            return None;
+        }
+        let resume = std::mem::replace(
+            time_update,
+            TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(frame_seconds)),
+        );
+        let capture = self.capture.insert(Capture { dir, frame: 0, frames, resume });
+        Some(&capture.dir)
+    }
+
+    fn stop(&mut self, time_update: &mut TimeUpdateStrategy) {
+        let Some(capture) = self.capture.take() else { return };
+        info!("Recorded {} frames to {}", capture.frame, capture.dir.display());
+        *time_update = capture.resume;
+    }
+}
+
+// Starts a recording, dropping the droplets again so the splash plays out from the first frame
+pub fn start_recording(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut recording: ResMut<Recording>,
+    mut time_update: ResMut<TimeUpdateStrategy>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    if recording.is_recording() || !bindings.just_pressed(Action::Record, &keys) {
+        return;
+    }
+    let Some(dir) = recording.start("splash", Some(RECORDING_FRAMES), RECORDING_FRAME_SECONDS, &mut time_update) else {
+        return;
+    };
+    info!("Recording {RECORDING_FRAMES} frames to {}", dir.display());
+    resets.send(ResetDroplets);
+}
+
+// F3 starts recording from the scene as it is, and stops it again
+pub fn toggle_recording(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut recording: ResMut<Recording>,
+    mut time_update: ResMut<TimeUpdateStrategy>,
+) {
+    if !bindings.just_pressed(Action::RecordVideo, &keys) {
+        return;
+    }
+    if recording.is_recording() {
+        recording.stop(&mut time_update);
+    } else if let Some(dir) = recording.start("video", None, VIDEO_FRAME_SECONDS, &mut time_update) {
+        info!("Recording to {} until F3", dir.display());
+    }
+}
+
+// Saves one frame per update until the recording is done, then hands the clock back to the timestep
+pub fn capture_frame(
+    mut recording: ResMut<Recording>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut time_update: ResMut<TimeUpdateStrategy>,
+) {
+    let Some(capture) = recording.capture.as_mut() else { return };
+    let Ok(window) = window.get_single() else { return };
+
+    let path = capture.dir.join(format!("frame_{:04}.png", capture.frame));
+    if let Err(err) = screenshots.save_screenshot_to_disk(window, &path) {
+        warn!("Frame {} skipped: {err}", capture.frame);
+        return;
+    }
+    capture.frame += 1;
+    if capture.frames == Some(capture.frame) {
+        recording.stop(&mut time_update);
+    }
+}
+
+// A red REC in the corner while recording, with the frames saved so far. It's in the frames too, like the rest of
+// the overlays, but not in a clean screenshot taken meanwhile.
+#[derive(Component)]
+pub struct RecordingIndicator;
+
+pub fn setup_recording_indicator(mut commands: Commands) {
+    let style = TextStyle { font_size: 22.0, color: Color::srgb(1.0, 0.15, 0.1), ..default() };
+    commands.spawn((
+        TextBundle::from_section("", style).with_style(Style {
+            position_type: PositionType::Absolute,
+            // Under the volume readout, which shares the corner
+            top: Val::Px(40.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        Visibility::Hidden,
+        RecordingIndicator,
+    ));
+}
+
+pub fn update_recording_indicator(
+    recording: Res<Recording>,
+    hidden: Res<OverlaysHidden>,
+    mut indicator: Query<(&mut Text, &mut Visibility), With<RecordingIndicator>>,
+) {
+    let Ok((mut text, mut visibility)) = indicator.get_single_mut() else { return };
+    match &recording.capture {
+        Some(capture) if !hidden.0 => {
+            text.sections[0].value = format!("REC {:04}", capture.frame);
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+        _ => {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screenshot;
+
+    #[test]
+    fn shift_r_starts_a_recording_from_a_fresh_drop_on_an_even_clock() {
+        use bevy::time::TimeUpdateStrategy;
+
+        let root = std::env::temp_dir().join(format!("droplet-recordings-{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<ResetDroplets>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Recording>()
+            .add_systems(Update, start_recording);
+        app.world_mut().resource_mut::<Recording>().root = root.clone();
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::KeyR);
+        app.update();
+
+        assert!(app.world().resource::<Recording>().is_recording());
+        assert!(matches!(app.world().resource::<TimeUpdateStrategy>(), TimeUpdateStrategy::ManualDuration(_)));
+        let resets = app.world().resource::<Events<ResetDroplets>>();
+        assert_eq!(resets.get_reader().read(resets).count(), 1);
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn f3_records_at_sixty_frames_a_second_until_pressed_again() {
+        use bevy::time::TimeUpdateStrategy;
+        use std::time::Duration;
+
+        let root = std::env::temp_dir().join(format!("droplet-videos-{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Recording>()
+            .init_resource::<screenshot::OverlaysHidden>()
+            .add_systems(Update, (toggle_recording, update_recording_indicator).chain());
+        app.world_mut().resource_mut::<Recording>().root = root.clone();
+        let indicator = TextBundle::from_section("", default());
+        let indicator = app.world_mut().spawn((indicator, RecordingIndicator)).id();
+        let press_f3 = |app: &mut App| {
+            let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            keys.release(KeyCode::F3);
+            keys.clear();
+            keys.press(KeyCode::F3);
+            app.update();
+        };
+
+        press_f3(&mut app);
+        assert!(app.world().resource::<Recording>().is_recording());
+        let frame = Duration::from_secs_f64(1.0 / 60.0);
+        let time_update = app.world().resource::<TimeUpdateStrategy>();
+        assert!(matches!(time_update, TimeUpdateStrategy::ManualDuration(step) if *step == frame));
+        assert_eq!(app.world().get::<Text>(indicator).unwrap().sections[0].value, "REC 0000");
+        assert_eq!(*app.world().get::<Visibility>(indicator).unwrap(), Visibility::Inherited);
+
+        // A clean screenshot in the middle of it leaves the REC out
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+        app.world_mut().resource_mut::<screenshot::OverlaysHidden>().0 = true;
+        app.update();
+        assert_eq!(*app.world().get::<Visibility>(indicator).unwrap(), Visibility::Hidden);
+        app.world_mut().resource_mut::<screenshot::OverlaysHidden>().0 = false;
+
+        press_f3(&mut app);
+        assert!(!app.world().resource::<Recording>().is_recording());
+        assert!(matches!(app.world().resource::<TimeUpdateStrategy>(), TimeUpdateStrategy::Automatic));
+        assert_eq!(*app.world().get::<Visibility>(indicator).unwrap(), Visibility::Hidden);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
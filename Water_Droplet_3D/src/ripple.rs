@@ -0,0 +1,104 @@
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+
+use crate::terrain::TerrainSettings;
+use crate::{SplashAssets, SplashEvent, MAX_SPLASH_ENERGY_SCALE, REFERENCE_IMPACT_SPEED};
+
+// Just above the floor so the ring doesn't z-fight with the checkerboard
+const RIPPLE_HEIGHT: f32 = 0.011;
+const RIPPLE_SECONDS: f32 = 1.5;
+const RIPPLE_START_RADIUS: f32 = 0.3;
+const RIPPLE_START_ALPHA: f32 = 0.6;
+// Ripples fade by stepping through a fixed set of materials instead of owning one each
+const RIPPLE_FADE_STEPS: usize = 16;
+// Final ring radius for a drop from the default height
+const REFERENCE_RIPPLE_RADIUS: f32 = 2.0;
+
+// An expanding ring left on the floor by a splash
+#[derive(Component)]
+pub struct Ripple {
+    age: f32,
+    max_radius: f32,
+}
+
+// Materials for each step of a ripple's fade, shared by all ripples
+pub fn fade_materials(materials: &mut Assets<StandardMaterial>) -> Vec<Handle<StandardMaterial>> {
+    (0..RIPPLE_FADE_STEPS)
+        .map(|step| {
+            let alpha = RIPPLE_START_ALPHA * (1.0 - step as f32 / RIPPLE_FADE_STEPS as f32);
+            materials.add(StandardMaterial {
+                base_color: Color::srgba(0.9, 0.95, 1.0, alpha),
+                perceptual_roughness: 0.1,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            })
+        })
+        .collect()
+}
+
+pub fn spawn_ripple(
+    mut commands: Commands,
+    mut splash_events: EventReader<SplashEvent>,
+    splash_assets: Res<SplashAssets>,
+    terrain: Res<TerrainSettings>,
+) {
+    for splash in splash_events.read() {
+        let energy_scale = (splash.impact_speed / REFERENCE_IMPACT_SPEED).min(MAX_SPLASH_ENERGY_SCALE);
+
+        commands.spawn((
+            PbrBundle {
+                // Thin unit ring, scaled up over time
+                mesh: splash_assets.ripple_mesh.clone(),
+                material: splash_assets.ripple_materials[0].clone(),
+                transform: if splash.into_water {
+                    // On the pool's surface, which is where the splash is
+                    Transform::from_translation(splash.position + Vec3::Y * RIPPLE_HEIGHT)
+                        .with_rotation(Quat::from_rotation_arc(Vec3::Z, Vec3::Y))
+                } else {
+                    floor_transform(&terrain, splash.position, RIPPLE_HEIGHT)
+                }
+                .with_scale(Vec3::splat(RIPPLE_START_RADIUS)),
+                ..default()
+            },
+            Ripple {
+                age: 0.0,
+                max_radius: REFERENCE_RIPPLE_RADIUS * energy_scale,
+            },
+            NotShadowCaster,
+            NotShadowReceiver,
+        ));
+    }
+}
+
+// Lays a flat ring or disc (built in XY) on the floor below `position`, following the slope of the terrain
+pub fn floor_transform(terrain: &TerrainSettings, position: Vec3, lift: f32) -> Transform {
+    let normal = terrain.normal_at(position.xz());
+    let ground = position.with_y(terrain.height_at(position.xz()));
+    Transform::from_translation(ground + normal * lift).with_rotation(Quat::from_rotation_arc(Vec3::Z, normal))
+}
+
+pub fn animate_ripples(
+    mut commands: Commands,
+    time: Res<Time>,
+    splash_assets: Res<SplashAssets>,
+    mut query: Query<(Entity, &mut Ripple, &mut Transform, &mut Handle<StandardMaterial>)>,
+) {
+    for (entity, mut ripple, mut transform, mut material) in query.iter_mut() {
+        ripple.age += time.delta_seconds();
+        let progress = ripple.age / RIPPLE_SECONDS;
+
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let radius = RIPPLE_START_RADIUS.lerp(ripple.max_radius, progress);
+        transform.scale = Vec3::splat(radius);
+
+        let fade_step = (progress * RIPPLE_FADE_STEPS as f32) as usize;
+        let faded = &splash_assets.ripple_materials[fade_step.min(RIPPLE_FADE_STEPS - 1)];
+        if *material != *faded {
+            *material = faded.clone();
+        }
+    }
+}
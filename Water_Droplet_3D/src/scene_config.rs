@@ -0,0 +1,813 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use bevy::time::Real;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::camera::TurntableSettings;
+use crate::cli::Cli;
+use crate::daynight::{DayNightSettings, NOON_ILLUMINANCE, NOON_SKY};
+use crate::floor::FloorSize;
+use crate::gravity::GravityPreset;
+use crate::keybindings::{Action, KeyBindings};
+use crate::liquid::{restyle_materials, CurrentLiquid, LiquidType};
+use crate::ramp::RampSettings;
+use crate::simulation::DEFAULT_TIMESTEP;
+use crate::terrain::TerrainSettings;
+use crate::tuning::{Bounciness, DropletTuning, Viscosity, MAX_BOUNCINESS};
+use crate::{DropletAssets, ResetDroplets, SplashAssets};
+use crate::{DropletRadius, PrimaryDroplet, SpawnPoint};
+use crate::{DropletSize, ParticleBudget, ParticleLifetimeSettings, SplashConfig, SplashThreshold};
+use crate::{DROPLET_RADIUS, MAX_DROPLET_RADIUS, MIN_DROPLET_RADIUS};
+
+const SCENE_CONFIG_PATH: &str = "assets/scene.ron";
+// How often the file is checked for edits while running
+const WATCH_INTERVAL_SECONDS: f32 = 0.5;
+
+// The starting scenario, so custom scenes don't need a rebuild.
+// Every field is optional in the file; anything left out keeps the built-in value.
+#[derive(Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SceneConfig {
+    // Where the primary droplet is dropped from (and where R puts it back)
+    pub droplet_position: (f32, f32, f32),
+    pub droplet_radius: f32,
+    pub liquid: LiquidType,
+    // Restitution from 0.0 (splat) to 0.95 (super ball); left out, the liquid's own is used
+    pub bounciness: Option<f32>,
+    // Impact speed (m/s) a landing needs to splash
+    pub splash_threshold: f32,
+    // Downward acceleration in m/s²; negative pulls things up
+    pub gravity: f32,
+    // How far the sun has travelled along its arc, in degrees: 0 is sunrise, 90 noon, 180 sunset
+    pub sun_angle: f32,
+    // The sky's colour at noon, as sRGB from 0 to 1; dawn, dusk and night are mixed in from it
+    pub sky_color: (f32, f32, f32),
+    // The sun's brightness at noon, in lux; it dims towards the horizon and is out at night
+    pub illuminance: f32,
+    // Width and depth of the square floor
+    pub floor_size: f32,
+    pub particles: ParticleConfig,
+    // Extra geometry loaded from a glTF file in assets, if any
+    pub obstacle_scene: Option<ObstacleScene>,
+    // Hills in place of the flat floor
+    pub terrain: TerrainSettings,
+    // Whether the sun casts shadows
+    pub shadows: bool,
+    pub turntable: TurntableSettings,
+    // Simulated seconds per physics step, however long the frames take
+    pub timestep: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParticleConfig {
+    pub count: usize,
+    pub horizontal_spread: f32,
+    // (min, max) upward launch speed
+    pub upward_speed: (f32, f32),
+    // Seconds a particle lives
+    pub lifetime: f32,
+    // Particles alive at once
+    pub budget: usize,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObstacleScene {
+    // A .glb or .gltf file, relative to assets
+    pub path: String,
+    // Where the model's origin is placed
+    pub position: (f32, f32, f32),
+    // Uniform scale applied to the whole model
+    pub scale: f32,
+}
+
+impl Default for SceneConfig {
+    fn default() -> Self {
+        Self {
+            droplet_position: (0.0, 5.0, 0.0),
+            droplet_radius: DROPLET_RADIUS,
+            liquid: LiquidType::default(),
+            bounciness: None,
+            splash_threshold: SplashThreshold::default().0,
+            gravity: 9.81,
+            sun_angle: DayNightSettings::default().time_of_day * 360.0,
+            sky_color: {
+                let sky = NOON_SKY.to_srgba();
+                (sky.red, sky.green, sky.blue)
+            },
+            illuminance: NOON_ILLUMINANCE,
+            floor_size: 20.0,
+            particles: ParticleConfig::default(),
+            obstacle_scene: None,
+            terrain: TerrainSettings::default(),
+            shadows: true,
+            turntable: TurntableSettings::default(),
+            timestep: DEFAULT_TIMESTEP,
+        }
+    }
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        let splash = SplashConfig::default();
+        Self {
+            count: splash.count,
+            horizontal_spread: splash.horizontal_spread,
+            upward_speed: (splash.upward_velocity_range.start, splash.upward_velocity_range.end),
+            lifetime: ParticleLifetimeSettings::default().seconds,
+            budget: ParticleBudget::default().max,
+        }
+    }
+}
+
+impl Default for ObstacleScene {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            position: (0.0, 0.0, 0.0),
+            scale: 1.0,
+        }
+    }
+}
+
+impl SceneConfig {
+    pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+
+    // Puts any out-of-range value back to its default, returning a description of each one
+    pub fn validate(&mut self, pool_size: usize) -> Vec<String> {
+        let defaults = Self::default();
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, name: &str, value: String, expected: &str| {
+            if !ok {
+                problems.push(format!("{name} is {value}, expected {expected}"));
+            }
+            ok
+        };
+
+        let (x, y, z) = self.droplet_position;
+        if !check(
+            x.is_finite() && z.is_finite() && y.is_finite() && y > 0.0,
+            "droplet_position",
+            format!("{:?}", self.droplet_position),
+            "finite numbers with y above the floor (> 0)",
+        ) {
+            self.droplet_position = defaults.droplet_position;
+        }
+        if !check(
+            (MIN_DROPLET_RADIUS..=MAX_DROPLET_RADIUS).contains(&self.droplet_radius),
+            "droplet_radius",
+            self.droplet_radius.to_string(),
+            &format!("{MIN_DROPLET_RADIUS}..={MAX_DROPLET_RADIUS}"),
+        ) {
+            self.droplet_radius = defaults.droplet_radius;
+        }
+        if let Some(bounciness) = self.bounciness {
+            if !check(
+                (0.0..=MAX_BOUNCINESS).contains(&bounciness),
+                "bounciness",
+                bounciness.to_string(),
+                &format!("0..={MAX_BOUNCINESS}"),
+            ) {
+                self.bounciness = defaults.bounciness;
+            }
+        }
+        if !check(
+            (0.0..=20.0).contains(&self.splash_threshold),
+            "splash_threshold",
+            self.splash_threshold.to_string(),
+            "0..=20",
+        ) {
+            self.splash_threshold = defaults.splash_threshold;
+        }
+        if !check((-50.0..=50.0).contains(&self.gravity), "gravity", self.gravity.to_string(), "-50..=50") {
+            self.gravity = defaults.gravity;
+        }
+        if !check(self.sun_angle.is_finite(), "sun_angle", self.sun_angle.to_string(), "a finite angle in degrees") {
+            self.sun_angle = defaults.sun_angle;
+        }
+        let (red, green, blue) = self.sky_color;
+        if !check(
+            [red, green, blue].iter().all(|channel| (0.0..=1.0).contains(channel)),
+            "sky_color",
+            format!("{:?}", self.sky_color),
+            "(red, green, blue) each within 0..=1",
+        ) {
+            self.sky_color = defaults.sky_color;
+        }
+        // Full sunlight is around 100000 lux
+        if !check(
+            (0.0..=150_000.0).contains(&self.illuminance),
+            "illuminance",
+            self.illuminance.to_string(),
+            "0..=150000 lux",
+        ) {
+            self.illuminance = defaults.illuminance;
+        }
+        // Much past 60 the floor texture, and the wet patches painted into it, get too coarse
+        if !check((4.0..=60.0).contains(&self.floor_size), "floor_size", self.floor_size.to_string(), "4..=60") {
+            self.floor_size = defaults.floor_size;
+        }
+
+        let particles = &mut self.particles;
+        if !check((1..=100).contains(&particles.count), "particles.count", particles.count.to_string(), "1..=100") {
+            particles.count = defaults.particles.count;
+        }
+        if !check(
+            (0.0..=10.0).contains(&particles.horizontal_spread),
+            "particles.horizontal_spread",
+            particles.horizontal_spread.to_string(),
+            "0..=10",
+        ) {
+            particles.horizontal_spread = defaults.particles.horizontal_spread;
+        }
+        let (min, max) = particles.upward_speed;
+        if !check(
+            (0.0..=20.0).contains(&min) && (0.0..=20.0).contains(&max) && min <= max,
+            "particles.upward_speed",
+            format!("{:?}", particles.upward_speed),
+            "(min, max) within 0..=20 with min <= max",
+        ) {
+            particles.upward_speed = defaults.particles.upward_speed;
+        }
+        if !check(
+            particles.lifetime > 0.0 && particles.lifetime <= 60.0,
+            "particles.lifetime",
+            particles.lifetime.to_string(),
+            "above 0 and at most 60 seconds",
+        ) {
+            particles.lifetime = defaults.particles.lifetime;
+        }
+        // Every live particle comes out of the pool, so the budget can't go past it
+        if !check(
+            (1..=pool_size).contains(&particles.budget),
+            "particles.budget",
+            particles.budget.to_string(),
+            &format!("1..={pool_size}"),
+        ) {
+            particles.budget = defaults.particles.budget;
+        }
+
+        let terrain = &mut self.terrain;
+        if !check(
+            (1..=256).contains(&terrain.resolution),
+            "terrain.resolution",
+            terrain.resolution.to_string(),
+            "1..=256",
+        ) {
+            terrain.resolution = defaults.terrain.resolution;
+        }
+        if !check((0.0..=3.0).contains(&terrain.amplitude), "terrain.amplitude", terrain.amplitude.to_string(), "0..=3") {
+            terrain.amplitude = defaults.terrain.amplitude;
+        }
+
+        let turntable = &mut self.turntable;
+        if !check(
+            turntable.seconds_per_turn > 0.0 && turntable.seconds_per_turn <= 600.0,
+            "turntable.seconds_per_turn",
+            turntable.seconds_per_turn.to_string(),
+            "above 0 and at most 600 seconds",
+        ) {
+            turntable.seconds_per_turn = defaults.turntable.seconds_per_turn;
+        }
+        if !check(
+            turntable.radius > 0.0 && turntable.radius <= 50.0,
+            "turntable.radius",
+            turntable.radius.to_string(),
+            "above 0 and at most 50",
+        ) {
+            turntable.radius = defaults.turntable.radius;
+        }
+        if !check(
+            (-50.0..=50.0).contains(&turntable.height),
+            "turntable.height",
+            turntable.height.to_string(),
+            "-50..=50",
+        ) {
+            turntable.height = defaults.turntable.height;
+        }
+
+        // Much longer and fast particles tunnel through the floor
+        if !check(
+            (1.0 / 240.0..=1.0 / 15.0).contains(&self.timestep),
+            "timestep",
+            self.timestep.to_string(),
+            "1/240..=1/15 seconds",
+        ) {
+            self.timestep = defaults.timestep;
+        }
+
+        if let Some(obstacle) = &self.obstacle_scene {
+            let (x, y, z) = obstacle.position;
+            let ok = check(!obstacle.path.is_empty(), "obstacle_scene.path", "empty".to_string(), "a model in assets")
+                & check(
+                    x.is_finite() && y.is_finite() && z.is_finite(),
+                    "obstacle_scene.position",
+                    format!("{:?}", obstacle.position),
+                    "finite numbers",
+                )
+                & check(
+                    obstacle.scale.is_finite() && obstacle.scale > 0.0,
+                    "obstacle_scene.scale",
+                    obstacle.scale.to_string(),
+                    "above 0",
+                );
+            // There's no sensible default model, so a broken entry is left out altogether
+            if !ok {
+                self.obstacle_scene = None;
+            }
+        }
+
+        problems
+    }
+
+    // Takes on a reloaded file's values, apart from the ones only read while the scene is built. Those keep their
+    // current values, and the names of any the file changed are returned.
+    pub fn reload_from(&mut self, mut reloaded: SceneConfig) -> Vec<&'static str> {
+        let mut needs_restart = Vec::new();
+        if reloaded.floor_size != self.floor_size {
+            needs_restart.push("floor_size");
+            reloaded.floor_size = self.floor_size;
+        }
+        if reloaded.terrain != self.terrain {
+            needs_restart.push("terrain");
+            reloaded.terrain = self.terrain.clone();
+        }
+        if reloaded.obstacle_scene != self.obstacle_scene {
+            needs_restart.push("obstacle_scene");
+            reloaded.obstacle_scene = self.obstacle_scene.clone();
+        }
+        *self = reloaded;
+        needs_restart
+    }
+
+    pub fn noon_sky(&self) -> Color {
+        let (red, green, blue) = self.sky_color;
+        Color::srgb(red, green, blue)
+    }
+
+    // The splash settings the scene starts with: its particle count, spread and speeds, over the built-in shape
+    pub fn splash_config(&self) -> SplashConfig {
+        let particles = &self.particles;
+        SplashConfig {
+            count: particles.count,
+            horizontal_spread: particles.horizontal_spread,
+            upward_velocity_range: particles.upward_speed.0..particles.upward_speed.1,
+            ..default()
+        }
+    }
+}
+
+fn scene_config_path() -> PathBuf {
+    FileAssetReader::get_base_path().join(SCENE_CONFIG_PATH)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+// The file's scene, or `None` if there's no file
+fn read_scene_file(path: &Path) -> Result<Option<SceneConfig>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            SceneConfig::parse(&text).map(Some).map_err(|err| format!("Couldn't parse {} ({err})", path.display()))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("Couldn't read {} ({err})", path.display())),
+    }
+}
+
+// Reads `assets/scene.ron` before the scene is built, falling back to the built-in scene if it is missing or broken.
+// Options given on the command line go over the top.
+pub fn load_scene_config(mut commands: Commands, pool: Res<crate::pool::ParticlePool>, cli: Option<Res<Cli>>) {
+    let path = scene_config_path();
+    let mut config = match read_scene_file(&path) {
+        Ok(Some(config)) => {
+            info!("Loaded scene from {}", path.display());
+            config
+        }
+        Ok(None) => SceneConfig::default(),
+        Err(err) => {
+            error!("{err}; using the built-in scene");
+            SceneConfig::default()
+        }
+    };
+
+    if let Some(cli) = cli {
+        cli.apply(&mut config);
+    }
+    for problem in config.validate(pool.size) {
+        error!("{}: {problem}; using the default instead", path.display());
+    }
+    commands.insert_resource(config);
+    commands.insert_resource(SceneConfigWatcher { modified: modified_time(&path), since_check: 0.0 });
+}
+
+// When the scene file was last seen to change, so edits can be picked up while running
+#[derive(Resource, Default)]
+pub struct SceneConfigWatcher {
+    modified: Option<SystemTime>,
+    since_check: f32,
+}
+
+// Sent once `SceneConfig` has taken on an edited scene file, with what it was before, so only the values the edit
+// changed are applied
+#[derive(Event)]
+pub struct SceneConfigReloaded {
+    pub previous: SceneConfig,
+}
+
+// What a reload changed, going by the scene from before it. With no reload to compare with, at startup, everything
+// counts as changed.
+struct SceneChanges<'a> {
+    previous: Option<SceneConfig>,
+    config: &'a SceneConfig,
+}
+
+impl<'a> SceneChanges<'a> {
+    fn since_reload(reloads: &mut EventReader<SceneConfigReloaded>, config: &'a SceneConfig) -> Self {
+        let previous = reloads.read().next().map(|reload| reload.previous.clone());
+        // Saved twice in a frame: the first one's `previous` covers both
+        reloads.clear();
+        Self { previous, config }
+    }
+
+    fn changed<T: PartialEq>(&self, value: impl Fn(&SceneConfig) -> T) -> bool {
+        match &self.previous {
+            Some(previous) => value(previous) != value(self.config),
+            None => true,
+        }
+    }
+}
+
+// Reloads the scene file whenever it's saved, or on F5. A file that doesn't parse leaves the running scene as it is.
+// Only runs once `load_scene_config` has read the file, so an app without the scene file never touches it.
+#[allow(clippy::too_many_arguments)]
+pub fn watch_scene_config(
+    time: Res<Time<Real>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    cli: Option<Res<Cli>>,
+    pool: Res<crate::pool::ParticlePool>,
+    mut watcher: ResMut<SceneConfigWatcher>,
+    mut config: ResMut<SceneConfig>,
+    mut reloaded: EventWriter<SceneConfigReloaded>,
+) {
+    let forced = bindings.just_pressed(Action::ReloadScene, &keys);
+    watcher.since_check += time.delta_seconds();
+    if !forced && watcher.since_check < WATCH_INTERVAL_SECONDS {
+        return;
+    }
+    watcher.since_check = 0.0;
+
+    let path = scene_config_path();
+    let modified = modified_time(&path);
+    if !forced && modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+
+    let mut scene = match read_scene_file(&path) {
+        Ok(Some(scene)) => scene,
+        Ok(None) => {
+            warn!("{} is missing; keeping the current scene", path.display());
+            return;
+        }
+        Err(err) => {
+            error!("{err}; keeping the current scene");
+            return;
+        }
+    };
+    if let Some(cli) = cli {
+        cli.apply(&mut scene);
+    }
+    for problem in scene.validate(pool.size) {
+        error!("{}: {problem}; using the default instead", path.display());
+    }
+    let previous = config.clone();
+    for name in config.reload_from(scene) {
+        warn!("{name} changed in {}; restart to apply it", path.display());
+    }
+    info!("Reloaded scene from {}", path.display());
+    reloaded.send(SceneConfigReloaded { previous });
+}
+
+// Carries the values a reload changed over to what's already in the scene: the primary droplet's size and drop
+// point, dropping it again from there, the liquid's materials, and the sun's shadows. `apply_scene_config` has
+// already updated the settings for new droplets and splashes.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_reloaded_scene(
+    config: Res<SceneConfig>,
+    mut reloads: EventReader<SceneConfigReloaded>,
+    ramp: Res<RampSettings>,
+    droplet_assets: Res<DropletAssets>,
+    splash_assets: Res<SplashAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut primary: Query<(&mut DropletRadius, &mut SpawnPoint), With<PrimaryDroplet>>,
+    mut lights: Query<&mut DirectionalLight>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    let changes = SceneChanges::since_reload(&mut reloads, &config);
+
+    if changes.changed(|scene| scene.liquid) {
+        restyle_materials(config.liquid, &droplet_assets, &splash_assets, &mut materials);
+    }
+    let resized = changes.changed(|scene| scene.droplet_radius);
+    let moved = changes.changed(|scene| scene.droplet_position);
+    for (mut radius, mut spawn_point) in primary.iter_mut() {
+        if resized {
+            radius.0 = config.droplet_radius;
+        }
+        // The ramp picks the drop point while dropping onto it
+        if moved && !ramp.drop_on_ramp {
+            let (x, y, z) = config.droplet_position;
+            spawn_point.0 = Vec3::new(x, y, z);
+        }
+    }
+    if changes.changed(|scene| scene.shadows) {
+        for mut light in lights.iter_mut() {
+            light.shadows_enabled = config.shadows;
+        }
+    }
+    if resized || moved {
+        resets.send(ResetDroplets);
+    }
+}
+
+// Hands the loaded settings to the resources that own them at runtime. At startup that's all of them; after a reload
+// it's only the ones the edit changed, so whatever was changed while running (from the panel, the keys, or the floor
+// resized with Shift+V) keeps its value.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_scene_config(
+    config: Res<SceneConfig>,
+    mut reloads: EventReader<SceneConfigReloaded>,
+    (mut splash, mut lifetime, mut budget): (
+        ResMut<SplashConfig>,
+        ResMut<ParticleLifetimeSettings>,
+        ResMut<ParticleBudget>,
+    ),
+    mut liquid: ResMut<CurrentLiquid>,
+    mut gravity: ResMut<GravityPreset>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut day_night: ResMut<DayNightSettings>,
+    mut terrain: ResMut<TerrainSettings>,
+    mut floor_size: ResMut<FloorSize>,
+    mut tuning: ResMut<DropletTuning>,
+    mut bounciness: ResMut<Bounciness>,
+    mut viscosity: ResMut<Viscosity>,
+    mut droplet_size: ResMut<DropletSize>,
+    mut threshold: ResMut<SplashThreshold>,
+    mut turntable: ResMut<TurntableSettings>,
+) {
+    let changes = SceneChanges::since_reload(&mut reloads, &config);
+
+    let particles = &config.particles;
+    let scene_splash = config.splash_config();
+    if changes.changed(|scene| scene.particles.count) {
+        splash.count = scene_splash.count;
+    }
+    if changes.changed(|scene| scene.particles.horizontal_spread) {
+        splash.horizontal_spread = scene_splash.horizontal_spread;
+    }
+    if changes.changed(|scene| scene.particles.upward_speed) {
+        splash.upward_velocity_range = scene_splash.upward_velocity_range;
+    }
+    if changes.changed(|scene| scene.particles.lifetime) {
+        lifetime.seconds = particles.lifetime;
+    }
+    if changes.changed(|scene| scene.particles.budget) {
+        budget.max = particles.budget;
+    }
+
+    if changes.changed(|scene| scene.liquid) {
+        liquid.0 = config.liquid;
+        viscosity.0 = config.liquid.viscosity();
+    }
+    if changes.changed(|scene| (scene.liquid, scene.bounciness)) {
+        bounciness.0 = config.bounciness.unwrap_or(config.liquid.restitution());
+    }
+    if changes.changed(|scene| scene.gravity) {
+        rapier_config.gravity = Vec3::new(0.0, -config.gravity, 0.0);
+        // +/- carry on from whichever preset is nearest
+        *gravity = GravityPreset::closest_to(config.gravity);
+    }
+    if changes.changed(|scene| scene.sun_angle) {
+        day_night.time_of_day = (config.sun_angle / 360.0).rem_euclid(1.0);
+    }
+    if changes.changed(|scene| scene.sky_color) {
+        day_night.noon_sky = config.noon_sky();
+    }
+    if changes.changed(|scene| scene.illuminance) {
+        day_night.noon_illuminance = config.illuminance;
+    }
+    // Only read at startup, as a reload keeps them as they were
+    if changes.changed(|scene| scene.floor_size) {
+        floor_size.0 = config.floor_size;
+    }
+    if changes.changed(|scene| (scene.terrain.clone(), scene.floor_size)) {
+        *terrain = TerrainSettings { size: config.floor_size, ..config.terrain.clone() };
+    }
+    if changes.changed(|scene| scene.droplet_position.1) {
+        tuning.drop_height = config.droplet_position.1;
+    }
+    if changes.changed(|scene| scene.droplet_radius) {
+        droplet_size.0 = config.droplet_radius;
+    }
+    if changes.changed(|scene| scene.splash_threshold) {
+        threshold.0 = config.splash_threshold;
+    }
+    if changes.changed(|scene| scene.turntable.clone()) {
+        *turntable = config.turntable.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camera, cli, daynight, floor, gravity, liquid, pool, simulation_plugin, terrain, tuning, SimulationRng};
+
+    #[test]
+    fn scene_config_keeps_defaults_for_missing_and_out_of_range_fields() {
+        use SceneConfig;
+
+        let text = "(liquid: Honey, floor_size: 500.0, particles: (count: 40, budget: 0))";
+        let mut config = SceneConfig::parse(text).unwrap();
+        let problems = config.validate(600);
+
+        let defaults = SceneConfig::default();
+        assert_eq!(config.liquid, liquid::LiquidType::Honey);
+        assert_eq!(config.particles.count, 40);
+        assert_eq!(config.floor_size, defaults.floor_size);
+        assert_eq!(config.particles.budget, defaults.particles.budget);
+        assert_eq!(config.gravity, defaults.gravity);
+        assert_eq!(problems.len(), 2);
+
+        // Typos are reported rather than silently ignored
+        assert!(SceneConfig::parse("(gravty: 1.6)").is_err());
+
+        // A bounciness past a super ball falls back to the liquid's own
+        let text = "(droplet_radius: 0.8, bounciness: Some(2.0), splash_threshold: 1.0)";
+        let mut config = SceneConfig::parse(text).unwrap();
+        let problems = config.validate(600);
+        assert_eq!((config.droplet_radius, config.bounciness, config.splash_threshold), (0.8, None, 1.0));
+        assert_eq!(problems, ["bounciness is 2, expected 0..=0.95"]);
+
+        // A sky colour past white, or a sun brighter than the real one, is put back
+        let text = "(sky_color: (0.2, 1.5, 0.4), illuminance: 1000000.0)";
+        let mut config = SceneConfig::parse(text).unwrap();
+        assert_eq!(config.validate(600).len(), 2);
+        assert_eq!((config.sky_color, config.illuminance), (defaults.sky_color, defaults.illuminance));
+        let mut config = SceneConfig::parse("(sky_color: (0.1, 0.2, 0.3), illuminance: 50000.0)").unwrap();
+        assert!(config.validate(600).is_empty());
+        assert_eq!(config.noon_sky(), Color::srgb(0.1, 0.2, 0.3));
+    }
+
+    #[test]
+    fn shipped_scene_config_is_valid() {
+        let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/scene.ron")).unwrap();
+        let mut config = SceneConfig::parse(&text).unwrap();
+        assert!(config.validate(pool::ParticlePool::default().size).is_empty());
+    }
+
+    #[test]
+    fn command_line_options_override_the_scene_file() {
+        use clap::Parser;
+
+        let args = ["droplet", "--spawn-height", "8", "--particles-per-splash", "50", "--no-shadows"];
+        let cli = cli::Cli::try_parse_from(args).unwrap();
+        assert_eq!((cli.width, cli.height, cli.fullscreen), (1280.0, 720.0, false));
+
+        let text = "(droplet_position: (1.0, 3.0, 0.0), droplet_radius: 0.8)";
+        let mut config = SceneConfig::parse(text).unwrap();
+        cli.apply(&mut config);
+        assert_eq!(config.droplet_position, (1.0, 8.0, 0.0));
+        assert_eq!(config.droplet_radius, 0.8);
+        assert_eq!(config.particles.count, 50);
+        assert!(!config.shadows);
+
+        assert!(cli::Cli::try_parse_from(["droplet", "--droplet-radius", "big"]).is_err());
+    }
+
+    #[test]
+    fn reloading_the_scene_keeps_what_only_applies_at_startup() {
+        let mut config = SceneConfig::default();
+        let text = "(droplet_radius: 0.9, splash_threshold: 5.0, floor_size: 30.0, liquid: Honey)";
+        let needs_restart = config.reload_from(SceneConfig::parse(text).unwrap());
+        assert_eq!(needs_restart, ["floor_size"]);
+        assert_eq!((config.droplet_radius, config.splash_threshold), (0.9, 5.0));
+        assert_eq!(config.liquid, liquid::LiquidType::Honey);
+        assert_eq!(config.floor_size, SceneConfig::default().floor_size);
+
+        // A file broken mid-edit doesn't parse, so the watcher leaves the running scene alone
+        assert!(SceneConfig::parse("(droplet_radius: 0.9,, )").is_err());
+    }
+
+    #[test]
+    fn without_the_scene_file_the_simulation_leaves_the_app_clocks_and_assets_folder_alone() {
+        use bevy::scene::ScenePlugin;
+        use bevy::time::TimeUpdateStrategy;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ScenePlugin, TransformPlugin, HierarchyPlugin))
+            .init_asset::<Mesh>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<KeyBindings>()
+            .insert_resource(SimulationRng::new(1))
+            .add_plugins(simulation_plugin);
+        app.finish();
+        app.cleanup();
+        // Where the scene file would be read
+        app.world_mut().run_schedule(PreStartup);
+
+        assert!(matches!(app.world().resource::<TimeUpdateStrategy>(), TimeUpdateStrategy::Automatic));
+        assert_eq!(app.world().resource::<Time<Fixed>>().timestep(), Time::<Fixed>::default().timestep());
+        // `assets/scene.ron` places a rock, which the built-in scene doesn't
+        assert_eq!(app.world().resource::<SceneConfig>(), &SceneConfig::default());
+    }
+
+    #[test]
+    fn a_reload_applies_only_what_the_edit_changed() {
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<SceneConfigReloaded>()
+            .insert_resource(RapierConfiguration::new(1.0))
+            .init_resource::<SceneConfig>()
+            .init_resource::<SplashConfig>()
+            .init_resource::<ParticleLifetimeSettings>()
+            .init_resource::<ParticleBudget>()
+            .init_resource::<CurrentLiquid>()
+            .init_resource::<gravity::GravityPreset>()
+            .init_resource::<daynight::DayNightSettings>()
+            .init_resource::<terrain::TerrainSettings>()
+            .init_resource::<floor::FloorSize>()
+            .init_resource::<tuning::DropletTuning>()
+            .init_resource::<tuning::Bounciness>()
+            .init_resource::<tuning::Viscosity>()
+            .init_resource::<DropletSize>()
+            .init_resource::<SplashThreshold>()
+            .init_resource::<camera::TurntableSettings>()
+            .add_systems(Update, apply_scene_config);
+        app.update();
+
+        // Changed while running: Shift+V, PageDown and the panel
+        app.world_mut().resource_mut::<floor::FloorSize>().0 = 30.0;
+        app.world_mut().resource_mut::<ParticleBudget>().max = 100;
+        app.world_mut().resource_mut::<SplashConfig>().count = 40;
+        let sun = app.world().resource::<daynight::DayNightSettings>().time_of_day;
+
+        let previous = app.world().resource::<SceneConfig>().clone();
+        let mut edited = previous.clone();
+        edited.splash_threshold = 6.0;
+        edited.particles.lifetime = 1.5;
+        edited.sky_color = (0.3, 0.3, 0.4);
+        edited.illuminance = 2000.0;
+        app.insert_resource(edited);
+        app.world_mut().send_event(SceneConfigReloaded { previous });
+        app.update();
+
+        assert_eq!(app.world().resource::<SplashThreshold>().0, 6.0);
+        assert_eq!(app.world().resource::<ParticleLifetimeSettings>().seconds, 1.5);
+        assert_eq!(app.world().resource::<floor::FloorSize>().0, 30.0);
+        assert_eq!(app.world().resource::<ParticleBudget>().max, 100);
+        assert_eq!(app.world().resource::<SplashConfig>().count, 40);
+        let day_night = app.world().resource::<daynight::DayNightSettings>();
+        assert_eq!(day_night.time_of_day, sun);
+        assert_eq!((day_night.noon_sky, day_night.noon_illuminance), (Color::srgb(0.3, 0.3, 0.4), 2000.0));
+    }
+
+    #[test]
+    fn the_scene_file_is_only_watched_once_it_has_been_read() {
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<SceneConfigReloaded>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<pool::ParticlePool>()
+            .init_resource::<SceneConfig>()
+            .add_systems(
+                Update,
+                watch_scene_config.run_if(resource_exists::<SceneConfigWatcher>),
+            );
+        let reloads = |app: &App| {
+            let events = app.world().resource::<Events<SceneConfigReloaded>>();
+            events.get_reader().read(events).count()
+        };
+
+        // Without the scene file, F5 does nothing
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::F5);
+        app.update();
+        assert_eq!(reloads(&app), 0);
+
+        // With it, and no `Cli` resource, F5 reloads it
+        app.init_resource::<SceneConfigWatcher>();
+        app.update();
+        assert_eq!(reloads(&app), 1);
+    }
+}
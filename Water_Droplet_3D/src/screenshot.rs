@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::time::Real;
+use bevy::window::PrimaryWindow;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::keybindings::{Action, KeyBindings};
+
+const NOTICE_SECONDS: f32 = 2.0;
+
+// Screenshots waiting for their frame. Only one can be taken per frame, so quick presses line up here instead
+// of being dropped.
+#[derive(Resource)]
+pub struct ScreenshotQueue {
+    // Where they're saved
+    pub dir: PathBuf,
+    pending: VecDeque<QueuedScreenshot>,
+    // The overlays a clean screenshot hid, and how they were, to put back once it's taken
+    hidden: Option<Vec<(Entity, Visibility)>>,
+    // The last screenshot taken, for the notice
+    taken: Option<PathBuf>,
+}
+
+impl Default for ScreenshotQueue {
+    fn default() -> Self {
+        Self { dir: PathBuf::from("screenshots"), pending: VecDeque::new(), hidden: None, taken: None }
+    }
+}
+
+struct QueuedScreenshot {
+    path: PathBuf,
+    // Taken with the overlays hidden
+    clean: bool,
+}
+
+// Set while a clean screenshot is being taken, for the egui panels to stay out of it
+#[derive(Resource, Default)]
+pub struct OverlaysHidden(pub bool);
+
+// Run condition for overlays drawn outside Bevy's UI
+#[cfg(feature = "egui")]
+pub fn overlays_shown(hidden: Res<OverlaysHidden>) -> bool {
+    !hidden.0
+}
+
+// Shows the name of the screenshot just taken. It's left out of the screenshot itself, showing from the frame
+// after.
+#[derive(Component)]
+pub struct ScreenshotNotice;
+
+pub fn setup_screenshot_notice(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section("", TextStyle { font_size: 18.0, color: Color::WHITE, ..default() }).with_style(
+            Style { position_type: PositionType::Absolute, bottom: Val::Px(8.0), right: Val::Px(8.0), ..default() },
+        ),
+        Visibility::Hidden,
+        ScreenshotNotice,
+    ));
+}
+
+// F2 queues a screenshot of the current frame into the screenshots folder, named after the (UTC) time it was taken.
+// Shift+F2 takes it without the HUD, panels and other overlays.
+pub fn take_screenshot(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut queue: ResMut<ScreenshotQueue>,
+) {
+    let clean = bindings.just_pressed(Action::CleanScreenshot, &keys);
+    if !clean && !bindings.just_pressed(Action::Screenshot, &keys) {
+        return;
+    }
+    if let Err(err) = std::fs::create_dir_all(&queue.dir) {
+        error!("Couldn't create {} ({err}); screenshot skipped", queue.dir.display());
+        return;
+    }
+
+    let path = queue.unused_path(&file_timestamp());
+    queue.pending.push_back(QueuedScreenshot { path, clean });
+}
+
+impl ScreenshotQueue {
+    // `droplet_<timestamp>.png`, numbered if another screenshot in the same second already has that name
+    fn unused_path(&self, timestamp: &str) -> PathBuf {
+        (1..)
+            .map(|n| match n {
+                1 => self.dir.join(format!("droplet_{timestamp}.png")),
+                n => self.dir.join(format!("droplet_{timestamp}_{n}.png")),
+            })
+            .find(|path| !path.exists() && !self.pending.iter().any(|queued| queued.path == *path))
+            .unwrap()
+    }
+}
+
+// Takes the next queued screenshot. A clean one hides the overlays first and is taken the frame after, once every
+// overlay has been drawn without them; they come back the frame after that.
+#[allow(clippy::type_complexity)]
+pub fn capture_queued_screenshot(
+    mut queue: ResMut<ScreenshotQueue>,
+    mut hidden: ResMut<OverlaysHidden>,
+    mut gizmos: ResMut<GizmoConfigStore>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut overlays: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>, Without<ScreenshotNotice>)>,
+) {
+    let clean_next = queue.pending.front().is_some_and(|next| next.clean);
+    if !clean_next {
+        if let Some(previous) = queue.hidden.take() {
+            for (entity, visibility) in previous {
+                if let Ok((_, mut current)) = overlays.get_mut(entity) {
+                    *current = visibility;
+                }
+            }
+            hidden.0 = false;
+            gizmos.config_mut::<DefaultGizmoConfigGroup>().0.enabled = true;
+        }
+    }
+
+    let Some(next) = queue.pending.front() else { return };
+    if next.clean && queue.hidden.is_none() {
+        let mut previous = Vec::new();
+        for (entity, mut visibility) in overlays.iter_mut() {
+            previous.push((entity, *visibility));
+            *visibility = Visibility::Hidden;
+        }
+        queue.hidden = Some(previous);
+        hidden.0 = true;
+        gizmos.config_mut::<DefaultGizmoConfigGroup>().0.enabled = false;
+        return;
+    }
+
+    let Ok(window) = window.get_single() else { return };
+    // Fails while the window already has a screenshot this frame, like a recording's; it's tried again next frame
+    if screenshots.save_screenshot_to_disk(window, &next.path).is_err() {
+        return;
+    }
+    let Some(taken) = queue.pending.pop_front() else { return };
+    info!("Saving screenshot to {}", taken.path.display());
+    queue.taken = Some(taken.path);
+}
+
+// Runs before the capture, so a screenshot never shows the notice for the one before it
+pub fn show_screenshot_notice(
+    time: Res<Time<Real>>,
+    mut queue: ResMut<ScreenshotQueue>,
+    hidden: Res<OverlaysHidden>,
+    mut remaining: Local<f32>,
+    mut notice: Query<(&mut Text, &mut Visibility), With<ScreenshotNotice>>,
+) {
+    let Ok((mut text, mut visibility)) = notice.get_single_mut() else { return };
+    if let Some(path) = queue.taken.take() {
+        text.sections[0].value = format!("Saved {}", path.display());
+        *remaining = NOTICE_SECONDS;
+    } else {
+        *remaining -= time.delta_seconds();
+    }
+    let shown = *remaining > 0.0 && !hidden.0;
+    visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+}
+
+// `YYYY-MM-DD_HH-MM-SS-mmm`, with milliseconds so recordings started in the same second don't share a folder
+pub fn timestamp() -> String {
+    let (since_epoch, (year, month, day), time_of_day) = utc_now();
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}-{:03}",
+        time_of_day / 3600,
+        time_of_day / 60 % 60,
+        time_of_day % 60,
+        since_epoch.subsec_millis(),
+    )
+}
+
+// `YYYYMMDD_HHMMSS`
+fn file_timestamp() -> String {
+    let (_, (year, month, day), time_of_day) = utc_now();
+    format!("{year:04}{month:02}{day:02}_{:02}{:02}{:02}", time_of_day / 3600, time_of_day / 60 % 60, time_of_day % 60)
+}
+
+// The time since the epoch, today's date, and the seconds since midnight, all UTC
+fn utc_now() -> (Duration, (i64, u32, u32), u64) {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    (since_epoch, civil_from_days((seconds / 86_400) as i64), seconds % 86_400)
+}
+
+// Days since 1970-01-01 to a (year, month, day) date, after Howard Hinnant's `civil_from_days`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_clean_screenshot_hides_the_overlays_for_its_frame_and_then_names_the_file() {
+        use bevy::render::view::screenshot::ScreenshotManager;
+        use bevy::window::PrimaryWindow;
+
+        let dir = std::env::temp_dir().join(format!("droplet-screenshots-{}", std::process::id()));
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<GizmoConfigStore>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ScreenshotManager>()
+            .init_resource::<ScreenshotQueue>()
+            .init_resource::<OverlaysHidden>()
+            .add_systems(
+                Update,
+                (take_screenshot, show_screenshot_notice, capture_queued_screenshot)
+                    .chain(),
+            );
+        app.world_mut().resource_mut::<GizmoConfigStore>().insert(GizmoConfig::default(), DefaultGizmoConfigGroup);
+        app.world_mut().resource_mut::<ScreenshotQueue>().dir = dir.clone();
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let hud = app.world_mut().spawn(NodeBundle::default()).id();
+        let notice = TextBundle::from_section("", default());
+        let notice = app.world_mut().spawn((notice, ScreenshotNotice)).id();
+        let overlays_hidden = |app: &App| {
+            let gizmos = app.world().resource::<GizmoConfigStore>();
+            (
+                *app.world().get::<Visibility>(hud).unwrap() == Visibility::Hidden,
+                app.world().resource::<OverlaysHidden>().0,
+                !gizmos.config::<DefaultGizmoConfigGroup>().0.enabled,
+            )
+        };
+
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::F2);
+        app.update();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().clear();
+        assert!(dir.is_dir());
+        assert_eq!(overlays_hidden(&app), (true, true, true));
+
+        // Taken this frame, with the overlays still hidden, and they're back the next
+        app.update();
+        assert_eq!(overlays_hidden(&app), (true, true, true));
+        app.update();
+        assert_eq!(overlays_hidden(&app), (false, false, false));
+
+        let text = &app.world().get::<Text>(notice).unwrap().sections[0].value;
+        let name = text.rsplit(std::path::MAIN_SEPARATOR).next().unwrap();
+        assert!(text.starts_with("Saved"), "{text}");
+        // droplet_YYYYMMDD_HHMMSS.png
+        assert_eq!(name.len(), "droplet_20260101_120000.png".len(), "{name}");
+        assert!(name.starts_with("droplet_") && name.ends_with(".png"), "{name}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::pool::ParticlePool;
+use crate::{HasSplashed, ImpactVelocity, ParticleBudget, ParticleLifetimeSettings, SimulationRng, SplashParticle};
+
+// Particles this deep don't splash any further
+const MAX_SPLASH_DEPTH: u8 = 2;
+// A particle has to land at least this fast (m/s) to throw a secondary splash
+const SECONDARY_SPLASH_SPEED: f32 = 2.0;
+const SECONDARY_PARTICLES: std::ops::RangeInclusive<usize> = 2..=4;
+const SECONDARY_PARTICLE_SCALE: f32 = 0.4;
+// Secondary particles are thrown with a fraction of the landing speed
+const SECONDARY_SPEED_FACTOR: f32 = 0.3;
+
+// A splash particle landing hard enough to throw a secondary splash, for anything that wants to follow along
+#[derive(Event)]
+pub struct ParticleSplashEvent {
+    pub position: Vec3,
+    pub impact_speed: f32,
+}
+
+// A splash particle that lands hard enough throws a few smaller particles of its own.
+// Each particle only splashes once, and contacts between particles themselves are ignored.
+// Secondary splashes only use spare room in the particle budget rather than evicting anything.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn splash_landed_particles(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut particle_splash_events: EventWriter<ParticleSplashEvent>,
+    landed: Query<(&Transform, &ImpactVelocity, &SplashParticle), (Without<HasSplashed>, Without<RigidBodyDisabled>)>,
+    others: Query<Has<SplashParticle>>,
+    budget: Res<ParticleBudget>,
+    mut particle_pool: ResMut<ParticlePool>,
+    time: Res<Time>,
+    lifetime: Res<ParticleLifetimeSettings>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    let mut splashed: Vec<Entity> = Vec::new();
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+
+        for (particle_entity, other) in [(*e1, *e2), (*e2, *e1)] {
+            if splashed.contains(&particle_entity) || others.get(other).unwrap_or(false) {
+                continue;
+            }
+            let Ok((transform, impact_velocity, particle)) = landed.get(particle_entity) else { continue };
+            if particle.splash_depth >= MAX_SPLASH_DEPTH {
+                continue;
+            }
+
+            let impact_speed = impact_velocity.0.length();
+            if impact_speed < SECONDARY_SPLASH_SPEED {
+                continue;
+            }
+
+            commands.entity(particle_entity).insert(HasSplashed);
+            splashed.push(particle_entity);
+            particle_splash_events.send(ParticleSplashEvent { position: transform.translation, impact_speed });
+
+            let count = rng
+                .rng
+                .gen_range(SECONDARY_PARTICLES)
+                .min(budget.max.saturating_sub(particle_pool.active()));
+
+            let speed = impact_speed * SECONDARY_SPEED_FACTOR;
+            for _ in 0..count {
+                let rng = &mut rng.rng;
+                let velocity = Vec3::new(
+                    rng.gen_range(-0.5..0.5),
+                    rng.gen_range(0.5..1.0),
+                    rng.gen_range(-0.5..0.5),
+                ) * speed;
+
+                // Each generation of particles is smaller than the one that threw it
+                let child = SplashParticle {
+                    splash_depth: particle.splash_depth + 1,
+                    spawned_at: time.elapsed_seconds(),
+                    size: particle.size * SECONDARY_PARTICLE_SCALE,
+                };
+                // Smaller particles don't need to hang around as long
+                if !particle_pool.launch(&mut commands, transform.translation, velocity, child, lifetime.seconds * 0.5) {
+                    return;
+                }
+            }
+        }
+    }
+}
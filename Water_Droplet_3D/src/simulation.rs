@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::time::Duration;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::scene_config::SceneConfig;
+
+#[derive(Resource, Default)]
+pub struct SimState {
+    pub paused: bool,
+    // Asked for by period while paused, and used up by the next physics step
+    pub step_pending: bool,
+}
+
+// Run condition for anything that should freeze while the simulation is paused
+pub fn simulation_running(state: Res<SimState>) -> bool {
+    !state.paused
+}
+
+// P pauses/resumes; while paused, period advances exactly one physics step
+pub fn control_simulation(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut state: ResMut<SimState>) {
+    if bindings.just_pressed(Action::Pause, &keys) {
+        state.paused = !state.paused;
+        info!("Simulation {}", if state.paused { "paused" } else { "resumed" });
+    }
+    if state.paused && bindings.just_pressed(Action::Step, &keys) {
+        state.step_pending = true;
+    }
+}
+
+// Runs ahead of each physics step, which is as long as the app's fixed timestep. Rapier runs in `FixedUpdate`,
+// stepping as many times as the frame's time covers, so the same seed and inputs play out the same on every run
+// however fast the frames come. A frame can take no steps at all, so a period press waits here for the next one
+// rather than switching the pipeline on for a frame.
+pub fn gate_physics_step(
+    time: Res<Time>,
+    mut state: ResMut<SimState>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let step = state.step_pending;
+    if step {
+        state.step_pending = false;
+    }
+    let active = !state.paused || step;
+    if rapier_config.physics_pipeline_active != active {
+        rapier_config.physics_pipeline_active = active;
+    }
+    let mode = fixed_step(time.delta_seconds());
+    if rapier_config.timestep_mode != mode {
+        rapier_config.timestep_mode = mode;
+    }
+}
+
+pub const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+
+// Rapier's step for a timestep
+pub fn fixed_step(timestep: f32) -> TimestepMode {
+    TimestepMode::Fixed { dt: timestep, substeps: 1 }
+}
+
+// Puts the app's fixed clock, and the physics with it, on the scene's timestep
+pub fn apply_timestep(config: Res<SceneConfig>, mut fixed_time: ResMut<Time<Fixed>>) {
+    let timestep = Duration::from_secs_f32(config.timestep);
+    if fixed_time.timestep() != timestep {
+        fixed_time.set_timestep(timestep);
+    }
+}
+
+const MIN_TIME_SCALE: f32 = 0.1;
+const MAX_TIME_SCALE: f32 = 2.0;
+
+// How fast simulated time runs relative to real time, for watching splashes in slow motion
+#[derive(Resource, PartialEq)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// [ halves and ] doubles the time scale (with Shift they tilt the ramp instead).
+// Applied through virtual time, which Rapier's step and every `Res<Time>` reader in `Update` (wobble, light orbit,
+// ripples, lifetimes) already follow, so everything slows together.
+pub fn control_time_scale(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut time_scale: ResMut<TimeScale>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let mut scale = time_scale.0;
+    if bindings.just_pressed(Action::SlowDown, &keys) {
+        scale *= 0.5;
+    }
+    if bindings.just_pressed(Action::SpeedUp, &keys) {
+        scale *= 2.0;
+    }
+    time_scale.set_if_neq(TimeScale(scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE)));
+
+    // Also picks up changes made to the resource from elsewhere
+    if time_scale.is_changed() {
+        time.set_relative_speed(time_scale.0);
+        info!("Time scale: {:.2}x", time_scale.0);
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::LoadState;
+use bevy::core_pipeline::Skybox;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension,
+};
+
+// Six square faces stacked top to bottom in the order +X, -X, +Y, -Y, +Z, -Z.
+// Optional: without it the sky is a generated gradient.
+const SKYBOX_PATH: &str = "textures/skybox.png";
+// Sky brightness at noon in cd/m², which the default camera exposure shows as a normal daylight sky
+pub const SKYBOX_BRIGHTNESS: f32 = 1000.0;
+// The gradient is smooth, so small faces are plenty
+const GRADIENT_FACE_SIZE: usize = 64;
+
+const ZENITH_COLOR: Color = Color::srgb(0.3, 0.55, 0.85);
+const HORIZON_COLOR: Color = Color::srgb(0.75, 0.88, 0.95);
+const GROUND_COLOR: Color = Color::srgb(0.35, 0.33, 0.3);
+
+// Cubemaps loaded from assets, which arrive as one tall image of six stacked faces and are turned
+// into cubemaps once they finish loading. Each is dropped from here once it's been dealt with.
+#[derive(Resource, Default)]
+pub struct StackedCubemaps(Vec<Handle<Image>>);
+
+impl StackedCubemaps {
+    pub fn load(&mut self, asset_server: &AssetServer, path: &'static str) -> Handle<Image> {
+        let image = asset_server.load(path);
+        self.0.push(image.clone());
+        image
+    }
+}
+
+// Gives the camera a skybox: the cubemap in assets if there is one, the gradient otherwise
+pub fn setup_skybox(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut cubemaps: ResMut<StackedCubemaps>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    let image = if FileAssetReader::get_base_path().join("assets").join(SKYBOX_PATH).exists() {
+        cubemaps.load(&asset_server, SKYBOX_PATH)
+    } else {
+        info!("No {SKYBOX_PATH} in assets, using a gradient sky");
+        images.add(gradient_cubemap())
+    };
+
+    for camera in cameras.iter() {
+        commands.entity(camera).insert(Skybox { image: image.clone(), brightness: SKYBOX_BRIGHTNESS });
+    }
+}
+
+// Reinterprets each stacked image as the six layers of a cubemap once it has loaded. One that fails to load or
+// isn't six faces can't be drawn: a skybox showing it falls back to the gradient, and an environment map using it
+// is taken off, leaving the ambient light.
+pub fn finish_loading_cubemaps(
+    mut commands: Commands,
+    mut cubemaps: ResMut<StackedCubemaps>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    skyboxes: Query<(Entity, &Skybox)>,
+    environment_maps: Query<(Entity, &EnvironmentMapLight)>,
+) {
+    let mut unusable = Vec::new();
+    cubemaps.0.retain(|handle| {
+        let path = asset_server.get_path(handle).map(|path| path.to_string()).unwrap_or_default();
+        match asset_server.get_load_state(handle) {
+            Some(LoadState::Failed(err)) => {
+                error!("Couldn't load {path} ({err})");
+                unusable.push(handle.id());
+                false
+            }
+            Some(LoadState::Loaded) => {
+                let Some(image) = images.get_mut(handle) else { return true };
+                if image.height() != image.width() * 6 {
+                    error!("{path} should be 6 square faces stacked vertically, but is {:?}", image.size());
+                    unusable.push(handle.id());
+                    return false;
+                }
+                image.reinterpret_stacked_2d_as_array(6);
+                image.texture_view_descriptor = Some(TextureViewDescriptor {
+                    dimension: Some(TextureViewDimension::Cube),
+                    ..default()
+                });
+                false
+            }
+            _ => true,
+        }
+    });
+    if unusable.is_empty() {
+        return;
+    }
+
+    let mut gradient = None;
+    for (camera, skybox) in skyboxes.iter().filter(|(_, skybox)| unusable.contains(&skybox.image.id())) {
+        info!("Using a gradient sky instead");
+        let image = gradient.get_or_insert_with(|| images.add(gradient_cubemap())).clone();
+        commands.entity(camera).insert(Skybox { image, brightness: skybox.brightness });
+    }
+    for (camera, light) in environment_maps.iter() {
+        if unusable.contains(&light.diffuse_map.id()) || unusable.contains(&light.specular_map.id()) {
+            warn!("Lighting without the environment map, so the droplet loses its rim reflections");
+            commands.entity(camera).remove::<EnvironmentMapLight>();
+        }
+    }
+}
+
+// A sky that fades from the horizon up to a deeper blue overhead, over a plain ground
+fn gradient_cubemap() -> Image {
+    const SIZE: usize = GRADIENT_FACE_SIZE;
+    let mut pixels = Vec::with_capacity(SIZE * SIZE * 6 * 4);
+
+    for face in 0..6 {
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                // Centre of the pixel, from -1 to 1 across the face
+                let u = (x as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+                let v = (y as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+                let direction = match face {
+                    0 => Vec3::new(1.0, -v, -u),
+                    1 => Vec3::new(-1.0, -v, u),
+                    2 => Vec3::new(u, 1.0, v),
+                    3 => Vec3::new(u, -1.0, -v),
+                    4 => Vec3::new(u, -v, 1.0),
+                    _ => Vec3::new(-u, -v, -1.0),
+                };
+                let height = direction.normalize().y;
+
+                let color = if height >= 0.0 {
+                    HORIZON_COLOR.mix(&ZENITH_COLOR, height.sqrt())
+                } else {
+                    // A soft edge rather than a hard line at the horizon
+                    HORIZON_COLOR.mix(&GROUND_COLOR, (-height * 8.0).min(1.0))
+                };
+                pixels.extend_from_slice(&color.to_srgba().to_u8_array());
+            }
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: SIZE as u32,
+            height: SIZE as u32,
+            depth_or_array_layers: 6,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..default()
+    });
+    image
+}
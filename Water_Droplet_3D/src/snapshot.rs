@@ -0,0 +1,289 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy::scene::serde::SceneDeserializer;
+use bevy_rapier3d::prelude::*;
+use serde::de::DeserializeSeed;
+use std::path::{Path, PathBuf};
+
+use crate::droplet_color::DropletColor;
+use crate::freeze::{Frozen, FrozenParticles};
+use crate::keybindings::{Action, KeyBindings};
+use crate::liquid::{restyle_materials, CurrentLiquid, LiquidType};
+use crate::pool::ParticlePool;
+use crate::rain::Raindrop;
+use crate::split::SplitThreshold;
+use crate::tuning::{Bounciness, DropletTuning, Viscosity};
+use crate::{attach_droplet_body, DropletAssets, SplashAssets};
+use crate::{
+    Droplet, DropletRadius, DropletSize, HasSplashed, ImpactVelocity, Lifetime, ParticleBudget,
+    ParticleLifetimeSettings, PrimaryDroplet, SpawnPoint, SplashConfig, SplashParticle, SplashThreshold, Squash,
+};
+
+const SNAPSHOT_PATH: &str = "snapshots/snapshot.scn.ron";
+
+// Registers the crate's own components and settings for reflection, which snapshots are saved and restored
+// through and the inspector shows them with
+pub fn plugin(app: &mut App) {
+    app.register_type::<Droplet>()
+        .register_type::<PrimaryDroplet>()
+        .register_type::<DropletRadius>()
+        .register_type::<DropletColor>()
+        .register_type::<SpawnPoint>()
+        .register_type::<ImpactVelocity>()
+        .register_type::<HasSplashed>()
+        .register_type::<Squash>()
+        .register_type::<Raindrop>()
+        .register_type::<SplashParticle>()
+        .register_type::<Lifetime>()
+        .register_type::<Frozen>()
+        .register_type::<FrozenParticles>()
+        .register_type::<LiquidType>()
+        .register_type::<CurrentLiquid>()
+        .register_type::<DropletSize>()
+        .register_type::<DropletTuning>()
+        .register_type::<Bounciness>()
+        .register_type::<Viscosity>()
+        .register_type::<SplashConfig>()
+        .register_type::<SplashThreshold>()
+        .register_type::<SplitThreshold>()
+        .register_type::<ParticleBudget>()
+        .register_type::<ParticleLifetimeSettings>();
+}
+
+// An entity a snapshot has just put back, still to be rebuilt into a droplet or particle
+#[derive(Component)]
+pub struct Restored;
+
+fn snapshot_path() -> PathBuf {
+    FileAssetReader::get_base_path().join(SNAPSHOT_PATH)
+}
+
+fn pressed(world: &World, action: Action) -> bool {
+    world.resource::<KeyBindings>().just_pressed(action, world.resource::<ButtonInput<KeyCode>>())
+}
+
+// The droplets and the particles in the air, where they are and how they're moving, with the settings they
+// move by. Colliders, meshes and the like are left out; they're rebuilt on restore.
+pub fn snapshot(world: &mut World) -> DynamicScene {
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Droplet>, (With<SplashParticle>, Without<RigidBodyDisabled>))>>()
+        .iter(world)
+        .collect();
+    DynamicSceneBuilder::from_world(world)
+        .deny_all()
+        .allow::<Transform>()
+        .allow::<Velocity>()
+        .allow::<Droplet>()
+        .allow::<PrimaryDroplet>()
+        .allow::<DropletRadius>()
+        .allow::<DropletColor>()
+        .allow::<SpawnPoint>()
+        .allow::<ImpactVelocity>()
+        .allow::<HasSplashed>()
+        .allow::<Squash>()
+        .allow::<Raindrop>()
+        .allow::<SplashParticle>()
+        .allow::<Lifetime>()
+        .allow::<Frozen>()
+        .deny_all_resources()
+        .allow_resource::<CurrentLiquid>()
+        .allow_resource::<DropletSize>()
+        .allow_resource::<DropletTuning>()
+        .allow_resource::<Bounciness>()
+        .allow_resource::<Viscosity>()
+        .allow_resource::<SplashConfig>()
+        .allow_resource::<SplashThreshold>()
+        .allow_resource::<SplitThreshold>()
+        .allow_resource::<ParticleBudget>()
+        .allow_resource::<ParticleLifetimeSettings>()
+        .allow_resource::<FrozenParticles>()
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build()
+}
+
+// Swaps the droplets and particles in play for the snapshot's, and its settings for the current ones
+pub fn restore(world: &mut World, scene: &DynamicScene) -> Result<(), String> {
+    let droplets: Vec<Entity> = world.query_filtered::<Entity, With<Droplet>>().iter(world).collect();
+    for droplet in droplets {
+        world.entity_mut(droplet).despawn_recursive();
+    }
+    let particles: Vec<Entity> = world
+        .query_filtered::<Entity, (With<SplashParticle>, Without<RigidBodyDisabled>)>()
+        .iter(world)
+        .collect();
+    world.resource_scope(|world, mut pool: Mut<ParticlePool>| {
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        for particle in particles {
+            pool.release(&mut commands, particle);
+        }
+        queue.apply(world);
+    });
+
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world(world, &mut entity_map).map_err(|err| err.to_string())?;
+    for entity in entity_map.values() {
+        world.entity_mut(*entity).insert(Restored);
+    }
+    Ok(())
+}
+
+fn write_snapshot(world: &mut World, path: &Path) -> Result<(), String> {
+    let scene = snapshot(world);
+    let text = scene.serialize(&world.resource::<AppTypeRegistry>().read()).map_err(|err| err.to_string())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|err| err.to_string())
+}
+
+pub fn read_snapshot(world: &World, text: &str) -> Result<DynamicScene, String> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::de::Deserializer::from_str(text).map_err(|err| err.to_string())?;
+    SceneDeserializer { type_registry: &registry }.deserialize(&mut deserializer).map_err(|err| err.to_string())
+}
+
+// F6 saves the moment to a file, overwriting the last one
+pub fn save_snapshot(world: &mut World) {
+    if !pressed(world, Action::SaveSnapshot) {
+        return;
+    }
+    let path = snapshot_path();
+    match write_snapshot(world, &path) {
+        Ok(()) => info!("Saved snapshot to {}", path.display()),
+        Err(err) => error!("Couldn't save snapshot to {} ({err})", path.display()),
+    }
+}
+
+// F7 goes back to it. A missing or broken file leaves the scene as it is.
+pub fn restore_snapshot(world: &mut World) {
+    if !pressed(world, Action::RestoreSnapshot) {
+        return;
+    }
+    let path = snapshot_path();
+    let scene = match std::fs::read_to_string(&path) {
+        Ok(text) => read_snapshot(world, &text),
+        Err(err) => Err(err.to_string()),
+    };
+    let restored = scene.and_then(|scene| restore(world, &scene));
+    match restored {
+        Ok(()) => info!("Restored snapshot from {}", path.display()),
+        Err(err) => error!("Couldn't restore snapshot from {} ({err})", path.display()),
+    }
+}
+
+// Droplets get back their collider, mesh and material. Particles are relaunched from the pool where they were, so
+// the pool still owns every particle in play. A frozen particle is relaunched moving as it will once thawed, and
+// frozen again straight away if the snapshot's spray was frozen.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn rebuild_restored(
+    mut commands: Commands,
+    droplet_assets: Res<DropletAssets>,
+    splash_assets: Res<SplashAssets>,
+    liquid: Res<CurrentLiquid>,
+    viscosity: Res<Viscosity>,
+    lifetime: Res<ParticleLifetimeSettings>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut pool: ResMut<ParticlePool>,
+    restored: Query<
+        (
+            Entity,
+            Has<Droplet>,
+            &Transform,
+            Option<&Velocity>,
+            Option<&SplashParticle>,
+            Option<&Lifetime>,
+            Option<&Frozen>,
+        ),
+        With<Restored>,
+    >,
+) {
+    if restored.is_empty() {
+        return;
+    }
+    // The snapshot may have been of another liquid
+    restyle_materials(liquid.0, &droplet_assets, &splash_assets, &mut materials);
+
+    for (entity, is_droplet, transform, velocity, particle, particle_lifetime, frozen) in restored.iter() {
+        if is_droplet {
+            attach_droplet_body(&mut commands, entity, &droplet_assets, &viscosity);
+            commands.entity(entity).remove::<Restored>();
+            continue;
+        }
+        if let Some(particle) = particle {
+            let particle = SplashParticle {
+                splash_depth: particle.splash_depth,
+                spawned_at: particle.spawned_at,
+                size: particle.size,
+            };
+            let seconds = particle_lifetime.map_or(lifetime.seconds, |left| left.0.remaining_secs());
+            let velocity = frozen.map(Frozen::velocity).or(velocity.copied());
+            let velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.linvel);
+            pool.launch(&mut commands, transform.translation, velocity, particle, seconds);
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{freeze, pool, tuning};
+
+    #[test]
+    fn a_restored_snapshot_puts_droplets_back_where_they_were_and_moving_as_they_were() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(plugin)
+            .register_type::<Transform>()
+            .register_type::<Velocity>()
+            .insert_resource(DropletAssets {
+                mesh: Handle::default(),
+                material: Handle::default(),
+                surface_materials: vec![Handle::default()],
+            })
+            .insert_resource(crate::tests::stub_splash_assets())
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<pool::ParticlePool>()
+            .init_resource::<ParticleLifetimeSettings>()
+            .init_resource::<CurrentLiquid>()
+            .init_resource::<tuning::Viscosity>()
+            .insert_resource(tuning::Bounciness(0.8))
+            .insert_resource(freeze::FrozenParticles(true))
+            .add_systems(Update, rebuild_restored);
+        let position = Vec3::new(1.0, 2.0, 3.0);
+        let velocity = Vec3::new(0.5, -4.0, 0.0);
+        app.world_mut().spawn((
+            Droplet,
+            PrimaryDroplet,
+            DropletRadius(0.7),
+            Transform::from_translation(position),
+            Velocity::linear(velocity),
+            Squash::new(5.0),
+        ));
+
+        let scene = snapshot(app.world_mut());
+        let text = scene.serialize(&app.world().resource::<AppTypeRegistry>().read()).unwrap();
+        app.world_mut().resource_mut::<tuning::Bounciness>().0 = 0.1;
+        app.world_mut().resource_mut::<freeze::FrozenParticles>().0 = false;
+        let mut droplets = app.world_mut().query_filtered::<&mut Transform, With<Droplet>>();
+        droplets.single_mut(app.world_mut()).translation = Vec3::ZERO;
+
+        let scene = read_snapshot(app.world(), &text).unwrap();
+        restore(app.world_mut(), &scene).unwrap();
+        app.update();
+
+        let mut droplets = app.world_mut().query_filtered::<
+            (&Transform, &Velocity, &DropletRadius, Has<Collider>, Has<PrimaryDroplet>, Has<Squash>),
+            With<Droplet>,
+        >();
+        let (transform, restored_velocity, radius, has_collider, primary, squashed) = droplets.single(app.world());
+        assert_eq!((transform.translation, restored_velocity.linvel, radius.0), (position, velocity, 0.7));
+        assert!(has_collider && primary && squashed);
+        assert_eq!(app.world().resource::<tuning::Bounciness>().0, 0.8);
+        assert!(app.world().resource::<freeze::FrozenParticles>().0);
+    }
+}
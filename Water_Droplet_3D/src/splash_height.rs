@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::{ResetDroplets, SplashParticle};
+
+// The highest any splash particle has flown since the droplet was last reset, for comparing splashes across
+// gravity, bounciness and viscosity settings. `None` while there's no spray to measure.
+#[derive(Resource, Default, PartialEq, Debug)]
+pub struct MaxSplashHeight(pub Option<f32>);
+
+#[derive(Component)]
+pub struct SplashHeightText;
+
+pub fn setup_splash_height(mut commands: Commands) {
+    let style = TextStyle { font_size: 18.0, color: Color::WHITE, ..default() };
+    commands.spawn((
+        TextBundle::from_section("", style).with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        }),
+        SplashHeightText,
+    ));
+}
+
+// Raises the mark as the spray rises. A reset starts it over, and it's cleared once the last particle is gone.
+pub fn track_splash_height(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut resets: EventReader<ResetDroplets>,
+    particles: Query<&Transform, (With<SplashParticle>, Without<RigidBodyDisabled>)>,
+    mut max_height: ResMut<MaxSplashHeight>,
+) {
+    let reset = resets.read().count() > 0 || bindings.just_pressed(Action::Reset, &keys);
+    let highest = particles.iter().map(|transform| transform.translation.y).reduce(f32::max);
+    let mark = match (highest, max_height.0) {
+        (Some(y), Some(mark)) if !reset => Some(y.max(mark)),
+        (highest, _) => highest,
+    };
+    max_height.set_if_neq(MaxSplashHeight(mark));
+}
+
+pub fn update_splash_height(
+    max_height: Res<MaxSplashHeight>,
+    mut readout: Query<&mut Text, With<SplashHeightText>>,
+) {
+    if !max_height.is_changed() {
+        return;
+    }
+    for mut text in readout.iter_mut() {
+        text.sections[0].value = match max_height.0 {
+            Some(height) => format!("Max splash height: {height:.1} m"),
+            None => String::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_splash_height_follows_the_rising_spray_and_starts_over_on_reset() {
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<ResetDroplets>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<MaxSplashHeight>()
+            .add_systems(Update, (track_splash_height, update_splash_height).chain());
+        let readout = app.world_mut().spawn((TextBundle::from_section("", default()), SplashHeightText)).id();
+        let text = |app: &App| app.world().get::<Text>(readout).unwrap().sections[0].value.clone();
+        let particle = SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 };
+        let particle = app.world_mut().spawn((TransformBundle::default(), particle)).id();
+        let fly_to = |app: &mut App, y: f32| {
+            app.world_mut().get_mut::<Transform>(particle).unwrap().translation.y = y;
+            app.update();
+        };
+
+        fly_to(&mut app, 1.0);
+        assert_eq!(text(&app), "Max splash height: 1.0 m");
+        fly_to(&mut app, 2.46);
+        fly_to(&mut app, 0.3);
+        assert_eq!(app.world().resource::<MaxSplashHeight>().0, Some(2.46));
+        assert_eq!(text(&app), "Max splash height: 2.5 m");
+
+        app.world_mut().send_event(ResetDroplets);
+        app.update();
+        assert_eq!(app.world().resource::<MaxSplashHeight>().0, Some(0.3));
+
+        app.world_mut().entity_mut(particle).insert(RigidBodyDisabled);
+        app.update();
+        assert_eq!(app.world().resource::<MaxSplashHeight>().0, None);
+        assert_eq!(text(&app), "");
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+use crate::droplet_color::DropletColor;
+use crate::tuning::Viscosity;
+use crate::{spawn_droplet, DropletAssets, DropletRadius, PrimaryDroplet, SimulationRng, SplashEvent};
+
+// Impact speed (m/s) above which a splashing droplet breaks apart instead of just flattening.
+// It's measured the same way as `SplashThreshold`, from the velocity going into the impact.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct SplitThreshold(pub f32);
+
+impl Default for SplitThreshold {
+    fn default() -> Self {
+        // Above a default drop (~9.5 m/s) so a plain drop on Earth still just splashes
+        Self(12.0)
+    }
+}
+
+// Droplets smaller than this don't split any further, which also keeps rain and fragments whole
+const MIN_SPLIT_RADIUS: f32 = 0.2;
+const FRAGMENTS: std::ops::RangeInclusive<usize> = 3..=6;
+// Fragments fly outwards with this fraction of the impact speed
+const FRAGMENT_SPEED_FACTOR: f32 = 0.3;
+
+// Breaks droplets that hit hard enough into 3-6 smaller droplets with the same total volume.
+// Fragments are ordinary droplets, so they splash (more gently) when they land.
+// The primary droplet is only hidden, so R can bring it back; fragments go away with the other extra droplets.
+#[allow(clippy::too_many_arguments)]
+pub fn split_on_impact(
+    mut commands: Commands,
+    mut splash_events: EventReader<SplashEvent>,
+    mut droplets: Query<(&DropletRadius, &mut Velocity, Has<PrimaryDroplet>, Option<&DropletColor>)>,
+    threshold: Res<SplitThreshold>,
+    droplet_assets: Res<DropletAssets>,
+    viscosity: Res<Viscosity>,
+    mut rng: ResMut<SimulationRng>,
+) {
+    for splash in splash_events.read() {
+        // Water gives way, so only hard surfaces break droplets apart, and a droplet that bounces holds together
+        if splash.impact_speed <= threshold.0 || splash.into_water || splash.bounced {
+            continue;
+        }
+        let Ok((radius, mut velocity, is_primary, color)) = droplets.get_mut(splash.droplet) else { continue };
+        if radius.0 < MIN_SPLIT_RADIUS {
+            continue;
+        }
+
+        let rng = &mut rng.rng;
+        let weights: Vec<f32> = (0..rng.gen_range(FRAGMENTS)).map(|_| rng.gen_range(0.5..1.5)).collect();
+        let total: f32 = weights.iter().sum();
+        let speed = splash.impact_speed * FRAGMENT_SPEED_FACTOR;
+        let start_angle = rng.gen_range(0.0..TAU);
+
+        for (i, weight) in weights.iter().enumerate() {
+            // Volume goes with r³, so each fragment's share of the volume sets its radius
+            let fragment_radius = radius.0 * (weight / total).cbrt();
+            let angle = start_angle + TAU * i as f32 / weights.len() as f32;
+            let outward = Vec3::new(angle.cos(), 0.0, angle.sin());
+
+            let position = splash.position + outward * radius.0 + Vec3::Y * radius.0;
+            let fragment = spawn_droplet(&mut commands, position, fragment_radius, &droplet_assets, &viscosity);
+            commands
+                .entity(fragment)
+                .insert(Velocity::linear((outward + Vec3::Y * 0.6) * speed));
+            // Fragments keep the droplet's dye
+            if let Some(color) = color {
+                commands.entity(fragment).insert(*color);
+            }
+        }
+
+        if is_primary {
+            *velocity = Velocity::zero();
+            commands.entity(splash.droplet).insert((RigidBodyDisabled, Visibility::Hidden));
+        } else {
+            commands.entity(splash.droplet).despawn();
+        }
+    }
+}
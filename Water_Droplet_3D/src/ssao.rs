@@ -0,0 +1,74 @@
+use bevy::pbr::{
+    ScreenSpaceAmbientOcclusionBundle, ScreenSpaceAmbientOcclusionQualityLevel, ScreenSpaceAmbientOcclusionSettings,
+};
+use bevy::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+
+// Shift+I steps through these, cheapest first
+const QUALITY_LEVELS: [ScreenSpaceAmbientOcclusionQualityLevel; 4] = [
+    ScreenSpaceAmbientOcclusionQualityLevel::Low,
+    ScreenSpaceAmbientOcclusionQualityLevel::Medium,
+    ScreenSpaceAmbientOcclusionQualityLevel::High,
+    ScreenSpaceAmbientOcclusionQualityLevel::Ultra,
+];
+
+// Soft contact shadows where the droplet and the resting particles meet the floor.
+// SSAO only darkens the ambient and environment light, so it adds to the sun's shadow map
+// rather than darkening the same spots twice.
+#[derive(Resource)]
+pub struct SsaoConfig {
+    pub enabled: bool,
+    pub quality: ScreenSpaceAmbientOcclusionQualityLevel,
+}
+
+impl Default for SsaoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quality: ScreenSpaceAmbientOcclusionQualityLevel::High,
+        }
+    }
+}
+
+// I switches SSAO off and on; Shift+I cycles its quality
+pub fn control_ssao(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut config: ResMut<SsaoConfig>,
+) {
+    if bindings.just_pressed(Action::SsaoQuality, &keys) {
+        let current = QUALITY_LEVELS.iter().position(|level| *level == config.quality).unwrap_or(0);
+        config.quality = QUALITY_LEVELS[(current + 1) % QUALITY_LEVELS.len()];
+        info!("SSAO quality: {:?}", config.quality);
+    } else if bindings.just_pressed(Action::Ssao, &keys) {
+        config.enabled = !config.enabled;
+        info!("SSAO {}", if config.enabled { "on" } else { "off" });
+    }
+}
+
+// Keeps the camera's SSAO in line with the config (including at startup)
+pub fn apply_ssao(
+    mut commands: Commands,
+    config: Res<SsaoConfig>,
+    mut msaa: ResMut<Msaa>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    // Bevy's SSAO doesn't run with multisampling, so the edges go unsmoothed while it is on
+    *msaa = if config.enabled { Msaa::Off } else { Msaa::default() };
+
+    for camera in cameras.iter() {
+        if config.enabled {
+            commands.entity(camera).insert(ScreenSpaceAmbientOcclusionBundle {
+                settings: ScreenSpaceAmbientOcclusionSettings { quality_level: config.quality },
+                ..default()
+            });
+        } else {
+            commands.entity(camera).remove::<ScreenSpaceAmbientOcclusionBundle>();
+        }
+    }
+}
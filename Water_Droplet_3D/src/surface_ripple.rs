@@ -0,0 +1,163 @@
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy_rapier3d::prelude::*;
+
+use crate::simulation::simulation_running;
+use crate::tuning::DropletTuning;
+use crate::droplet_color::DropletColor;
+use crate::DropletAssets;
+
+// How far (as a fraction of the radius) the waves push the surface in and out at full strength
+const MAX_RIPPLE_AMPLITUDE: f32 = 0.03;
+// Once a droplet touches something its ripples die down at this rate, and build back up while it falls
+const RIPPLE_DECAY_RATE: f32 = 2.0;
+// A droplet sitting still never goes glassy-flat; it keeps this much of an idle ripple,
+// standing in for the old scaling wobble
+const RESTING_RIPPLE_STRENGTH: f32 = 0.3;
+const RIPPLE_RECOVERY_RATE: f32 = 1.0;
+// Like the floor ripples, droplets step through a fixed set of materials rather than owning one each,
+// which keeps them batched and editable from one place
+const RIPPLE_AMPLITUDE_STEPS: usize = 8;
+
+// Registers the ripple material and the systems that drive it
+pub fn plugin(app: &mut App) {
+    app.add_plugins(MaterialPlugin::<DropletMaterial>::default()).add_systems(
+        Update,
+        (settle_surface_ripples, update_ripple_time, sync_surface_materials, scale_ripples).run_if(simulation_running),
+    );
+}
+
+pub type DropletMaterial = ExtendedMaterial<StandardMaterial, RippleExtension>;
+
+// Vertex shader that moves the droplet's surface with travelling waves, on top of the usual
+// StandardMaterial shading
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct RippleExtension {
+    // Both go into the one `DropletRipple` uniform struct in the shader
+    #[uniform(100)]
+    time: f32,
+    #[uniform(100)]
+    amplitude: f32,
+}
+
+impl MaterialExtension for RippleExtension {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/droplet_ripple.wgsl".into()
+    }
+}
+
+// How strongly a droplet is rippling, from 0.0 (flat) to 1.0
+#[derive(Component)]
+pub struct SurfaceRipple {
+    strength: f32,
+}
+
+impl Default for SurfaceRipple {
+    fn default() -> Self {
+        Self { strength: 1.0 }
+    }
+}
+
+// One material per amplitude step, from full ripples down to a still surface
+pub fn surface_materials(
+    base: &StandardMaterial,
+    materials: &mut Assets<DropletMaterial>,
+) -> Vec<Handle<DropletMaterial>> {
+    (0..RIPPLE_AMPLITUDE_STEPS)
+        .map(|step| {
+            materials.add(DropletMaterial {
+                base: base.clone(),
+                extension: RippleExtension { time: 0.0, amplitude: step_amplitude(step, 1.0) },
+            })
+        })
+        .collect()
+}
+
+// Wave height for one of the steps, with the tuned wobble applied
+fn step_amplitude(step: usize, wobble: f32) -> f32 {
+    MAX_RIPPLE_AMPLITUDE * wobble * (1.0 - step as f32 / (RIPPLE_AMPLITUDE_STEPS - 1) as f32)
+}
+
+// Rescales the waves on every step when the wobble is tuned
+pub fn scale_ripples(
+    tuning: Res<DropletTuning>,
+    droplet_assets: Res<DropletAssets>,
+    mut materials: ResMut<Assets<DropletMaterial>>,
+) {
+    if !tuning.is_changed() {
+        return;
+    }
+    for (step, handle) in droplet_assets.surface_materials.iter().enumerate() {
+        let Some(material) = materials.get(handle) else { continue };
+        let amplitude = step_amplitude(step, tuning.wobble);
+        if material.extension.amplitude != amplitude {
+            materials.get_mut(handle).unwrap().extension.amplitude = amplitude;
+        }
+    }
+}
+
+// Calms a droplet's surface down to an idle ripple while it rests on something, and lets it ripple fully
+// again once it is airborne. Dyed droplets have a material of their own, whose waves are set to the same step.
+pub fn settle_surface_ripples(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    droplet_assets: Res<DropletAssets>,
+    tuning: Res<DropletTuning>,
+    mut materials: ResMut<Assets<DropletMaterial>>,
+    mut droplets: Query<(Entity, &mut SurfaceRipple, &mut Handle<DropletMaterial>, Has<DropletColor>)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut ripple, mut material, dyed) in droplets.iter_mut() {
+        let touching = rapier_context.contact_pairs_with(entity).any(|pair| pair.has_any_active_contact());
+        ripple.strength = if touching {
+            RESTING_RIPPLE_STRENGTH + (ripple.strength - RESTING_RIPPLE_STRENGTH) * (-RIPPLE_DECAY_RATE * dt).exp()
+        } else {
+            (ripple.strength + RIPPLE_RECOVERY_RATE * dt).min(1.0)
+        };
+
+        let step = ((1.0 - ripple.strength) * (RIPPLE_AMPLITUDE_STEPS - 1) as f32).round() as usize;
+        // Until `apply_droplet_colors` gives a newly dyed droplet its copy, it steps through the shared ones
+        if dyed && !droplet_assets.surface_materials.contains(&material) {
+            let amplitude = step_amplitude(step, tuning.wobble);
+            // Only touched when the step changes, as every edit re-uploads the material
+            if materials.get(material.id()).is_some_and(|own| own.extension.amplitude != amplitude) {
+                materials.get_mut(material.id()).unwrap().extension.amplitude = amplitude;
+            }
+            continue;
+        }
+        let stepped = &droplet_assets.surface_materials[step];
+        if *material != *stepped {
+            *material = stepped.clone();
+        }
+    }
+}
+
+// Moves the waves along, on dyed droplets' own materials too; uses the simulation clock, so they freeze while paused
+pub fn update_ripple_time(time: Res<Time>, mut materials: ResMut<Assets<DropletMaterial>>) {
+    for (_, material) in materials.iter_mut() {
+        material.extension.time = time.elapsed_seconds();
+    }
+}
+
+// The panel and L edit the plain droplet material; carry those edits over to every ripple step
+pub fn sync_surface_materials(
+    mut events: EventReader<AssetEvent<StandardMaterial>>,
+    droplet_assets: Res<DropletAssets>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut materials: ResMut<Assets<DropletMaterial>>,
+) {
+    let mut edited = false;
+    for event in events.read() {
+        edited |= event.is_modified(&droplet_assets.material);
+    }
+    if !edited {
+        return;
+    }
+    let Some(base) = standard_materials.get(&droplet_assets.material) else { return };
+    for handle in &droplet_assets.surface_materials {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base = base.clone();
+        }
+    }
+}
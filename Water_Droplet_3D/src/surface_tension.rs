@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::PI;
+
+use crate::freeze::Frozen;
+use crate::pool::ParticlePool;
+use crate::puddle::{self, Puddle};
+use crate::terrain::TerrainSettings;
+use crate::{Lifetime, SplashAssets, SplashParticle};
+
+// Resting particles are only checked every so often; merging doesn't need to be instant
+const MERGE_INTERVAL: f32 = 0.25;
+// Gap (m) between two resting particles' surfaces that they'll still pull together across
+const MERGE_GAP: f32 = 0.05;
+// Radius of the shared particle mesh at size 1.0
+pub const PARTICLE_RADIUS: f32 = 0.1;
+// A blob that grows past this size flattens out into a puddle
+const PUDDLE_SIZE: f32 = 2.0;
+
+// Slower than this (m/s) and touching the floor, a particle counts as resting even before Rapier puts it to sleep
+const REST_SPEED: f32 = 0.05;
+// How far (m) above the floor a resting particle's underside can be, for the bumps and the solver's slop
+const FLOOR_TOLERANCE: f32 = 0.02;
+
+// Splash particles that have come to rest next to each other bead up, like surface tension: every particle in a
+// touching group becomes one bead at the group's centre of volume, with their combined volume.
+// Only particles that have stopped, either asleep or all but still on the floor, are merged, so nothing still
+// flying (or hanging at the top of its arc) gets caught.
+// Once a bead is big enough it drains into the nearest puddle.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn merge_resting_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut since_last: Local<f32>,
+    mut particles: Query<
+        (Entity, &mut SplashParticle, &mut Transform, &mut Velocity, &mut Lifetime, &Sleeping),
+        (Without<RigidBodyDisabled>, Without<Puddle>, Without<Frozen>),
+    >,
+    mut puddles: Query<(&mut Puddle, &mut Transform), Without<SplashParticle>>,
+    mut particle_pool: ResMut<ParticlePool>,
+    splash_assets: Res<SplashAssets>,
+    terrain: Res<TerrainSettings>,
+) {
+    *since_last += time.delta_seconds();
+    if *since_last < MERGE_INTERVAL {
+        return;
+    }
+    *since_last = 0.0;
+
+    let floor_height = terrain.sampler();
+    let on_floor =
+        |position: Vec3, size: f32| position.y - size * PARTICLE_RADIUS < floor_height(position.xz()) + FLOOR_TOLERANCE;
+    let resting: Vec<(Entity, Vec3, f32)> = particles
+        .iter()
+        .filter(|(_, particle, transform, velocity, _, sleeping)| {
+            sleeping.sleeping
+                || (velocity.linvel.length() < REST_SPEED && on_floor(transform.translation, particle.size))
+        })
+        .map(|(entity, particle, transform, ..)| (entity, transform.translation, particle.size))
+        .collect();
+
+    let mut taken = vec![false; resting.len()];
+    for first in 0..resting.len() {
+        if taken[first] {
+            continue;
+        }
+        // Everything touching this particle, or touching one that does
+        taken[first] = true;
+        let mut bead = vec![first];
+        let mut next = 0;
+        while let Some(&i) = bead.get(next) {
+            next += 1;
+            let (_, position, size) = resting[i];
+            for (j, &(_, other, other_size)) in resting.iter().enumerate() {
+                if !taken[j] && position.distance(other) < (size + other_size) * PARTICLE_RADIUS + MERGE_GAP {
+                    taken[j] = true;
+                    bead.push(j);
+                }
+            }
+        }
+        if bead.len() < 2 {
+            continue;
+        }
+
+        // Volume goes with size³
+        let volume: f32 = bead.iter().map(|&i| resting[i].2.powi(3)).sum();
+        let center = bead.iter().map(|&i| resting[i].1 * resting[i].2.powi(3)).sum::<Vec3>() / volume;
+        let size = volume.cbrt();
+        // The bead lasts as long as the longest-lived of them
+        let lifetime = bead
+            .iter()
+            .filter_map(|&i| particles.get(resting[i].0).ok())
+            .map(|(.., lifetime, _)| lifetime.0.clone())
+            .max_by(|a, b| a.remaining_secs().total_cmp(&b.remaining_secs()));
+
+        let (kept, others) = (resting[bead[0]].0, &bead[1..]);
+        for &i in others {
+            particle_pool.release(&mut commands, resting[i].0);
+        }
+        if size > PUDDLE_SIZE {
+            particle_pool.release(&mut commands, kept);
+            let volume = 4.0 / 3.0 * PI * (size * PARTICLE_RADIUS).powi(3);
+            puddle::pour(&mut commands, &mut puddles, &splash_assets, &terrain, center, volume);
+            continue;
+        }
+
+        let Ok((_, mut particle, mut transform, mut velocity, mut particle_lifetime, _)) = particles.get_mut(kept)
+        else {
+            continue;
+        };
+        particle.size = size;
+        // Sit the bigger bead on the floor rather than sinking into it
+        let floor = floor_height(center.xz());
+        transform.translation = center.with_y(center.y.max(floor + size * PARTICLE_RADIUS));
+        transform.scale = Vec3::splat(size);
+        *velocity = Velocity::zero();
+        if let Some(lifetime) = lifetime {
+            particle_lifetime.0 = lifetime;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool, terrain};
+
+    #[test]
+    fn touching_resting_particles_bead_into_one_and_moving_ones_are_left_alone() {
+        use bevy::time::TimeUpdateStrategy;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(0.3)))
+            .insert_resource(crate::tests::stub_splash_assets())
+            .init_resource::<pool::ParticlePool>()
+            .init_resource::<terrain::TerrainSettings>()
+            .add_systems(Update, merge_resting_particles);
+        let mut particle = |x: f32, y: f32, speed: f32| {
+            app.world_mut()
+                .spawn((
+                    SplashParticle { splash_depth: 0, spawned_at: 0.0, size: 1.0 },
+                    Transform::from_xyz(x, y, 0.0),
+                    Velocity::linear(Vec3::X * speed),
+                    Lifetime(Timer::from_seconds(x + 1.0, TimerMode::Once)),
+                    Sleeping::default(),
+                ))
+                .id()
+        };
+        // A chain of three on the floor, each touching the next but not the one after
+        let chain = [particle(0.0, 0.1, 0.0), particle(0.2, 0.1, 0.0), particle(0.4, 0.1, 0.0)];
+        let alone = particle(3.0, 0.1, 0.0);
+        let rolling = particle(0.6, 0.1, 1.0);
+        // Stopped at the top of its arc, right over the chain
+        let in_the_air = particle(0.2, 0.3, 0.0);
+        app.update();
+        app.update();
+
+        let world = app.world();
+        let bead = world.get::<SplashParticle>(chain[0]).unwrap();
+        assert!((bead.size - 3.0_f32.cbrt()).abs() < 1e-5, "bead size {}", bead.size);
+        assert!((world.get::<Transform>(chain[0]).unwrap().translation.x - 0.2).abs() < 1e-5);
+        // It keeps the longest lifetime of the three
+        assert!(world.get::<Lifetime>(chain[0]).unwrap().0.remaining_secs() > 1.0);
+        for merged in &chain[1..] {
+            assert!(world.get::<RigidBodyDisabled>(*merged).is_some());
+        }
+        for untouched in [alone, rolling, in_the_air] {
+            assert!(world.get::<RigidBodyDisabled>(untouched).is_none());
+            assert_eq!(world.get::<SplashParticle>(untouched).unwrap().size, 1.0);
+        }
+    }
+}
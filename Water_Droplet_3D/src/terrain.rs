@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_rapier3d::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+use crate::floor::gradient_noise;
+
+// Noise lattice cells across the floor for each octave, with their weights: broad hills with smaller bumps on top
+const TERRAIN_OCTAVES: [(usize, f32); 2] = [(3, 0.75), (7, 0.25)];
+// Step used to estimate the slope for normals
+const NORMAL_SAMPLE_DISTANCE: f32 = 0.01;
+
+// Rolling hills in place of the flat floor, off by default. The mesh and its heightfield collider are both
+// sampled from the same noise, so droplets land exactly where the floor is drawn.
+#[derive(Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainSettings {
+    pub enabled: bool,
+    // Width and depth; always the scene's floor size, so it isn't read from the file
+    #[serde(skip)]
+    pub size: f32,
+    // Grid cells along each side
+    pub resolution: usize,
+    // Highest the hills rise above (and valleys sink below) the flat floor height
+    pub amplitude: f32,
+    pub seed: u64,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: 20.0,
+            resolution: 64,
+            amplitude: 0.4,
+            seed: 1,
+        }
+    }
+}
+
+impl TerrainSettings {
+    // Height of the floor at a point; flat at 0 when the terrain is off
+    pub fn height_at(&self, xz: Vec2) -> f32 {
+        self.sampler()(xz)
+    }
+
+    // Which way the floor faces at a point
+    pub fn normal_at(&self, xz: Vec2) -> Vec3 {
+        let height = self.sampler();
+        normal(&height, xz)
+    }
+
+    // Builds the noise lattices once, for sampling many points
+    pub fn sampler(&self) -> impl Fn(Vec2) -> f32 {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let octaves: Vec<(usize, f32, Vec<Vec2>)> = TERRAIN_OCTAVES
+            .iter()
+            .map(|&(cells, weight)| {
+                let gradients = (0..cells * cells).map(|_| Vec2::from_angle(rng.gen_range(0.0..TAU))).collect();
+                (cells, weight, gradients)
+            })
+            .collect();
+        let (enabled, size, amplitude) = (self.enabled, self.size, self.amplitude);
+
+        move |xz: Vec2| {
+            if !enabled {
+                return 0.0;
+            }
+            // 0..1 across the floor; past the edges the border height carries on
+            let uv = (xz / size + Vec2::splat(0.5)).clamp(Vec2::ZERO, Vec2::splat(0.999));
+            let noise: f32 = octaves
+                .iter()
+                .map(|(cells, weight, gradients)| weight * gradient_noise(uv * *cells as f32, *cells, gradients))
+                .sum();
+            noise * amplitude
+        }
+    }
+
+    fn grid_point(&self, row: usize, column: usize) -> Vec2 {
+        let step = self.size / self.resolution as f32;
+        Vec2::new(column as f32 * step, row as f32 * step) - Vec2::splat(self.size / 2.0)
+    }
+
+    // A grid over the whole floor with the checkerboard stretched across it, like the flat plane's UVs
+    pub fn mesh(&self) -> Mesh {
+        let height = self.sampler();
+        let side = self.resolution + 1;
+        let mut positions = Vec::with_capacity(side * side);
+        let mut normals = Vec::with_capacity(side * side);
+        let mut uvs = Vec::with_capacity(side * side);
+        for row in 0..side {
+            for column in 0..side {
+                let point = self.grid_point(row, column);
+                positions.push([point.x, height(point), point.y]);
+                normals.push(normal(&height, point).to_array());
+                uvs.push([column as f32 / self.resolution as f32, row as f32 / self.resolution as f32]);
+            }
+        }
+
+        let mut indices = Vec::with_capacity(self.resolution * self.resolution * 6);
+        for row in 0..self.resolution {
+            for column in 0..self.resolution {
+                let corner = (row * side + column) as u32;
+                let below = corner + side as u32;
+                // Wound so both triangles face up
+                indices.extend([corner, below, corner + 1, corner + 1, below, below + 1]);
+            }
+        }
+
+        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_indices(Indices::U32(indices))
+    }
+
+    // Rapier's heightfield spans the unit square scaled by `scale`, with rows running along Z and columns along X
+    pub fn collider(&self) -> Collider {
+        let height = self.sampler();
+        let side = self.resolution + 1;
+        // Column-major, so each column (one X) lists its heights from -Z to +Z
+        let heights = (0..side)
+            .flat_map(|column| (0..side).map(move |row| (row, column)))
+            .map(|(row, column)| height(self.grid_point(row, column)))
+            .collect();
+        Collider::heightfield(heights, side, side, Vec3::new(self.size, 1.0, self.size))
+    }
+}
+
+fn normal(height: &impl Fn(Vec2) -> f32, xz: Vec2) -> Vec3 {
+    let dx = Vec2::new(NORMAL_SAMPLE_DISTANCE, 0.0);
+    let dz = Vec2::new(0.0, NORMAL_SAMPLE_DISTANCE);
+    Vec3::new(
+        height(xz - dx) - height(xz + dx),
+        2.0 * NORMAL_SAMPLE_DISTANCE,
+        height(xz - dz) - height(xz + dz),
+    )
+    .normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_collider_matches_its_mesh() {
+        use bevy::render::mesh::VertexAttributeValues;
+
+        let terrain = TerrainSettings { enabled: true, resolution: 16, amplitude: 1.0, ..default() };
+        let mesh = terrain.mesh();
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("terrain mesh has no positions");
+        };
+        let collider = terrain.collider();
+
+        let mut relief = 0.0_f32;
+        // A vertex from each part of the grid, so a transposed heightfield wouldn't line up
+        for &[x, y, z] in positions.iter().step_by(23) {
+            let origin = Vec3::new(x, 10.0, z);
+            let toi = collider.cast_local_ray(origin, Vec3::NEG_Y, 20.0, true).expect("ray hits the terrain");
+            assert!((origin.y - toi - y).abs() < 1e-3, "collider and mesh disagree at ({x}, {z})");
+            assert!((terrain.height_at(Vec2::new(x, z)) - y).abs() < 1e-5);
+            relief = relief.max(y.abs());
+        }
+        assert!(relief > 0.1, "terrain should not be flat");
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+use crate::{Droplet, DropletRadius};
+
+// Droplets slower than this (m/s) don't leave a trail
+const TRAIL_MIN_SPEED: f32 = 3.0;
+const TRAIL_SECONDS: f32 = 0.4;
+// Segment size relative to the droplet that left it
+const TRAIL_SCALE: f32 = 0.35;
+const TRAIL_START_ALPHA: f32 = 0.35;
+const TRAIL_FADE_STEPS: usize = 8;
+
+// One fading blob of a droplet's trail
+#[derive(Component)]
+pub struct TrailSegment {
+    age: f32,
+    size: f32,
+}
+
+// Every segment shares the mesh and steps through the same fade materials
+#[derive(Resource)]
+pub struct TrailAssets {
+    mesh: Handle<Mesh>,
+    materials: Vec<Handle<StandardMaterial>>,
+}
+
+pub fn setup_trail(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let fade_materials = (0..TRAIL_FADE_STEPS)
+        .map(|step| {
+            let alpha = TRAIL_START_ALPHA * (1.0 - step as f32 / TRAIL_FADE_STEPS as f32);
+            materials.add(StandardMaterial {
+                base_color: Color::srgba(0.8, 0.9, 1.0, alpha),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })
+        })
+        .collect();
+
+    commands.insert_resource(TrailAssets {
+        mesh: meshes.add(Sphere::new(1.0).mesh().ico(1).unwrap()),
+        materials: fade_materials,
+    });
+}
+
+pub fn spawn_trail(
+    mut commands: Commands,
+    droplets: Query<(&Transform, &Velocity, &DropletRadius), With<Droplet>>,
+    assets: Res<TrailAssets>,
+) {
+    for (transform, velocity, radius) in droplets.iter() {
+        if velocity.linvel.length() < TRAIL_MIN_SPEED {
+            continue;
+        }
+
+        let size = radius.0 * TRAIL_SCALE;
+        commands.spawn((
+            PbrBundle {
+                mesh: assets.mesh.clone(),
+                material: assets.materials[0].clone(),
+                transform: Transform::from_translation(transform.translation).with_scale(Vec3::splat(size)),
+                ..default()
+            },
+            TrailSegment { age: 0.0, size },
+            NotShadowCaster,
+            NotShadowReceiver,
+        ));
+    }
+}
+
+// Shrinks and fades trail segments, then despawns them
+pub fn fade_trail(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<TrailAssets>,
+    mut query: Query<(Entity, &mut TrailSegment, &mut Transform, &mut Handle<StandardMaterial>)>,
+) {
+    for (entity, mut segment, mut transform, mut material) in query.iter_mut() {
+        segment.age += time.delta_seconds();
+        let progress = segment.age / TRAIL_SECONDS;
+
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.scale = Vec3::splat(segment.size * (1.0 - 0.5 * progress));
+
+        let fade_step = ((progress * TRAIL_FADE_STEPS as f32) as usize).min(TRAIL_FADE_STEPS - 1);
+        if *material != assets.materials[fade_step] {
+            *material = assets.materials[fade_step].clone();
+        }
+    }
+}
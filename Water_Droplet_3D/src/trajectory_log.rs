@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::cli::Cli;
+use crate::{Droplet, HasSplashed, SplashEvent};
+
+const HEADER: &str = "row,time,entity,x,y,z,vx,vy,vz,splashed,impact_speed,particles";
+
+// `--log-trajectory`: a CSV with a "step" row for every droplet on every physics step, and a "splash" row for every
+// splash with how hard it hit and how many particles it threw, for plotting fall curves and checking bounciness and
+// damping changes. Droplets are told apart by their entity index.
+#[derive(Resource)]
+pub struct TrajectoryLog {
+    writer: BufWriter<File>,
+    // Set after the first failed write, so a full disk is reported once rather than every frame
+    broken: bool,
+}
+
+impl TrajectoryLog {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{HEADER}")?;
+        Ok(Self { writer, broken: false })
+    }
+
+    fn write_row(&mut self, row: std::fmt::Arguments) {
+        if self.broken {
+            return;
+        }
+        if let Err(err) = self.writer.write_fmt(row) {
+            error!("Couldn't write the trajectory log ({err}); no more rows will be written");
+            self.broken = true;
+        }
+    }
+
+    pub fn step(&mut self, time: f32, droplet: Entity, position: Vec3, velocity: Vec3, splashed: bool) {
+        let (p, v) = (position, velocity);
+        self.write_row(format_args!(
+            "step,{time},{},{},{},{},{},{},{},{splashed},,\n",
+            droplet.index(),
+            p.x,
+            p.y,
+            p.z,
+            v.x,
+            v.y,
+            v.z,
+        ));
+    }
+
+    // Where the droplet hit and how fast it was going
+    pub fn splash(&mut self, time: f32, splash: &SplashEvent, particles: usize) {
+        let (p, v) = (splash.position, splash.impact_velocity);
+        self.write_row(format_args!(
+            "splash,{time},{},{},{},{},{},{},{},true,{},{particles}\n",
+            splash.droplet.index(),
+            p.x,
+            p.y,
+            p.z,
+            v.x,
+            v.y,
+            v.z,
+            splash.impact_speed,
+        ));
+    }
+
+    pub fn flush(&mut self) {
+        if !self.broken {
+            if let Err(err) = self.writer.flush() {
+                error!("Couldn't write the trajectory log ({err}); no more rows will be written");
+                self.broken = true;
+            }
+        }
+    }
+}
+
+pub fn open_trajectory_log(mut commands: Commands, cli: Option<Res<Cli>>) {
+    let Some(path) = cli.as_ref().and_then(|cli| cli.log_trajectory.as_ref()) else { return };
+    match TrajectoryLog::create(path) {
+        Ok(log) => {
+            info!("Logging droplet trajectories to {}", path.display());
+            commands.insert_resource(log);
+        }
+        Err(err) => error!("Couldn't create {} ({err}); not logging trajectories", path.display()),
+    }
+}
+
+// Where every droplet ended up after each physics step. Nothing is written while paused, as nothing moved.
+pub fn log_droplet_steps(
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    mut log: ResMut<TrajectoryLog>,
+    droplets: Query<(Entity, &Transform, &Velocity, Has<HasSplashed>), With<Droplet>>,
+) {
+    if !rapier_config.physics_pipeline_active {
+        return;
+    }
+    for (droplet, transform, velocity, splashed) in droplets.iter() {
+        log.step(time.elapsed_seconds(), droplet, transform.translation, velocity.linvel, splashed);
+    }
+}
+
+// Flushed every frame rather than on exit, so a Ctrl+C, which ends the process without an `AppExit`, loses nothing
+pub fn flush_trajectory_log(mut log: ResMut<TrajectoryLog>) {
+    log.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplashParticle;
+    use crate::{cli, headless};
+
+    #[test]
+    fn the_trajectory_log_has_a_row_per_step_and_one_per_splash() {
+        use clap::Parser;
+
+        let path = std::env::temp_dir().join(format!("droplet-trajectory-{}.csv", std::process::id()));
+        let args = ["droplet", "--headless", "--seed", "3", "--log-trajectory", path.to_str().unwrap()];
+        let mut app = headless::app(cli::Cli::try_parse_from(args).unwrap());
+        for _ in 0..90 {
+            app.update();
+        }
+        let splashed = app.world().resource::<headless::Splashes>().0;
+        // Those the splash threw, leaving out the secondary splashes they threw in turn when they landed
+        let world = app.world_mut();
+        let particles = world
+            .query_filtered::<&SplashParticle, Without<RigidBodyDisabled>>()
+            .iter(world)
+            .filter(|particle| particle.splash_depth == 0)
+            .count();
+        drop(app);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("row,time,entity,x,y,z,vx,vy,vz,splashed,impact_speed,particles"));
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert!(rows.iter().all(|row| row.len() == 12));
+        let steps: Vec<&Vec<&str>> = rows.iter().filter(|row| row[0] == "step").collect();
+        let splashes: Vec<&Vec<&str>> = rows.iter().filter(|row| row[0] == "splash").collect();
+        assert_eq!((steps.len(), splashes.len()), (90, splashed));
+        assert_eq!(splashed, 1);
+        assert_eq!(splashes[0][11].parse::<usize>().unwrap(), particles);
+        assert!(splashes[0][10].parse::<f32>().unwrap() > 3.0);
+
+        // Falling from rest until it lands
+        let heights: Vec<f32> = steps.iter().map(|row| row[4].parse().unwrap()).collect();
+        assert!(heights[0] > heights[30] && heights[30] > heights[50]);
+        assert_eq!((steps[0][9], steps[89][9]), ("false", "true"));
+    }
+}
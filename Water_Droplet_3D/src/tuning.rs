@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::liquid::LiquidType;
+use crate::ramp::RampSettings;
+use crate::scene_config::SceneConfig;
+use crate::{Droplet, PrimaryDroplet, ResetDroplets, SpawnPoint};
+
+// Droplet settings that can be changed while running, from the tuning panel.
+// Every droplet follows them, including the ones already falling.
+#[derive(Resource, Clone, PartialEq, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct DropletTuning {
+    // Height the primary droplet is dropped from, unless it's dropping onto the ramp
+    pub drop_height: f32,
+    // How quickly a spinning droplet stops turning. Viscosity sets how it slows moving; this is tuned on its own.
+    pub angular_damping: f32,
+    // Scales the droplet's surface ripples (or the scaling wobble with `cpu_wobble`); 0.0 holds it still
+    pub wobble: f32,
+}
+
+impl DropletTuning {
+    // What the scene file starts with
+    pub fn defaults(scene: &SceneConfig) -> Self {
+        Self { drop_height: scene.droplet_position.1, angular_damping: DEFAULT_ANGULAR_DAMPING, wobble: 1.0 }
+    }
+}
+
+pub const DEFAULT_ANGULAR_DAMPING: f32 = 0.5;
+
+impl Default for DropletTuning {
+    fn default() -> Self {
+        Self::defaults(&SceneConfig::default())
+    }
+}
+
+// Restitution of every droplet, from a dead splat (0.0) to a super ball (`MAX_BOUNCINESS`).
+// Each liquid starts it somewhere of its own; W and E take it down and up from there.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct Bounciness(pub f32);
+
+pub const MAX_BOUNCINESS: f32 = 0.95;
+const BOUNCINESS_STEP: f32 = 0.05;
+
+impl Default for Bounciness {
+    fn default() -> Self {
+        Self(LiquidType::default().restitution())
+    }
+}
+
+pub fn adjust_bounciness(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut bounciness: ResMut<Bounciness>,
+) {
+    let step = if bindings.just_pressed(Action::MoreBouncy, &keys) {
+        BOUNCINESS_STEP
+    } else if bindings.just_pressed(Action::LessBouncy, &keys) {
+        -BOUNCINESS_STEP
+    } else {
+        return;
+    };
+    bounciness.0 = (bounciness.0 + step).clamp(0.0, MAX_BOUNCINESS);
+    info!("Bounciness: {:.2}", bounciness.0);
+}
+
+/// How thick the droplets are, from 0.0 (thinner than water) to 1.0 (stiff syrup), which sets their damping and how
+/// far they splash. Like bounciness, each liquid starts it somewhere of its own and the ; and ' keys take it down and
+/// up from there. Resets leave it as it is.
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct Viscosity(pub f32);
+
+const VISCOSITY_STEP: f32 = 0.05;
+// Linear damping at no viscosity and at full viscosity. Each step in between multiplies the damping by the same
+// factor, so every step feels like the same change rather than the thin end doing nothing at all.
+const MIN_VISCOUS_DAMPING: f32 = 0.1;
+const MAX_VISCOUS_DAMPING: f32 = 8.0;
+// Share of a splash's speed full viscosity takes away
+const VISCOUS_SPLASH_LOSS: f32 = 0.6;
+
+impl Viscosity {
+    /// The droplets' linear damping at this viscosity
+    pub fn linear_damping(self) -> f32 {
+        MIN_VISCOUS_DAMPING * (MAX_VISCOUS_DAMPING / MIN_VISCOUS_DAMPING).powf(self.0)
+    }
+
+    /// How much faster (above 1.0) or slower the splash flies than the liquid's own does. The liquid's splash is
+    /// already scaled to its usual thickness, so this only covers the difference.
+    pub fn splash_scale(self, liquid: LiquidType) -> f32 {
+        (1.0 - VISCOUS_SPLASH_LOSS * self.0) / (1.0 - VISCOUS_SPLASH_LOSS * liquid.viscosity())
+    }
+}
+
+impl Default for Viscosity {
+    fn default() -> Self {
+        Self(LiquidType::default().viscosity())
+    }
+}
+
+pub fn adjust_viscosity(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut viscosity: ResMut<Viscosity>,
+) {
+    let step = if bindings.just_pressed(Action::Thicker, &keys) {
+        VISCOSITY_STEP
+    } else if bindings.just_pressed(Action::Thinner, &keys) {
+        -VISCOSITY_STEP
+    } else {
+        return;
+    };
+    viscosity.0 = (viscosity.0 + step).clamp(0.0, 1.0);
+    info!("Viscosity: {:.2} (damping {:.2})", viscosity.0, viscosity.linear_damping());
+}
+
+// Puts the bounce, viscosity and angular damping on every droplet when they change, and on each new droplet as it
+// appears
+#[allow(clippy::type_complexity)]
+pub fn apply_droplet_tuning(
+    tuning: Res<DropletTuning>,
+    bounciness: Res<Bounciness>,
+    viscosity: Res<Viscosity>,
+    mut droplets: Query<(Ref<Droplet>, &mut Restitution, &mut Damping)>,
+) {
+    for (droplet, mut restitution, mut damping) in droplets.iter_mut() {
+        if !tuning.is_changed() && !bounciness.is_changed() && !viscosity.is_changed() && !droplet.is_added() {
+            continue;
+        }
+        restitution.coefficient = bounciness.0;
+        damping.linear_damping = viscosity.linear_damping();
+        damping.angular_damping = tuning.angular_damping;
+    }
+}
+
+// Moves the primary droplet's drop point to the tuned height and drops it again from there
+pub fn apply_drop_height(
+    tuning: Res<DropletTuning>,
+    ramp: Res<RampSettings>,
+    mut primary: Query<&mut SpawnPoint, With<PrimaryDroplet>>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    if !tuning.is_changed() || ramp.drop_on_ramp {
+        return;
+    }
+    for mut spawn_point in primary.iter_mut() {
+        if spawn_point.0.y != tuning.drop_height {
+            spawn_point.0.y = tuning.drop_height;
+            resets.send(ResetDroplets);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquid;
+
+    #[test]
+    fn tuning_reaches_droplets_already_in_the_air() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<DropletTuning>()
+            .init_resource::<Bounciness>()
+            .init_resource::<Viscosity>()
+            .add_systems(Update, apply_droplet_tuning);
+        let falling = app
+            .world_mut()
+            .spawn((Droplet, Restitution::coefficient(0.0), Damping { linear_damping: 0.0, angular_damping: 0.0 }))
+            .id();
+        app.update();
+        let defaults = Bounciness::default();
+        assert_eq!(app.world().get::<Restitution>(falling).unwrap().coefficient, defaults.0);
+
+        app.world_mut().resource_mut::<Viscosity>().0 = 1.0;
+        app.update();
+        let damping = app.world().get::<Damping>(falling).unwrap().linear_damping;
+        assert_eq!(damping, Viscosity(1.0).linear_damping());
+
+        // Turning is damped on its own, whatever the viscosity
+        app.world_mut().resource_mut::<DropletTuning>().angular_damping = 2.0;
+        app.update();
+        let damping = app.world().get::<Damping>(falling).unwrap();
+        assert_eq!((damping.linear_damping, damping.angular_damping), (Viscosity(1.0).linear_damping(), 2.0));
+    }
+
+    #[test]
+    fn each_viscosity_step_thickens_the_droplet_by_the_same_factor() {
+        use Viscosity;
+
+        let damping: Vec<f32> = (0..=4).map(|step| Viscosity(step as f32 * 0.25).linear_damping()).collect();
+        for pair in damping.windows(2) {
+            assert!((pair[1] / pair[0] - damping[1] / damping[0]).abs() < 1e-3);
+        }
+        // Each liquid starts near the damping it always had
+        let water = Viscosity(liquid::LiquidType::Water.viscosity());
+        assert!((water.linear_damping() - 0.5).abs() < 0.1);
+
+        // Its own viscosity leaves the liquid's splash alone; thicker slows it and thinner speeds it up
+        let honey = liquid::LiquidType::Honey;
+        assert_eq!(Viscosity(honey.viscosity()).splash_scale(honey), 1.0);
+        assert!(Viscosity(1.0).splash_scale(liquid::LiquidType::Water) < 1.0);
+        assert!(Viscosity(0.0).splash_scale(honey) > 1.0);
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+use bevy_panorbit_camera::{EguiFocusIncludesHover, EguiWantsFocus};
+
+use crate::environment::EnvironmentSettings;
+use crate::fog::FogConfig;
+use crate::keybindings::{Action, KeyBindings};
+use crate::liquid::{CurrentLiquid, DROPLET_THICKNESS};
+use crate::scene_config::SceneConfig;
+use crate::screenshot::overlays_shown;
+use crate::tuning::{Bounciness, DropletTuning, Viscosity, MAX_BOUNCINESS};
+use crate::{DropletAssets, DropletRadius, DropletSize, PrimaryDroplet, ResetDroplets, SplashConfig};
+use crate::{MAX_DROPLET_RADIUS, MIN_DROPLET_RADIUS};
+
+// Registers egui and the tuning panel
+pub fn plugin(app: &mut App) {
+    app.add_plugins(EguiPlugin)
+        // The panel is docked, so hovering it (not just dragging in it) should leave the camera alone
+        .insert_resource(EguiFocusIncludesHover(true))
+        .init_resource::<TuningPanel>()
+        .add_systems(Update, (toggle_tuning_panel, tuning_panel.run_if(overlays_shown)).chain());
+}
+
+#[derive(Resource)]
+pub struct TuningPanel {
+    pub open: bool,
+}
+
+impl Default for TuningPanel {
+    fn default() -> Self {
+        Self { open: true }
+    }
+}
+
+// F1 shows and hides the panel
+pub fn toggle_tuning_panel(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut panel: ResMut<TuningPanel>,
+) {
+    if bindings.just_pressed(Action::TuningPanel, &keys) {
+        panel.open = !panel.open;
+    }
+}
+
+// Side panel for live-editing the droplet, its material and the splash, to see what each setting does without
+// recompiling. Edits go straight into the shared resources and materials, so every droplet updates at once.
+#[allow(clippy::too_many_arguments)]
+pub fn tuning_panel(
+    mut contexts: EguiContexts,
+    panel: Res<TuningPanel>,
+    droplet_assets: Res<DropletAssets>,
+    liquid: Res<CurrentLiquid>,
+    scene: Res<SceneConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut splash: ResMut<SplashConfig>,
+    mut tuning: ResMut<DropletTuning>,
+    mut bounciness: ResMut<Bounciness>,
+    mut viscosity: ResMut<Viscosity>,
+    mut droplet_size: ResMut<DropletSize>,
+    mut environment: ResMut<EnvironmentSettings>,
+    mut fog: ResMut<FogConfig>,
+    mut primary: Query<&mut DropletRadius, With<PrimaryDroplet>>,
+    mut resets: EventWriter<ResetDroplets>,
+) {
+    if !panel.open {
+        return;
+    }
+    let Some(material) = materials.get(&droplet_assets.material) else { return };
+    let mut edited = material.clone();
+    let mut changed = false;
+    // Edit copies, so change detection only fires on a real edit
+    let mut droplet = tuning.clone();
+    let mut bounce = bounciness.0;
+    let mut thickness = viscosity.0;
+    let mut radius = droplet_size.0;
+    let mut config = splash.clone();
+    let mut intensity = environment.intensity;
+    let mut density = fog.density;
+
+    egui::SidePanel::right("tuning_panel").show(contexts.ctx_mut(), |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::CollapsingHeader::new("Droplet").default_open(true).show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut radius, MIN_DROPLET_RADIUS..=MAX_DROPLET_RADIUS).text("Radius"));
+                ui.add(egui::Slider::new(&mut droplet.drop_height, 1.0..=15.0).text("Drop height"));
+                ui.add(egui::Slider::new(&mut droplet.wobble, 0.0..=3.0).text("Wobble"));
+            });
+
+            egui::CollapsingHeader::new("Physics").default_open(true).show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut bounce, 0.0..=MAX_BOUNCINESS).text("Bounciness"));
+                ui.add(egui::Slider::new(&mut thickness, 0.0..=1.0).text("Viscosity"));
+                ui.add(egui::Slider::new(&mut droplet.angular_damping, 0.0..=5.0).text("Angular damping"));
+            });
+
+            egui::CollapsingHeader::new("Splash").default_open(true).show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut config.count, 1..=100).text("Particles"));
+                ui.add(egui::Slider::new(&mut config.horizontal_spread, 0.0..=10.0).text("Horizontal spread"));
+                let upward = &mut config.upward_velocity_range;
+                ui.add(egui::Slider::new(&mut upward.start, 0.0..=20.0).text("Min upward speed"));
+                ui.add(egui::Slider::new(&mut upward.end, 0.0..=20.0).text("Max upward speed"));
+                upward.end = upward.end.max(upward.start);
+                ui.add(egui::Slider::new(&mut config.crown_angle, 0.0..=80.0).text("Crown angle (°)"));
+                ui.add(egui::Slider::new(&mut config.ring_radius, 0.0..=1.0).text("Ring radius"));
+                ui.add(egui::Slider::new(&mut config.inner_fraction, 0.0..=1.0).text("Inner fraction"));
+            });
+
+            egui::CollapsingHeader::new("Material").default_open(true).show(ui, |ui| {
+                let mut slider = |ui: &mut egui::Ui, value: &mut f32, range, label| {
+                    changed |= ui.add(egui::Slider::new(value, range).text(label)).changed();
+                };
+                slider(ui, &mut edited.perceptual_roughness, 0.0..=1.0, "Roughness");
+                slider(ui, &mut edited.metallic, 0.0..=1.0, "Metallic");
+                slider(ui, &mut edited.reflectance, 0.0..=1.0, "Reflectance");
+                slider(ui, &mut edited.ior, 1.0..=2.5, "IOR");
+                slider(ui, &mut edited.specular_transmission, 0.0..=1.0, "Transmission");
+                slider(ui, &mut edited.thickness, 0.0..=2.0, "Thickness");
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut edited.attenuation_distance, 0.1..=100.0)
+                            .logarithmic(true)
+                            .text("Attenuation distance"),
+                    )
+                    .changed();
+            });
+
+            egui::CollapsingHeader::new("Scene").default_open(true).show(ui, |ui| {
+                // Reflections off the droplet's rim come from the environment map
+                ui.add(egui::Slider::new(&mut intensity, 0.0..=5000.0).text("Environment light"));
+                ui.add(egui::Slider::new(&mut density, 0.0..=0.3).text("Fog density"));
+            });
+
+            ui.separator();
+            if ui.button("Reset to defaults").clicked() {
+                edited = liquid.0.material(DROPLET_THICKNESS);
+                changed = true;
+                droplet = DropletTuning::defaults(&scene);
+                bounce = scene.bounciness.unwrap_or(liquid.0.restitution());
+                thickness = liquid.0.viscosity();
+                radius = scene.droplet_radius;
+                config = scene.splash_config();
+            }
+        });
+    });
+
+    // Only touch the asset on an actual edit, so it isn't re-uploaded every frame
+    if changed {
+        if let Some(material) = materials.get_mut(&droplet_assets.material) {
+            *material = edited;
+        }
+    }
+    if droplet != *tuning {
+        *tuning = droplet;
+    }
+    if bounce != bounciness.0 {
+        bounciness.0 = bounce;
+    }
+    if thickness != viscosity.0 {
+        viscosity.0 = thickness;
+    }
+    // Like X and Z, a new size drops the primary droplet again so it can be seen from the start
+    if radius != droplet_size.0 {
+        droplet_size.0 = radius;
+        for mut primary_radius in primary.iter_mut() {
+            primary_radius.0 = radius;
+        }
+        resets.send(ResetDroplets);
+    }
+    if config != *splash {
+        *splash = config;
+    }
+    if intensity != environment.intensity {
+        environment.intensity = intensity;
+    }
+    if density != fog.density {
+        fog.density = density;
+    }
+}
+
+// Clicks over the panel shouldn't also drop droplets into the scene
+pub fn pointer_outside_panel(focus: Res<EguiWantsFocus>) -> bool {
+    !focus.prev && !focus.curr
+}
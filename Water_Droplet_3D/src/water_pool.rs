@@ -0,0 +1,195 @@
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::floor::FloorSize;
+use crate::surface_tension::PARTICLE_RADIUS;
+use crate::{Droplet, DropletRadius, HasSplashed, ImpactVelocity, SplashEvent, SplashParticle, SplashThreshold};
+
+// A shallow pool in the corner of the floor, off to the side of the default drop.
+// This is for the default floor; see `FloorSize::layout_scale`.
+const POOL_CENTER: Vec3 = Vec3::new(-4.0, 0.0, 3.0);
+const POOL_SIZE: Vec2 = Vec2::new(3.0, 3.0);
+const POOL_DEPTH: f32 = 1.0;
+// Relative to the droplets, so they float with about 1/1.6 of themselves under the surface
+const POOL_DENSITY: f32 = 1.6;
+// Extra drag (per second) on anything fully under water, so a plop slows quickly and the bobbing dies down
+const SUBMERGED_DAMPING: f32 = 6.0;
+
+// A body of water with no collider: things sink into it and are pushed back up by `apply_buoyancy`.
+// The footprint is centred on the entity's translation.
+#[derive(Component)]
+pub struct WaterVolume {
+    pub surface_y: f32,
+    // Density of the water relative to the droplets; above 1.0 they float
+    pub density: f32,
+    pub half_extents: Vec2,
+}
+
+impl WaterVolume {
+    fn covers(&self, volume_position: Vec3, point: Vec3) -> bool {
+        (point.xz() - volume_position.xz()).abs().cmple(self.half_extents).all()
+    }
+
+    // From 0.0 (clear of the water) to 1.0 (under it), for a ball of this radius
+    fn submerged(&self, center: Vec3, radius: f32) -> f32 {
+        ((self.surface_y - (center.y - radius)) / (2.0 * radius)).clamp(0.0, 1.0)
+    }
+}
+
+pub fn setup_water_pool(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    floor_size: Res<FloorSize>,
+) {
+    let pool = spawn_water_volume(&mut commands, &floor_size);
+    commands.entity(pool).insert((
+        meshes.add(Cuboid::new(POOL_SIZE.x, POOL_DEPTH, POOL_SIZE.y)),
+        materials.add(StandardMaterial {
+            base_color: Color::srgba(0.15, 0.4, 0.75, 0.35),
+            perceptual_roughness: 0.05,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        VisibilityBundle::default(),
+        NotShadowCaster,
+    ));
+}
+
+// The pool's water, with nothing to draw it; `--headless` uses it as it is
+pub fn spawn_water_volume(commands: &mut Commands, floor_size: &FloorSize) -> Entity {
+    commands
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(pool_translation(floor_size))),
+            WaterVolume {
+                surface_y: POOL_CENTER.y + POOL_DEPTH,
+                density: POOL_DENSITY,
+                half_extents: POOL_SIZE / 2.0,
+            },
+        ))
+        .id()
+}
+
+fn pool_translation(floor_size: &FloorSize) -> Vec3 {
+    POOL_CENTER * floor_size.layout_scale() + Vec3::Y * POOL_DEPTH / 2.0
+}
+
+// Keeps the pool on the floor when it is resized, the same distance across it as before
+pub fn fit_pool_to_floor(floor_size: Res<FloorSize>, mut volumes: Query<&mut Transform, With<WaterVolume>>) {
+    if !floor_size.is_changed() {
+        return;
+    }
+    for mut transform in volumes.iter_mut() {
+        transform.translation = pool_translation(&floor_size);
+    }
+}
+
+// Pushes droplets and particles up by the weight of the water they displace, and slows them while they're in it
+#[allow(clippy::type_complexity)]
+pub fn apply_buoyancy(
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    volumes: Query<(&WaterVolume, &Transform)>,
+    mut bodies: Query<
+        (&Transform, &mut Velocity, Option<&DropletRadius>, Option<&SplashParticle>),
+        (Or<(With<Droplet>, With<SplashParticle>)>, Without<RigidBodyDisabled>),
+    >,
+) {
+    let dt = time.delta_seconds();
+    for (transform, mut velocity, droplet_radius, particle) in bodies.iter_mut() {
+        let radius = match (droplet_radius, particle) {
+            (Some(radius), _) => radius.0,
+            (None, Some(particle)) => PARTICLE_RADIUS * particle.size,
+            (None, None) => continue,
+        };
+        let position = transform.translation;
+        let Some((volume, submerged)) = volumes
+            .iter()
+            .filter(|(volume, volume_transform)| volume.covers(volume_transform.translation, position))
+            .map(|(volume, _)| (volume, volume.submerged(position, radius)))
+            .find(|(_, submerged)| *submerged > 0.0)
+        else {
+            continue;
+        };
+
+        velocity.linvel -= rapier_config.gravity * volume.density * submerged * dt;
+        velocity.linvel *= (-SUBMERGED_DAMPING * submerged * dt).exp();
+    }
+}
+
+// Falling into the pool splashes at the surface, like hitting anything solid would
+#[allow(clippy::type_complexity)]
+pub fn splash_into_water(
+    mut commands: Commands,
+    mut splash_events: EventWriter<SplashEvent>,
+    threshold: Res<SplashThreshold>,
+    volumes: Query<(&WaterVolume, &Transform)>,
+    droplets: Query<
+        (Entity, &Transform, &DropletRadius, &ImpactVelocity),
+        (With<Droplet>, Without<HasSplashed>, Without<RigidBodyDisabled>),
+    >,
+) {
+    for (entity, transform, radius, impact_velocity) in droplets.iter() {
+        let position = transform.translation;
+        let impact_speed = impact_velocity.0.length();
+        if impact_velocity.0.y >= 0.0 || impact_speed <= threshold.0 {
+            continue;
+        }
+        let Some((volume, _)) = volumes.iter().find(|(volume, volume_transform)| {
+            volume.covers(volume_transform.translation, position) && volume.submerged(position, radius.0) > 0.0
+        }) else {
+            continue;
+        };
+
+        commands.entity(entity).insert(HasSplashed);
+        splash_events.send(SplashEvent {
+            position: position.with_y(volume.surface_y),
+            impact_speed,
+            impact_velocity: impact_velocity.0,
+            normal: Vec3::Y,
+            into_water: true,
+            bounced: false,
+            droplet: entity,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submerged_droplets_are_pushed_up_and_slowed() {
+        use bevy::time::TimeUpdateStrategy;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)))
+            .insert_resource(RapierConfiguration::new(1.0))
+            .add_systems(Update, apply_buoyancy);
+        app.world_mut().spawn((
+            WaterVolume { surface_y: 1.0, density: 1.6, half_extents: Vec2::splat(1.5) },
+            Transform::from_xyz(-4.0, 0.5, 3.0),
+        ));
+        let sinking = Velocity::linear(Vec3::new(1.0, -0.2, 0.0));
+        let under = app
+            .world_mut()
+            .spawn((Droplet, DropletRadius(0.5), Transform::from_xyz(-4.0, 0.3, 3.0), sinking))
+            .id();
+        let beside = app
+            .world_mut()
+            .spawn((Droplet, DropletRadius(0.5), Transform::from_xyz(0.0, 0.3, 0.0), sinking))
+            .id();
+
+        // The first update only starts the clock
+        app.update();
+        app.update();
+
+        let velocity = app.world().get::<Velocity>(under).unwrap().linvel;
+        assert!(velocity.y > 0.0, "buoyancy should beat the sinking speed, got {velocity}");
+        assert!(velocity.x < 1.0, "the water should drag on it");
+        assert_eq!(app.world().get::<Velocity>(beside).unwrap().linvel, sinking.linvel);
+    }
+}
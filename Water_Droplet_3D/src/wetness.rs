@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::SplashEvent;
+
+// Splashes higher than this didn't land on the floor
+const MAX_WET_HEIGHT: f32 = 1.5;
+// World-space radius of the wet patch a single splash leaves
+const WET_RADIUS: f32 = 1.0;
+const WETNESS_PER_SPLASH: f32 = 0.4;
+const MAX_WETNESS: f32 = 1.0;
+// Fraction of brightness lost on a fully soaked pixel
+const WET_DARKENING: f32 = 0.45;
+// Seconds for a fully soaked spot to dry out
+const DRYING_SECONDS: f32 = 60.0;
+// Drying is slow, so the texture only needs re-uploading now and then
+const DRYING_INTERVAL: f32 = 0.5;
+
+// Per-pixel wetness of the floor texture, kept alongside the dry pixels it darkens
+#[derive(Resource)]
+pub struct FloorWetness {
+    image: Handle<Image>,
+    dry: Vec<u8>,
+    wetness: Vec<f32>,
+    size: usize,
+    // The checkerboard covers the whole floor plane, so world x/z map straight onto its UVs
+    floor_size: f32,
+}
+
+impl FloorWetness {
+    // `image` must be square RGBA8 and still hold its pixel data on the CPU
+    pub fn new(image: Handle<Image>, dry: Vec<u8>, size: usize, floor_size: f32) -> Self {
+        Self {
+            image,
+            dry,
+            wetness: vec![0.0; size * size],
+            size,
+            floor_size,
+        }
+    }
+
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    // Swaps in a new dry texture, then paints the current wetness back over it
+    pub fn set_dry(&mut self, image: &mut Image) {
+        self.dry = image.data.clone();
+        self.write_to(image);
+    }
+
+    fn soak(&mut self, position: Vec3) {
+        let to_pixels = self.size as f32 / self.floor_size;
+        let center = (position.xz() + Vec2::splat(self.floor_size / 2.0)) * to_pixels;
+        let radius = WET_RADIUS * to_pixels;
+
+        // Clamp the affected square to the texture so splashes near the edge just get cut off
+        let min = (center - Vec2::splat(radius)).max(Vec2::ZERO);
+        let max = (center + Vec2::splat(radius)).min(Vec2::splat(self.size as f32 - 1.0));
+        if min.x > max.x || min.y > max.y {
+            return;
+        }
+
+        for y in min.y as usize..=max.y as usize {
+            for x in min.x as usize..=max.x as usize {
+                let distance = Vec2::new(x as f32, y as f32).distance(center) / radius;
+                if distance >= 1.0 {
+                    continue;
+                }
+                // Soft edge that fades smoothly to dry
+                let falloff = 1.0 - distance * distance * (3.0 - 2.0 * distance);
+                let wetness = &mut self.wetness[y * self.size + x];
+                *wetness = (*wetness + WETNESS_PER_SPLASH * falloff).min(MAX_WETNESS);
+            }
+        }
+    }
+
+    fn dry_out(&mut self, seconds: f32) -> bool {
+        let amount = MAX_WETNESS * seconds / DRYING_SECONDS;
+        let mut changed = false;
+        for wetness in self.wetness.iter_mut().filter(|wetness| **wetness > 0.0) {
+            *wetness = (*wetness - amount).max(0.0);
+            changed = true;
+        }
+        changed
+    }
+
+    fn write_to(&self, image: &mut Image) {
+        for (i, wetness) in self.wetness.iter().enumerate() {
+            let brightness = 1.0 - wetness * WET_DARKENING;
+            // RGB only, alpha stays as it was
+            for channel in i * 4..i * 4 + 3 {
+                image.data[channel] = (self.dry[channel] as f32 * brightness) as u8;
+            }
+        }
+    }
+}
+
+pub fn wet_floor(
+    mut splash_events: EventReader<SplashEvent>,
+    mut wetness: ResMut<FloorWetness>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mut soaked = false;
+    for splash in splash_events.read() {
+        if splash.position.y < MAX_WET_HEIGHT && !splash.into_water {
+            wetness.soak(splash.position);
+            soaked = true;
+        }
+    }
+
+    if soaked {
+        if let Some(image) = images.get_mut(&wetness.image) {
+            wetness.write_to(image);
+        }
+    }
+}
+
+pub fn dry_floor(
+    time: Res<Time>,
+    mut wetness: ResMut<FloorWetness>,
+    mut images: ResMut<Assets<Image>>,
+    mut since_last: Local<f32>,
+) {
+    *since_last += time.delta_seconds();
+    if *since_last < DRYING_INTERVAL {
+        return;
+    }
+
+    let elapsed = std::mem::take(&mut *since_last);
+    if wetness.dry_out(elapsed) {
+        if let Some(image) = images.get_mut(&wetness.image) {
+            wetness.write_to(image);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::keybindings::{Action, KeyBindings};
+use crate::surface_tension::PARTICLE_RADIUS;
+use crate::{Droplet, DropletRadius, SplashParticle};
+
+const WIND_SPEED_STEP: f32 = 1.0;
+const MAX_WIND_SPEED: f32 = 15.0;
+const WIND_TURN_STEP: f32 = 15.0;
+// Force per (m/s of wind × m² of cross-section). Drag goes with area but mass with volume,
+// so the small splash particles are blown about far more than the droplet.
+const WIND_DRAG: f32 = 0.5;
+
+// Horizontal wind. The heading is kept apart from the speed so it survives the wind dropping to nothing.
+#[derive(Resource, Default)]
+pub struct Wind {
+    // m/s; zero is still air
+    pub speed: f32,
+    // Compass heading in degrees, 0 blowing towards +X and 90 towards +Z
+    pub heading: f32,
+}
+
+impl Wind {
+    pub fn velocity(&self) -> Vec3 {
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        Vec3::new(cos, 0.0, sin) * self.speed
+    }
+}
+
+// Up/Down strengthen and weaken the wind; Left/Right turn it
+pub fn control_wind(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut wind: ResMut<Wind>,
+) {
+    let mut speed = wind.speed;
+    let mut heading = wind.heading;
+    if bindings.just_pressed(Action::WindStronger, &keys) {
+        speed += WIND_SPEED_STEP;
+    }
+    if bindings.just_pressed(Action::WindWeaker, &keys) {
+        speed -= WIND_SPEED_STEP;
+    }
+    if bindings.just_pressed(Action::WindLeft, &keys) {
+        heading -= WIND_TURN_STEP;
+    }
+    if bindings.just_pressed(Action::WindRight, &keys) {
+        heading += WIND_TURN_STEP;
+    }
+
+    let speed = speed.clamp(0.0, MAX_WIND_SPEED);
+    let heading = heading.rem_euclid(360.0);
+    if speed != wind.speed || heading != wind.heading {
+        *wind = Wind { speed, heading };
+        info!("Wind: {speed:.0} m/s towards {heading:.0}°");
+    }
+}
+
+// Keeps a steady push on every droplet and particle in play. Only a body whose push changed is woken, so a gust
+// rolls resting ones but a steady wind lets them settle.
+#[allow(clippy::type_complexity)]
+pub fn apply_wind(
+    wind: Res<Wind>,
+    mut bodies: Query<
+        (&mut ExternalForce, &mut Sleeping, &Transform, Option<&DropletRadius>),
+        (Or<(With<Droplet>, With<SplashParticle>)>, Without<RigidBodyDisabled>),
+    >,
+) {
+    let velocity = wind.velocity();
+    for (mut external_force, mut sleeping, transform, droplet_radius) in bodies.iter_mut() {
+        let radius = droplet_radius.map_or(PARTICLE_RADIUS * transform.scale.x, |radius| radius.0);
+        let force = velocity * WIND_DRAG * radius * radius;
+        if external_force.force != force {
+            external_force.force = force;
+            sleeping.sleeping = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_wind_keeps_its_heading_through_a_lull_and_only_wakes_bodies_it_pushes_differently() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        world.init_resource::<KeyBindings>();
+        world.insert_resource(Wind { speed: 1.0, heading: 90.0 });
+        let press = |world: &mut World, key: KeyCode| {
+            let mut keys = ButtonInput::<KeyCode>::default();
+            keys.press(key);
+            world.insert_resource(keys);
+            world.run_system_once(control_wind);
+        };
+        press(&mut world, KeyCode::ArrowDown);
+        assert_eq!(world.resource::<Wind>().speed, 0.0);
+        press(&mut world, KeyCode::ArrowUp);
+        assert!(world.resource::<Wind>().velocity().abs_diff_eq(Vec3::Z, 1e-6));
+
+        let droplet = world
+            .spawn((Droplet, DropletRadius(1.0), Transform::default(), ExternalForce::default(), Sleeping::default()))
+            .id();
+        let asleep = |world: &mut World| {
+            world.get_mut::<Sleeping>(droplet).unwrap().sleeping = true;
+            world.run_system_once(apply_wind);
+            world.get::<Sleeping>(droplet).unwrap().sleeping
+        };
+        assert!(!asleep(&mut world), "the first push wakes it");
+        assert!(asleep(&mut world), "a steady wind lets it rest");
+        world.resource_mut::<Wind>().heading = 180.0;
+        assert!(!asleep(&mut world), "a change of wind wakes it again");
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use clap::Parser;
+
+use water_droplet_renderer::cli::Cli;
+use water_droplet_renderer::headless;
+use water_droplet_renderer::{DropletRadius, HasSplashed, PrimaryDroplet, SplashParticle};
+
+const PARTICLES_PER_SPLASH: usize = 10;
+
+// The `--headless` app: physics, splashes and resets, with handles that point at nothing in place of the meshes and
+// materials
+fn app() -> App {
+    let args = ["droplet", "--headless", "--seed", "4", "--particles-per-splash", "10"];
+    let mut app = headless::app(Cli::try_parse_from(args).unwrap());
+    app.update();
+    app
+}
+
+fn primary_droplet(app: &mut App) -> Entity {
+    app.world_mut().query_filtered::<Entity, With<PrimaryDroplet>>().single(app.world())
+}
+
+fn particles_in_play(app: &mut App) -> usize {
+    let mut particles = app.world_mut().query_filtered::<(), (With<SplashParticle>, Without<RigidBodyDisabled>)>();
+    particles.iter(app.world()).count()
+}
+
+// Steps until the droplet lands, or gives up after five seconds
+fn fall_until_splash(app: &mut App, droplet: Entity) {
+    for _ in 0..300 {
+        if app.world().get::<HasSplashed>(droplet).is_some() {
+            return;
+        }
+        app.update();
+    }
+    panic!("the droplet never splashed");
+}
+
+#[test]
+fn a_droplet_dropped_from_five_metres_splashes_and_squashes_flat() {
+    let mut app = app();
+    let droplet = primary_droplet(&mut app);
+    assert_eq!(app.world().get::<Transform>(droplet).unwrap().translation.xz(), Vec2::ZERO);
+    assert_eq!(particles_in_play(&mut app), 0);
+
+    fall_until_splash(&mut app, droplet);
+    // Damping holds it to nearly 8 m/s, the speed a splash throws the configured count at
+    let thrown = particles_in_play(&mut app);
+    assert!((PARTICLES_PER_SPLASH - 2..=PARTICLES_PER_SPLASH + 2).contains(&thrown), "{thrown} particles");
+
+    // A hit that hard flattens the droplet to a tenth of its height at the deepest point of the squash
+    let radius = app.world().get::<DropletRadius>(droplet).unwrap().0;
+    let mut flattest = f32::MAX;
+    for _ in 0..10 {
+        app.update();
+        flattest = flattest.min(app.world().get::<Transform>(droplet).unwrap().scale.y / radius);
+    }
+    assert!((flattest - 0.1).abs() < 0.02, "flattened to {flattest}");
+}
+
+#[test]
+fn r_clears_the_splash_and_puts_the_droplet_back_up() {
+    let mut app = app();
+    let droplet = primary_droplet(&mut app);
+    let start = app.world().get::<Transform>(droplet).unwrap().translation;
+    fall_until_splash(&mut app, droplet);
+    // Past the squash, when the droplet is put away until R
+    for _ in 0..30 {
+        app.update();
+    }
+    assert!(app.world().get::<RigidBodyDisabled>(droplet).is_some());
+    assert!(particles_in_play(&mut app) > 0);
+
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyR);
+    app.update();
+
+    assert_eq!(particles_in_play(&mut app), 0);
+    assert!(app.world().get::<HasSplashed>(droplet).is_none());
+    assert!(app.world().get::<RigidBodyDisabled>(droplet).is_none());
+    // Back where it started, bar the one step it has fallen since
+    let position = app.world().get::<Transform>(droplet).unwrap().translation;
+    assert!(position.distance(Vec3::new(0.0, 5.0, 0.0)) < 0.01, "{position}");
+    assert!(position.distance(start) < 0.01, "{position} vs {start}");
+}